@@ -0,0 +1,250 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    config::{ConfigApiKey, ConfigAuth, C},
+    error::Error,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use http::StatusCode;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+use warp::{Filter, Rejection};
+
+/// Header which carries the API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+lazy_static! {
+    /// All API keys known by this instance, with their usage.
+    /// Built from `[auth]` section of config (and `auth.store` file if given).
+    pub static ref API_KEYS: ApiKeyStore =
+        ApiKeyStore::from_config(C.auth.as_ref()).expect("Failed to load API keys");
+}
+
+/// An authenticated caller. The key itself is never kept here, so it's safe to log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiKey {
+    /// Name of the key owner, shown in logs and tracing spans.
+    pub name: String,
+    /// Max requests per minute. Unlimited if `None`.
+    pub rate_limit: Option<u32>,
+    /// Max requests per day (UTC). Unlimited if `None`.
+    pub daily_quota: Option<u64>,
+    /// Value of `Origin` header should be one of these. Any origin is allowed if empty.
+    pub allowed_origins: Vec<String>,
+}
+
+impl From<&ConfigApiKey> for ApiKey {
+    fn from(config: &ConfigApiKey) -> Self {
+        ApiKey {
+            name: config.name.clone(),
+            rate_limit: config.rate_limit,
+            daily_quota: config.daily_quota,
+            allowed_origins: config.allowed_origins.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// Rate limit is checked over a sliding window of this length, in milliseconds.
+const RATE_LIMIT_WINDOW_MS: i64 = 60_000;
+
+/// Usage counters of an API key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    /// Requests accepted since this instance started.
+    pub total: u64,
+    /// Requests rejected by rate limit or daily quota.
+    pub rejected: u64,
+    /// Requests accepted in current UTC day.
+    pub today: u64,
+    /// Requests accepted in the last 60 seconds.
+    pub last_minute: u32,
+    #[serde(skip)]
+    day: Option<NaiveDate>,
+    /// Timestamps (in milliseconds) of requests accepted in the last 60 seconds, oldest first.
+    #[serde(skip)]
+    recent: VecDeque<i64>,
+}
+
+/// API keys and their usage. Counters live in memory, so they're per instance.
+#[derive(Debug, Default)]
+pub struct ApiKeyStore {
+    /// Reject requests without an API key.
+    required: bool,
+    /// key => owner
+    keys: HashMap<String, ApiKey>,
+    /// key => usage. Keyed by the key itself, since owner names may be shared.
+    usage: Mutex<HashMap<String, Usage>>,
+}
+
+impl ApiKeyStore {
+    pub fn new(required: bool, keys: Vec<ConfigApiKey>) -> Self {
+        ApiKeyStore {
+            required,
+            keys: keys
+                .iter()
+                .map(|config| (config.key.clone(), ApiKey::from(config)))
+                .collect(),
+            usage: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build from `[auth]` config. Keys in `auth.store` (a JSON array of `ConfigApiKey`)
+    /// are appended to the ones in config.
+    pub fn from_config(config: Option<&ConfigAuth>) -> Result<Self, Error> {
+        let config = match config {
+            None => return Ok(ApiKeyStore::default()),
+            Some(config) => config,
+        };
+        let mut keys = config.keys.clone().unwrap_or_default();
+        if let Some(path) = config.store.as_ref().filter(|path| !path.is_empty()) {
+            let stored: Vec<ConfigApiKey> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            keys.extend(stored);
+        }
+        Ok(ApiKeyStore::new(config.required, keys))
+    }
+
+    /// Find the owner of `key`, and make sure it's used from an allowed `origin`.
+    /// Returns `None` for anonymous requests if the key is not required.
+    pub fn authenticate(
+        &self,
+        key: Option<&str>,
+        origin: Option<&str>,
+    ) -> Result<Option<ApiKey>, Error> {
+        let key = match key.map(str::trim).filter(|key| !key.is_empty()) {
+            None if self.required => {
+                return Err(Error::General(
+                    format!("API key required in header `{}`", API_KEY_HEADER),
+                    StatusCode::UNAUTHORIZED,
+                ))
+            }
+            None => return Ok(None),
+            Some(key) => key,
+        };
+        let api_key = self
+            .keys
+            .get(key)
+            .ok_or_else(|| Error::General("Invalid API key".into(), StatusCode::UNAUTHORIZED))?;
+        if !api_key.allowed_origins.is_empty() {
+            let allowed = origin.is_some_and(|origin| {
+                api_key
+                    .allowed_origins
+                    .iter()
+                    .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+            });
+            if !allowed {
+                return Err(Error::General(
+                    "Origin not allowed for this API key".into(),
+                    StatusCode::FORBIDDEN,
+                ));
+            }
+        }
+        Ok(Some(api_key.clone()))
+    }
+
+    /// Count one request of `key` (owned by `api_key`) at `now`,
+    /// if it's within rate limit and daily quota.
+    pub fn consume(&self, key: &str, api_key: &ApiKey, now: DateTime<Utc>) -> Result<(), Error> {
+        let mut all_usage = self.usage.lock().unwrap();
+        let usage = all_usage.entry(key.to_string()).or_default();
+
+        let today = now.date_naive();
+        if usage.day != Some(today) {
+            usage.day = Some(today);
+            usage.today = 0;
+        }
+        let now_ms = now.timestamp_millis();
+        while usage
+            .recent
+            .front()
+            .is_some_and(|accepted| now_ms - accepted >= RATE_LIMIT_WINDOW_MS)
+        {
+            usage.recent.pop_front();
+        }
+        usage.last_minute = usage.recent.len() as u32;
+
+        if api_key
+            .daily_quota
+            .is_some_and(|quota| usage.today >= quota)
+        {
+            usage.rejected += 1;
+            return Err(Error::General(
+                "Daily quota exceeded".into(),
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+        if api_key
+            .rate_limit
+            .is_some_and(|limit| usage.last_minute >= limit)
+        {
+            usage.rejected += 1;
+            return Err(Error::General(
+                "Rate limit exceeded".into(),
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+
+        usage.total += 1;
+        usage.today += 1;
+        usage.recent.push_back(now_ms);
+        usage.last_minute += 1;
+        Ok(())
+    }
+
+    /// `authenticate` then `consume`.
+    pub fn check(
+        &self,
+        key: Option<&str>,
+        origin: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<ApiKey>, Error> {
+        let api_key = self.authenticate(key, origin)?;
+        if let (Some(key), Some(api_key)) = (key, api_key.as_ref()) {
+            self.consume(key.trim(), api_key, now)?;
+        }
+        Ok(api_key)
+    }
+
+    /// Usage counters of `key`.
+    pub fn usage(&self, key: &str) -> Usage {
+        self.usage
+            .lock()
+            .unwrap()
+            .get(key.trim())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Warp filter which checks and counts the API key of a request.
+/// Extracts `None` for anonymous requests (when the key is not required).
+pub fn with_api_key() -> impl Filter<Extract = (Option<ApiKey>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(API_KEY_HEADER)
+        .and(warp::header::optional::<String>("origin"))
+        .and_then(|key: Option<String>, origin: Option<String>| async move {
+            API_KEYS
+                .check(key.as_deref(), origin.as_deref(), Utc::now())
+                .map_err(warp::reject::custom)
+        })
+}
+
+/// Warp filter which only checks the API key, without counting it.
+/// Extracts the key itself, which is required here since there is nothing to show
+/// for anonymous requests.
+pub fn authenticated() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(API_KEY_HEADER)
+        .and(warp::header::optional::<String>("origin"))
+        .and_then(|key: Option<String>, origin: Option<String>| async move {
+            match API_KEYS.authenticate(key.as_deref(), origin.as_deref()) {
+                Ok(Some(_)) => Ok(key.unwrap_or_default().trim().to_string()),
+                Ok(None) => Err(warp::reject::custom(Error::General(
+                    format!("API key required in header `{}`", API_KEY_HEADER),
+                    StatusCode::UNAUTHORIZED,
+                ))),
+                Err(err) => Err(warp::reject::custom(err)),
+            }
+        })
+}
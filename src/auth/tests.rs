@@ -0,0 +1,151 @@
+use super::*;
+use chrono::TimeZone;
+
+fn store(required: bool) -> ApiKeyStore {
+    ApiKeyStore::new(
+        required,
+        vec![
+            ConfigApiKey {
+                key: "limited-key".into(),
+                name: "limited".into(),
+                rate_limit: Some(2),
+                daily_quota: Some(3),
+                allowed_origins: Some(vec!["https://example.com".into()]),
+            },
+            ConfigApiKey {
+                key: "free-key".into(),
+                name: "free".into(),
+                ..Default::default()
+            },
+        ],
+    )
+}
+
+#[test]
+fn test_authenticate() {
+    let optional = store(false);
+    assert_eq!(optional.authenticate(None, None).unwrap(), None);
+    assert_eq!(
+        optional
+            .authenticate(Some("free-key"), None)
+            .unwrap()
+            .unwrap()
+            .name,
+        "free"
+    );
+    assert!(optional.authenticate(Some("wrong-key"), None).is_err());
+
+    let required = store(true);
+    let err = required.authenticate(None, None).unwrap_err();
+    assert_eq!(err.http_status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn test_allowed_origins() {
+    let store = store(false);
+    assert!(store
+        .authenticate(Some("limited-key"), Some("https://EXAMPLE.com"))
+        .is_ok());
+    let err = store
+        .authenticate(Some("limited-key"), Some("https://evil.com"))
+        .unwrap_err();
+    assert_eq!(err.http_status(), StatusCode::FORBIDDEN);
+    assert!(store.authenticate(Some("limited-key"), None).is_err());
+}
+
+#[test]
+fn test_rate_limit_and_daily_quota() {
+    let store = store(false);
+    let origin = Some("https://example.com");
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+    assert!(store.check(Some("limited-key"), origin, now).is_ok());
+    assert!(store.check(Some("limited-key"), origin, now).is_ok());
+    let err = store.check(Some("limited-key"), origin, now).unwrap_err();
+    assert_eq!(err.http_status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Still within the sliding window.
+    let half_minute = now + chrono::Duration::seconds(30);
+    assert!(store
+        .check(Some("limited-key"), origin, half_minute)
+        .is_err());
+
+    // A minute later: rate limit window has passed, but only 1 request is left today.
+    let next_minute = now + chrono::Duration::minutes(1);
+    assert!(store
+        .check(Some("limited-key"), origin, next_minute)
+        .is_ok());
+    assert!(store
+        .check(Some("limited-key"), origin, next_minute)
+        .is_err());
+
+    // Next day: daily quota resets.
+    let next_day = now + chrono::Duration::days(1);
+    assert!(store.check(Some("limited-key"), origin, next_day).is_ok());
+
+    let usage = store.usage("limited-key");
+    assert_eq!(usage.total, 4);
+    assert_eq!(usage.rejected, 3);
+    assert_eq!(usage.today, 1);
+
+    // Unlimited key
+    for _ in 0..100 {
+        assert!(store.check(Some("free-key"), None, now).is_ok());
+    }
+    assert_eq!(store.usage("free-key").total, 100);
+    assert_eq!(store.usage("free-key").last_minute, 100);
+}
+
+#[test]
+fn test_sliding_window() {
+    let store = store(false);
+    let origin = Some("https://example.com");
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 59).unwrap();
+
+    // Fixed windows would reset at 12:01:00.
+    assert!(store.check(Some("limited-key"), origin, now).is_ok());
+    assert!(store.check(Some("limited-key"), origin, now).is_ok());
+    let next_second = now + chrono::Duration::seconds(1);
+    assert!(store
+        .check(Some("limited-key"), origin, next_second)
+        .is_err());
+    assert_eq!(store.usage("limited-key").last_minute, 2);
+}
+
+#[test]
+fn test_usage_by_key() {
+    let store = ApiKeyStore::new(
+        false,
+        vec![
+            ConfigApiKey {
+                key: "first-key".into(),
+                name: "shared".into(),
+                rate_limit: Some(1),
+                ..Default::default()
+            },
+            ConfigApiKey {
+                key: "second-key".into(),
+                name: "shared".into(),
+                rate_limit: Some(1),
+                ..Default::default()
+            },
+        ],
+    );
+    let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    assert!(store.check(Some("first-key"), None, now).is_ok());
+    assert!(store.check(Some("second-key"), None, now).is_ok());
+    assert_eq!(store.usage("first-key").total, 1);
+    assert_eq!(store.usage("second-key").total, 1);
+}
+
+#[test]
+fn test_debug_redacts_key() {
+    let config = ConfigApiKey {
+        key: "secret-key".into(),
+        name: "partner".into(),
+        ..Default::default()
+    };
+    let debug = format!("{:?}", config);
+    assert!(!debug.contains("secret-key"));
+    assert!(debug.contains("partner"));
+}
@@ -0,0 +1,53 @@
+use relation_server::{error::Error, tigergraph::backfill};
+use tracing::info;
+use tracing_subscriber::{
+    filter::{EnvFilter, LevelFilter},
+    layer::SubscriberExt,
+};
+
+const USAGE: &str = "Replace domains stored before names were normalized by their canonical form.
+
+Usage:
+  backfill [--batch-size <n>] [<platform>...]
+
+Every platform of domain names is backfilled if none is given.";
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let log_subscriber = tracing_subscriber::registry()
+        .with(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .from_env_lossy()
+                .add_directive("hyper=info".parse().unwrap()),
+        )
+        .with(tracing_subscriber::fmt::layer());
+
+    tracing::subscriber::set_global_default(log_subscriber)
+        .expect("Setting default subscriber failed");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (batch_size, platforms) = match args.as_slice() {
+        ["--help"] | ["--batch-size"] => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        ["--batch-size", batch_size, platforms @ ..] => (batch_size.parse()?, platforms),
+        platforms => (backfill::DEFAULT_BATCH_SIZE, platforms),
+    };
+    let platforms = match platforms {
+        [] => backfill::domain_platforms(),
+        platforms => platforms
+            .iter()
+            .map(|platform| platform.parse())
+            .collect::<Result<_, _>>()?,
+    };
+
+    let mut total = 0;
+    for platform in platforms {
+        total += backfill::backfill_platform(platform, batch_size).await?;
+    }
+    info!(total, "Backfill: Done");
+    Ok(())
+}
@@ -1,36 +1,50 @@
-use lambda_http::Error as LambdaError;
+use lambda_http::{service_fn, Error as LambdaError};
+use relation_server::{
+    controller::lambda::{entrypoint, invoke},
+    search, telemetry,
+    util::background::set_short_lived,
+};
+use tracing_subscriber::{
+    filter::{EnvFilter, LevelFilter},
+    layer::SubscriberExt,
+};
+
+/// Path to an API Gateway event (JSON). If set, run it once locally instead of
+/// waiting for invocations from AWS Lambda runtime.
+/// e.g. `LAMBDA_LOCAL_EVENT=src/controller/lambda/events/graphql.json cargo run --bin lambda`
+const LOCAL_EVENT_ENV: &str = "LAMBDA_LOCAL_EVENT";
 
 #[tokio::main]
 async fn main() -> Result<(), LambdaError> {
-    //     let _ = env_logger::try_init();
+    let fmt = tracing_subscriber::fmt::layer()
+        // CloudWatch doesn't render ANSI colors.
+        .with_ansi(false)
+        .without_time();
+    let log_subscriber = tracing_subscriber::registry()
+        .with(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .from_env_lossy()
+                .add_directive("hyper=info".parse().unwrap()),
+        )
+        .with(fmt)
+        .with(telemetry::layer()?);
 
-    //     lambda_http::run(service_fn(entrypoint)).await?;
-    Ok(())
-}
+    tracing::subscriber::set_global_default(log_subscriber)
+        .expect("Setting default subscriber failed");
 
-// fn entrypoint (req: LambdaRequest) -> Result<impl IntoResponse, LambdaError> {
-//     todo!()
-// }
+    // Execution environment is frozen between invocations.
+    set_short_lived(true);
 
-// /// Translate between `lambda_http` `Body` and our `Body`.
-// async fn parse<F>(req: LambdaRequest, controller: fn(OurRequest) -> F) -> LambdaResponse<LambdaBody>
-// where
-//     F: Future<Output = Result<OurResponse, Error>>,
-// {
-//     let (parts, old_body) = req.into_parts();
-//     let body: OurBody = crate::controller::LambdaBody(old_body).into();
-//     let new_req: OurRequest = http::Request::from_parts(parts, body);
+    if let Ok(path) = std::env::var(LOCAL_EVENT_ENV) {
+        let event = std::fs::read_to_string(path)?;
+        let response = invoke(&event).await?;
+        println!("{}", response.status());
+        println!("{}", String::from_utf8_lossy(response.body()));
+        return Ok(());
+    }
 
-//     match controller(new_req).await {
-//         Ok(resp) => {
-//             let (parts, our_resp) = resp.into_parts();
-//             let resp = lambda_http::Body::Text(our_resp);
-//             LambdaResponse::from_parts(parts, resp)
-//         }
-//         Err(err) => {
-//             let (parts, our_resp) = error_response(err).into_parts();
-//             let resp = lambda_http::Body::Text(our_resp);
-//             LambdaResponse::from_parts(parts, resp)
-//         }
-//     }
-// }
+    // Runs while invocations are served, the runtime is frozen in between.
+    tokio::spawn(search::warm_up());
+    lambda_http::run(service_fn(entrypoint)).await
+}
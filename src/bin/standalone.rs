@@ -1,33 +1,38 @@
-use async_graphql::{
-    http::{playground_source, GraphQLPlaygroundConfig},
-    EmptyMutation, EmptySubscription, Schema,
-};
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
 use async_graphql_warp::{GraphQLBadRequest, GraphQLResponse};
-use dataloader::non_cached::Loader;
-use http::StatusCode;
+use http::{HeaderMap, StatusCode};
 use relation_server::{
+    auth::{authenticated, with_api_key, ApiKey, API_KEYS},
+    cache,
     config::C,
-    controller::tigergraphql::Query,
-    error::Result,
-    tigergraph::vertex::{ContractLoadFn, IdentityLoadFn, OwnerLoadFn},
-    util::make_http_client,
+    controller::{
+        error_response, healthz, rest,
+        tigergraphql::{build_schema, RelationSchema},
+    },
+    error::{Error, Result},
+    metrics, search, telemetry,
+    tigergraph::expiry,
 };
 use std::{convert::Infallible, net::SocketAddr};
-use tracing::{info, warn};
-use tracing_subscriber::filter::{EnvFilter, LevelFilter};
-use warp::{http::Response as HttpResponse, Filter, Rejection};
+use tracing::{info, info_span, warn, Instrument};
+use tracing_subscriber::{
+    filter::{EnvFilter, LevelFilter},
+    layer::SubscriberExt,
+};
+use warp::{http::Response as HttpResponse, path::FullPath, Filter, Rejection};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let log_subscriber = tracing_subscriber::FmtSubscriber::builder()
-        .with_env_filter(
+    let log_subscriber = tracing_subscriber::registry()
+        .with(
             EnvFilter::builder()
                 .with_default_directive(LevelFilter::INFO.into())
                 .from_env_lossy()
                 .add_directive("hyper=info".parse().unwrap())
                 .add_directive("tokio=info".parse().unwrap()),
         )
-        .finish();
+        .with(tracing_subscriber::fmt::layer())
+        .with(telemetry::layer()?);
 
     tracing::subscriber::set_global_default(log_subscriber)
         .expect("Setting default subscriber failed");
@@ -35,41 +40,104 @@ async fn main() -> Result<()> {
     let middleware_cors = warp::cors()
         .allow_any_origin() // : maybe more strict CORS in production?
         .allow_methods(vec!["GET", "POST"])
-        .allow_headers(vec!["Accept", "Content-Type", "Length"]);
-
-    let client = make_http_client();
-    let contract_loader_fn = ContractLoadFn {
-        client: client.to_owned(),
-    };
-    let identity_loader_fn = IdentityLoadFn {
-        client: client.to_owned(),
-    };
-    let owner_loader_fn = OwnerLoadFn {
-        client: client.to_owned(),
-    };
-    let contract_loader = Loader::new(contract_loader_fn)
-        .with_max_batch_size(500)
-        .with_yield_count(100);
-    let identity_loader = Loader::new(identity_loader_fn)
-        .with_max_batch_size(500)
-        .with_yield_count(100);
-    let owner_loader = Loader::new(owner_loader_fn)
-        .with_max_batch_size(500)
-        .with_yield_count(100);
-
-    let schema = Schema::build(Query::default(), EmptyMutation, EmptySubscription)
-        .data(contract_loader)
-        .data(identity_loader)
-        .data(owner_loader)
-        .finish();
-
-    let graphql_post = async_graphql_warp::graphql(schema)
+        .allow_headers(vec!["Accept", "Content-Type", "Length", "X-Api-Key"]);
+
+    // Fail fast on a broken key store.
+    lazy_static::initialize(&API_KEYS);
+
+    let schema = build_schema();
+
+    let graphql_post = with_api_key()
+        .and(warp::header::headers_cloned())
+        .and(async_graphql_warp::graphql(schema))
         .and_then(
-            |(schema, request): (
-                Schema<Query, EmptyMutation, EmptySubscription>,
-                async_graphql::Request,
-            )| async move {
-                Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
+            |api_key: Option<ApiKey>,
+             headers: HeaderMap,
+             (schema, request): (RelationSchema, async_graphql::Request)| async move {
+                let key_name = api_key
+                    .as_ref()
+                    .map_or("anonymous".to_string(), |key| key.name.clone());
+                let request = match api_key {
+                    Some(api_key) => request.data(api_key),
+                    None => request,
+                };
+                let span = info_span!("graphql", api_key = %key_name);
+                telemetry::set_parent(&span, &headers);
+                let response = schema.execute(request).instrument(span).await;
+                Ok::<_, Infallible>(GraphQLResponse::from(response))
+            },
+        )
+        .with(middleware_cors.clone());
+
+    let usage = warp::path!("api" / "usage")
+        .and(warp::get())
+        .and(authenticated())
+        .map(|key: String| warp::reply::json(&API_KEYS.usage(&key)))
+        .with(middleware_cors.clone());
+
+    let cache_stats = warp::path!("api" / "cache")
+        .and(warp::get())
+        .map(|| warp::reply::json(&cache::stats()))
+        .with(middleware_cors.clone());
+
+    let liveness = warp::path!("api" / "healthz")
+        .and(warp::get())
+        .then(|| async {
+            healthz::controller(http::Request::default())
+                .await
+                .unwrap_or_else(error_response)
+        });
+
+    let readiness = warp::path!("api" / "readyz")
+        .and(warp::get())
+        .then(|| async {
+            healthz::readiness(http::Request::default())
+                .await
+                .unwrap_or_else(error_response)
+        });
+
+    let metrics = warp::path!("metrics").and(warp::get()).then(|| async {
+        match metrics::render().await {
+            Ok(body) => HttpResponse::builder()
+                .header("content-type", metrics::CONTENT_TYPE)
+                .body(body)
+                .map_err(Error::from)
+                .unwrap_or_else(error_response),
+            Err(err) => error_response(err),
+        }
+    });
+
+    let rest_api = warp::path!("api" / "v1" / ..)
+        .and(warp::get())
+        .and(with_api_key())
+        .and(warp::header::headers_cloned())
+        .and(warp::path::full())
+        .and(
+            warp::query::raw()
+                .or(warp::any().map(String::new))
+                .unify(),
+        )
+        .and_then(
+            |api_key: Option<ApiKey>,
+             headers: HeaderMap,
+             path: FullPath,
+             query: String| async move {
+                let key_name = api_key.map_or("anonymous".to_string(), |key| key.name);
+                let uri = match query.is_empty() {
+                    true => path.as_str().to_string(),
+                    false => format!("{}?{}", path.as_str(), query),
+                };
+                let request = http::Request::builder()
+                    .uri(uri)
+                    .body(String::new())
+                    .map_err(|err| warp::reject::custom(Error::from(err)))?;
+                let span = info_span!("rest", api_key = %key_name);
+                telemetry::set_parent(&span, &headers);
+                let response = rest::controller(request)
+                    .instrument(span)
+                    .await
+                    .unwrap_or_else(error_response);
+                Ok::<_, Rejection>(response)
             },
         )
         .with(middleware_cors);
@@ -81,6 +149,12 @@ async fn main() -> Result<()> {
     });
 
     let routes = playground
+        .or(liveness)
+        .or(readiness)
+        .or(usage)
+        .or(cache_stats)
+        .or(metrics)
+        .or(rest_api)
         .or(graphql_post)
         .recover(|err: Rejection| async move {
             if let Some(GraphQLBadRequest(err)) = err.find() {
@@ -107,7 +181,11 @@ async fn main() -> Result<()> {
     let address = SocketAddr::new(C.web.listen.parse().unwrap(), C.web.port);
     info!("Playground: http://{}", address);
 
+    tokio::spawn(expiry::run_sweeper());
+    tokio::spawn(search::warm_up());
+
     warp::serve(routes).run(address).await;
+    telemetry::shutdown();
 
     println!("Shutting down...");
     Ok(())
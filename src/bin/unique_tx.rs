@@ -0,0 +1,50 @@
+use relation_server::{error::Error, tigergraph::unique_tx};
+use std::path::Path;
+use tracing::info;
+use tracing_subscriber::{
+    filter::{EnvFilter, LevelFilter},
+    layer::SubscriberExt,
+};
+
+const USAGE: &str = "Count transactions between addresses into Relation_Unique_TX edges.
+
+Usage:
+  unique_tx <export.csv|export.ndjson>...
+  unique_tx --blocks <from> <to>
+
+Transactions counted already are skipped, so exports may overlap.
+Blocks fetched already are not fetched again, see `[unique_tx] checkpoint`.";
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let log_subscriber = tracing_subscriber::registry()
+        .with(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::INFO.into())
+                .from_env_lossy()
+                .add_directive("hyper=info".parse().unwrap()),
+        )
+        .with(tracing_subscriber::fmt::layer());
+
+    tracing::subscriber::set_global_default(log_subscriber)
+        .expect("Setting default subscriber failed");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let pairs = match args.as_slice() {
+        ["--blocks", from, to] => unique_tx::ingest_block_range(from.parse()?, to.parse()?).await?,
+        [] | ["--help"] | ["--blocks", ..] => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        paths => {
+            let mut pairs = 0;
+            for path in paths {
+                pairs += unique_tx::ingest_export(Path::new(path)).await?;
+            }
+            pairs
+        }
+    };
+    info!(pairs, "UniqueTX: Done");
+    Ok(())
+}
@@ -0,0 +1,294 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    config::C,
+    tigergraph::vertex::{ExpandIdentityRecord, IdentityGraph},
+    upstream::Platform,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tracing::trace;
+
+/// Default of `[cache] capacity`.
+const DEFAULT_CAPACITY: usize = 10_000;
+/// Default of `[cache] ttl`, in seconds.
+const DEFAULT_TTL: u64 = 60;
+
+lazy_static! {
+    /// Results of `IdentityGraph::find_expand_identity`.
+    pub static ref IDENTITY_CACHE: ResponseCache<CacheKey, ExpandIdentityRecord> =
+        ResponseCache::from_config();
+    /// Results of `IdentityGraph::find_graph_by_platform_identity`.
+    pub static ref GRAPH_CACHE: ResponseCache<CacheKey, IdentityGraph> =
+        ResponseCache::from_config();
+}
+
+/// What a cached response was queried by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub platform: Platform,
+    pub identity: String,
+    pub reverse: Option<bool>,
+}
+
+impl CacheKey {
+    pub fn new(platform: &Platform, identity: &str, reverse: Option<bool>) -> Self {
+        CacheKey {
+            platform: *platform,
+            identity: identity.to_string(),
+            reverse,
+        }
+    }
+}
+
+/// Hit / miss counters of a cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped to make room for new ones.
+    pub evictions: u64,
+    /// Entries dropped by `invalidate`.
+    pub invalidations: u64,
+    /// Entries currently cached.
+    pub entries: usize,
+}
+
+struct Entry<V> {
+    value: V,
+    /// Tags this entry can be invalidated by.
+    tags: Vec<String>,
+    inserted_at: Instant,
+    /// Value of `LruTtl::tick` when this entry was written.
+    inserted_tick: u64,
+    /// Value of `LruTtl::tick` when this entry was last read or written.
+    used_at: u64,
+}
+
+/// A size-bound map whose entries expire after `ttl`.
+/// When full, the least recently used entry is evicted.
+/// Every entry is tagged (e.g. by vertex ids and graph id) so that writes
+/// can invalidate all entries they affect.
+pub struct LruTtl<K, V> {
+    capacity: usize,
+    ttl: Duration,
+    tick: u64,
+    entries: HashMap<K, Entry<V>>,
+    /// `used_at` => key, least recently used first.
+    recency: BTreeMap<u64, K>,
+    /// `inserted_tick` => key, oldest (so first to expire) first.
+    insertion: BTreeMap<u64, K>,
+    tagged: HashMap<String, HashSet<K>>,
+    stats: CacheStats,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> LruTtl<K, V> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        LruTtl {
+            capacity,
+            ttl,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            insertion: BTreeMap::new(),
+            tagged: HashMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn get_at(&mut self, key: &K, now: Instant) -> Option<V> {
+        self.tick += 1;
+        let expired = match self.entries.get_mut(key) {
+            None => {
+                self.stats.misses += 1;
+                return None;
+            }
+            Some(entry) if now.duration_since(entry.inserted_at) >= self.ttl => true,
+            Some(entry) => {
+                self.recency.remove(&entry.used_at);
+                self.recency.insert(self.tick, key.clone());
+                entry.used_at = self.tick;
+                false
+            }
+        };
+        if expired {
+            self.remove(key);
+            self.stats.misses += 1;
+            return None;
+        }
+        self.stats.hits += 1;
+        self.entries.get(key).map(|entry| entry.value.clone())
+    }
+
+    pub fn insert_at(&mut self, key: K, value: V, tags: Vec<String>, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        self.remove(&key);
+        if self.entries.len() >= self.capacity {
+            self.evict(now);
+        }
+        for tag in tags.iter() {
+            self.tagged
+                .entry(tag.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        self.recency.insert(self.tick, key.clone());
+        self.insertion.insert(self.tick, key.clone());
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                tags,
+                inserted_at: now,
+                inserted_tick: self.tick,
+                used_at: self.tick,
+            },
+        );
+    }
+
+    /// Drop every entry tagged by any of `tags`. Returns amount of dropped entries.
+    pub fn invalidate(&mut self, tags: &[String]) -> usize {
+        let keys: HashSet<K> = tags
+            .iter()
+            .filter_map(|tag| self.tagged.get(tag))
+            .flatten()
+            .cloned()
+            .collect();
+        for key in keys.iter() {
+            self.remove(key);
+        }
+        self.stats.invalidations += keys.len() as u64;
+        keys.len()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            ..self.stats.clone()
+        }
+    }
+
+    fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.used_at);
+            self.insertion.remove(&entry.inserted_tick);
+            for tag in entry.tags {
+                if let Some(keys) = self.tagged.get_mut(&tag) {
+                    keys.remove(key);
+                    if keys.is_empty() {
+                        self.tagged.remove(&tag);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop all expired entries, or the least recently used one if none expired.
+    /// Entries expire in insertion order, so only the expired ones are visited.
+    fn evict(&mut self, now: Instant) {
+        let mut expired = false;
+        while let Some((_, key)) = self.insertion.first_key_value() {
+            let key = key.clone();
+            let is_expired = self
+                .entries
+                .get(&key)
+                .is_some_and(|entry| now.duration_since(entry.inserted_at) >= self.ttl);
+            if !is_expired {
+                break;
+            }
+            self.remove(&key);
+            expired = true;
+        }
+        if expired {
+            return;
+        }
+        if let Some((_, key)) = self.recency.first_key_value() {
+            let key = key.clone();
+            self.remove(&key);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+/// Thread-safe `LruTtl`, shared by all requests.
+pub struct ResponseCache<K, V>(Mutex<LruTtl<K, V>>);
+
+impl<K: Clone + Eq + Hash, V: Clone> ResponseCache<K, V> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        ResponseCache(Mutex::new(LruTtl::new(capacity, ttl)))
+    }
+
+    /// Capacity and TTL are read from `[cache]` section of config.
+    pub fn from_config() -> Self {
+        let capacity = C
+            .cache
+            .as_ref()
+            .and_then(|c| c.capacity)
+            .unwrap_or(DEFAULT_CAPACITY);
+        let ttl = C.cache.as_ref().and_then(|c| c.ttl).unwrap_or(DEFAULT_TTL);
+        ResponseCache::new(capacity, Duration::from_secs(ttl))
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.0.lock().unwrap().get_at(key, Instant::now())
+    }
+
+    pub fn insert(&self, key: K, value: V, tags: Vec<String>) {
+        self.0
+            .lock()
+            .unwrap()
+            .insert_at(key, value, tags, Instant::now())
+    }
+
+    pub fn invalidate(&self, tags: &[String]) -> usize {
+        self.0.lock().unwrap().invalidate(tags)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.0.lock().unwrap().stats()
+    }
+}
+
+/// Tags of a cached `ExpandIdentityRecord`: its vertex id.
+pub fn identity_tags(record: &ExpandIdentityRecord) -> Vec<String> {
+    vec![record.v_id.clone()]
+}
+
+/// Tags of a cached `IdentityGraph`: its graph id and every vertex id in it.
+pub fn graph_tags(graph: &IdentityGraph) -> Vec<String> {
+    let mut tags: Vec<String> = graph
+        .vertices
+        .iter()
+        .map(|vertex| vertex.v_id.clone())
+        .collect();
+    tags.push(graph.graph_id.clone());
+    tags
+}
+
+/// Drop every cached response containing any of `tags` (vertex ids or graph ids).
+/// Should be called after each write into TigerGraph.
+pub fn invalidate(tags: &[String]) {
+    if tags.is_empty() {
+        return;
+    }
+    let identities = IDENTITY_CACHE.invalidate(tags);
+    let graphs = GRAPH_CACHE.invalidate(tags);
+    trace!(identities, graphs, "Response cache invalidated.");
+}
+
+/// Stats of all response caches, by name.
+pub fn stats() -> HashMap<&'static str, CacheStats> {
+    HashMap::from([
+        ("identity", IDENTITY_CACHE.stats()),
+        ("identity_graph", GRAPH_CACHE.stats()),
+    ])
+}
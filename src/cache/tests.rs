@@ -0,0 +1,101 @@
+use super::*;
+
+fn cache(capacity: usize) -> LruTtl<&'static str, u32> {
+    LruTtl::new(capacity, Duration::from_secs(60))
+}
+
+#[test]
+fn test_hit_and_miss() {
+    let now = Instant::now();
+    let mut cache = cache(10);
+    assert_eq!(cache.get_at(&"a", now), None);
+    cache.insert_at("a", 1, vec![], now);
+    assert_eq!(cache.get_at(&"a", now), Some(1));
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.entries, 1);
+}
+
+#[test]
+fn test_ttl() {
+    let now = Instant::now();
+    let mut cache = cache(10);
+    cache.insert_at("a", 1, vec![], now);
+    assert_eq!(cache.get_at(&"a", now + Duration::from_secs(59)), Some(1));
+    assert_eq!(cache.get_at(&"a", now + Duration::from_secs(60)), None);
+    assert_eq!(cache.stats().entries, 0);
+}
+
+#[test]
+fn test_lru_eviction() {
+    let now = Instant::now();
+    let mut cache = cache(2);
+    cache.insert_at("a", 1, vec![], now);
+    cache.insert_at("b", 2, vec![], now);
+    // "a" is used more recently than "b" now.
+    assert_eq!(cache.get_at(&"a", now), Some(1));
+    cache.insert_at("c", 3, vec![], now);
+
+    assert_eq!(cache.get_at(&"b", now), None);
+    assert_eq!(cache.get_at(&"a", now), Some(1));
+    assert_eq!(cache.get_at(&"c", now), Some(3));
+    assert_eq!(cache.stats().evictions, 1);
+}
+
+#[test]
+fn test_invalidate_by_tag() {
+    let now = Instant::now();
+    let mut cache = cache(10);
+    cache.insert_at(
+        "graph",
+        1,
+        vec!["graph_id".into(), "ethereum,0x0".into()],
+        now,
+    );
+    cache.insert_at("identity", 2, vec!["ethereum,0x0".into()], now);
+    cache.insert_at("other", 3, vec!["twitter,suji_yan".into()], now);
+
+    assert_eq!(cache.invalidate(&["ethereum,0x0".into()]), 2);
+    assert_eq!(cache.get_at(&"graph", now), None);
+    assert_eq!(cache.get_at(&"identity", now), None);
+    assert_eq!(cache.get_at(&"other", now), Some(3));
+    assert_eq!(cache.invalidate(&["graph_id".into()]), 0);
+}
+
+#[test]
+fn test_expired_evicted_before_lru() {
+    let now = Instant::now();
+    let mut cache = cache(2);
+    cache.insert_at("old", 1, vec![], now);
+    let later = now + Duration::from_secs(30);
+    cache.insert_at("new", 2, vec![], later);
+    // "new" is the least recently used one, but "old" has expired.
+    assert_eq!(cache.get_at(&"old", later), Some(1));
+    let expired = now + Duration::from_secs(60);
+    cache.insert_at("newer", 3, vec![], expired);
+
+    assert_eq!(cache.get_at(&"new", expired), Some(2));
+    assert_eq!(cache.get_at(&"newer", expired), Some(3));
+    let stats = cache.stats();
+    assert_eq!(stats.evictions, 0);
+    assert_eq!(stats.entries, 2);
+}
+
+#[test]
+fn test_orderings_follow_entries() {
+    let now = Instant::now();
+    let mut cache: LruTtl<String, u32> = LruTtl::new(100, Duration::from_secs(60));
+    for i in 0..1000u32 {
+        let key = format!("key{}", i % 150);
+        cache.insert_at(key, i, vec![format!("tag{}", i % 7)], now);
+        cache.get_at(&"key0".to_string(), now);
+        if i % 50 == 0 {
+            cache.invalidate(&["tag3".into()]);
+        }
+    }
+    assert_eq!(cache.recency.len(), cache.entries.len());
+    assert_eq!(cache.insertion.len(), cache.entries.len());
+    assert!(cache.entries.len() <= 100);
+}
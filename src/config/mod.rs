@@ -3,6 +3,7 @@ mod env;
 use crate::error::Error;
 use config::Config;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 use self::env::ENV;
 
@@ -26,6 +27,13 @@ lazy_static! {
 pub struct KVConfig {
     pub tdb: ConfigTigerGraph,
     pub web: ConfigWeb,
+    pub auth: Option<ConfigAuth>,
+    pub cache: Option<ConfigCache>,
+    pub search: Option<ConfigSearch>,
+    pub expiry: Option<ConfigExpiry>,
+    pub unique_tx: Option<ConfigUniqueTx>,
+    pub confidence: Option<ConfigConfidence>,
+    pub tracing: Option<ConfigTracing>,
     pub upstream: Upstream,
 }
 
@@ -49,6 +57,58 @@ pub struct Upstream {
     pub solana_rpc: ConfigSolanaRPC,
     pub genome_api: ConfigGenomeAPI,
     pub clusters_api: ConfigClustersAPI,
+    /// Optional: ENS is fetched from TheGraph only if omitted.
+    #[serde(default)]
+    pub ethereum_rpc: ConfigEthereumRPC,
+    /// Optional: Basenames are not fetched if omitted.
+    #[serde(default)]
+    pub base_rpc: ConfigBaseRPC,
+}
+
+impl Upstream {
+    /// Settings left empty, which their upstreams can't work without.
+    /// Only upstreams enabled in `batch_fetch_upstream` are checked
+    /// (i.e. not `sybil_service` and `knn3_service`).
+    pub fn missing_settings(&self) -> Vec<&'static str> {
+        [
+            ("upstream.proof_service.url", &self.proof_service.url),
+            (
+                "upstream.proof_service.api_key",
+                &self.proof_service.api_key,
+            ),
+            (
+                "upstream.aggregation_service.url",
+                &self.aggregation_service.url,
+            ),
+            ("upstream.keybase_service.url", &self.keybase_service.url),
+            (
+                "upstream.keybase_service.stable_url",
+                &self.keybase_service.stable_url,
+            ),
+            ("upstream.rss3_service.url", &self.rss3_service.url),
+            ("upstream.the_graph.ens", &self.the_graph.ens),
+            ("upstream.ens_reverse.url", &self.ens_reverse.url),
+            ("upstream.dotbit_service.url", &self.dotbit_service.url),
+            ("upstream.lens_api.url", &self.lens_api.url),
+            ("upstream.unstoppable_api.url", &self.unstoppable_api.url),
+            (
+                "upstream.unstoppable_api.token",
+                &self.unstoppable_api.token,
+            ),
+            ("upstream.datamgr_api.url", &self.datamgr_api.url),
+            ("upstream.warpcast_api.url", &self.warpcast_api.url),
+            ("upstream.warpcast_api.token", &self.warpcast_api.token),
+            ("upstream.spaceid_api.url", &self.spaceid_api.url),
+            ("upstream.crossbell_api.url", &self.crossbell_api.url),
+            ("upstream.solana_rpc.rpc_url", &self.solana_rpc.rpc_url),
+            ("upstream.genome_api.url", &self.genome_api.url),
+            ("upstream.clusters_api.url", &self.clusters_api.url),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(name, _)| name)
+        .collect()
+    }
 }
 
 #[derive(Clone, Deserialize, Default)]
@@ -64,6 +124,111 @@ pub struct ConfigTigerGraph {
 pub struct ConfigWeb {
     pub listen: String,
     pub port: u16,
+    /// Max nesting level of a GraphQL query. Defaults to 15.
+    pub max_query_depth: Option<usize>,
+    /// Max total cost of a GraphQL query. Defaults to 2000.
+    pub max_query_complexity: Option<usize>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigAuth {
+    /// Reject requests without an API key.
+    pub required: bool,
+    /// Path to a JSON file containing an array of `ConfigApiKey`, loaded at startup.
+    pub store: Option<String>,
+    pub keys: Option<Vec<ConfigApiKey>>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigApiKey {
+    pub key: String,
+    /// Owner of this key, shown in logs.
+    pub name: String,
+    /// Max requests per minute.
+    pub rate_limit: Option<u32>,
+    /// Max requests per day (UTC).
+    pub daily_quota: Option<u64>,
+    /// Allowed values of `Origin` header. Any origin is allowed if omitted.
+    pub allowed_origins: Option<Vec<String>>,
+}
+
+/// The key itself is redacted, so config can be logged safely.
+impl std::fmt::Debug for ConfigApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigApiKey")
+            .field("key", &"<redacted>")
+            .field("name", &self.name)
+            .field("rate_limit", &self.rate_limit)
+            .field("daily_quota", &self.daily_quota)
+            .field("allowed_origins", &self.allowed_origins)
+            .finish()
+    }
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigCache {
+    /// Max entries of each response cache. Defaults to 10000, `0` disables caching.
+    pub capacity: Option<usize>,
+    /// Seconds before a cached response expires. Defaults to 60.
+    pub ttl: Option<u64>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigSearch {
+    /// Max identities in the search index. Defaults to 100000.
+    /// The least recently indexed ones are evicted when full.
+    pub capacity: Option<usize>,
+    /// Max identities loaded from TigerGraph at startup, most recently updated first.
+    /// Defaults to `capacity`, `0` disables loading.
+    pub warm_up: Option<usize>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigExpiry {
+    /// Seconds between sweeps of domain names past their grace period.
+    /// Defaults to 3600, `0` disables sweeping.
+    pub sweep_interval: Option<u64>,
+    /// Max domains swept per TigerGraph query. Defaults to 500.
+    pub batch_size: Option<usize>,
+    /// Grace period (days) by domain name system, e.g. `space_id = 30`.
+    /// Defaults to `DomainNameSystem::default_grace_period`.
+    #[serde(default)]
+    pub grace_periods: HashMap<String, i64>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigUniqueTx {
+    /// JSON file of block ranges already counted into `Relation_Unique_TX`.
+    /// Defaults to `./config/unique_tx_checkpoint.json`.
+    pub checkpoint: Option<String>,
+    /// Max transactions upserted per TigerGraph request. Defaults to 1000.
+    pub batch_size: Option<usize>,
+    /// Blocks fetched from `[upstream.ethereum_rpc]` before each upsert. Defaults to 100.
+    pub blocks_per_batch: Option<u64>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigConfidence {
+    /// Base confidence (0 to 1) of connections by data source, e.g. `opensea = 0.2`.
+    /// Defaults to the proof level stored on the connection, then `DataSource::proof_level`.
+    /// Keys are case-insensitive, like all keys in config.
+    #[serde(default)]
+    pub sources: HashMap<String, f64>,
+    /// Base confidence (0 to 1) of connections by edge type, e.g. `Reverse_Resolve = 0.9`.
+    /// Defaults to 1.
+    #[serde(default)]
+    pub edge_types: HashMap<String, f64>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigTracing {
+    /// OTLP (gRPC) collector to export spans to, e.g. `http://localhost:4317`.
+    pub otlp_endpoint: String,
+    /// `service.name` of exported spans. Defaults to `relation_server`.
+    pub service_name: Option<String>,
+    /// Ratio of new traces to sample, from 0.0 to 1.0. Defaults to 1.0.
+    /// Traces propagated from callers follow the sampling decision of caller.
+    pub sample_ratio: Option<f64>,
 }
 
 #[derive(Clone, Deserialize, Default)]
@@ -165,6 +330,20 @@ pub struct ConfigClustersAPI {
     pub url: String,
 }
 
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigEthereumRPC {
+    /// Any Ethereum mainnet JSON-RPC endpoint.
+    pub rpc_url: String,
+    /// ENS Universal Resolver. Defaults to the one deployed by ENS.
+    pub universal_resolver: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Default)]
+pub struct ConfigBaseRPC {
+    /// Any Base mainnet JSON-RPC endpoint.
+    pub rpc_url: String,
+}
+
 #[derive(Clone, Deserialize)]
 pub enum ConfigCategory {
     File,
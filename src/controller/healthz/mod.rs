@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    config::C,
+    controller::{json_response, Request, Response},
+    error::Error,
+    tigergraph::{installed_queries, ping_id_allocation, Graph},
+    util::make_http_client,
+};
+use futures::future::join_all;
+use http::StatusCode;
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    future::Future,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Max time a single dependency check may take.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// GSQL migrations creating the queries this server calls.
+const MIGRATIONS: [&str; 2] = [
+    include_str!("../../config/tdb/migrations/LoadingJob_SocialGraph.gsql"),
+    include_str!("../../config/tdb/migrations/DBImportExport_IdentityGraph.gsql"),
+];
+
+lazy_static! {
+    /// Queries which should be installed, by graph. Read from `MIGRATIONS`,
+    /// so this list follows the GSQL files.
+    static ref REQUIRED_QUERIES: HashMap<Graph, BTreeSet<String>> =
+        required_queries(&MIGRATIONS);
+}
+
+/// Queries created by `migrations`, by graph.
+/// Definitions look like `CREATE OR REPLACE QUERY name(...) FOR GRAPH graph ... {`.
+fn required_queries(migrations: &[&str]) -> HashMap<Graph, BTreeSet<String>> {
+    let mut required: HashMap<Graph, BTreeSet<String>> = HashMap::new();
+    for line in migrations.iter().flat_map(|migration| migration.lines()) {
+        let definition = match line
+            .trim()
+            .strip_prefix("CREATE OR REPLACE QUERY ")
+            .or_else(|| line.trim().strip_prefix("CREATE QUERY "))
+        {
+            Some(definition) => definition,
+            None => continue,
+        };
+        let name = match definition.split('(').next() {
+            Some(name) if !name.trim().is_empty() => name.trim(),
+            _ => continue,
+        };
+        let graph = definition
+            .split("FOR GRAPH ")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|graph| Graph::from_str(graph).ok());
+        if let Some(graph) = graph {
+            required.entry(graph).or_default().insert(name.to_string());
+        }
+    }
+    required
+}
+
+#[derive(Serialize)]
+struct HealthzResponse {
+    pub hello: String,
+    pub built_at: String,
+    pub revision: String,
+}
+
+/// Liveness: the process is up. Dependencies are not checked.
+pub async fn controller(_req: Request) -> Result<Response, Error> {
+    json_response(
+        StatusCode::OK,
+        &HealthzResponse {
+            hello: "kv server".to_string(),
+            built_at: option_env!("RELATION_SERVER_BUILT_AT")
+                .unwrap_or("UNKNOWN")
+                .to_string(),
+            revision: option_env!("RELATION_SERVER_REVISION")
+                .unwrap_or("UNKNOWN")
+                .to_string(),
+        },
+    )
+}
+
+/// Result of checking one dependency.
+#[derive(Debug, Serialize)]
+pub struct DependencyCheck {
+    pub name: String,
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    /// `true` if all dependencies are ok.
+    pub ready: bool,
+    pub checks: Vec<DependencyCheck>,
+}
+
+impl ReadinessReport {
+    pub fn new(checks: Vec<DependencyCheck>) -> Self {
+        ReadinessReport {
+            ready: checks.iter().all(|check| check.ok),
+            checks,
+        }
+    }
+}
+
+/// Readiness: all dependencies are usable. `503` with the same report if any is not.
+pub async fn readiness(_req: Request) -> Result<Response, Error> {
+    let client = make_http_client();
+    let (id_allocation, graphs) = futures::join!(
+        check("id_allocation", ping_id_allocation()),
+        join_all(REQUIRED_QUERIES.iter().map(|(graph, queries)| {
+            let client = &client;
+            check(format!("tigergraph.{}", graph), async move {
+                let installed = installed_queries(client, *graph).await?;
+                check_queries(&installed, queries)
+            })
+        })),
+    );
+    let mut checks = vec![id_allocation];
+    checks.extend(graphs);
+    checks.push(check("upstream_config", async { check_upstream_config() }).await);
+
+    let report = ReadinessReport::new(checks);
+    let status = match report.ready {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    json_response(status, &report)
+}
+
+/// Run `dependency` with a timeout, measuring its latency.
+async fn check<F>(name: impl Into<String>, dependency: F) -> DependencyCheck
+where
+    F: Future<Output = Result<(), Error>>,
+{
+    let started = Instant::now();
+    let result = tokio::time::timeout(CHECK_TIMEOUT, dependency)
+        .await
+        .unwrap_or_else(|_| {
+            Err(Error::General(
+                format!("Timeout: no response in {:?}.", CHECK_TIMEOUT),
+                StatusCode::REQUEST_TIMEOUT,
+            ))
+        });
+    DependencyCheck {
+        name: name.into(),
+        ok: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        message: result.err().map(|err| err.to_string()),
+    }
+}
+
+fn check_queries<'a>(
+    installed: &HashSet<String>,
+    required: impl IntoIterator<Item = &'a String>,
+) -> Result<(), Error> {
+    let missing: Vec<&str> = required
+        .into_iter()
+        .filter(|query| !installed.contains(*query))
+        .map(String::as_str)
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(Error::General(
+        format!("Queries not installed: {}", missing.join(", ")),
+        StatusCode::SERVICE_UNAVAILABLE,
+    ))
+}
+
+fn check_upstream_config() -> Result<(), Error> {
+    let missing = C.upstream.missing_settings();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(Error::General(
+        format!("Empty settings: {}", missing.join(", ")),
+        StatusCode::SERVICE_UNAVAILABLE,
+    ))
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+fn names(queries: &[&str]) -> BTreeSet<String> {
+    queries.iter().map(|query| query.to_string()).collect()
+}
+
+#[test]
+fn test_check_queries() {
+    let installed: HashSet<String> = ["neighbors", "expand"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    assert!(check_queries(&installed, &names(&["neighbors", "expand"])).is_ok());
+
+    let err = check_queries(&installed, &names(&["neighbors", "nfts", "hold_nft"])).unwrap_err();
+    assert_eq!(err.to_string(), "Queries not installed: hold_nft, nfts");
+}
+
+#[test]
+fn test_required_queries() {
+    let migration = "
+CREATE OR REPLACE QUERY neighbors(VERTEX<Identities> p, INT depth) FOR GRAPH SocialGraph {
+  PRINT p;
+}
+CREATE QUERY owners_by_ids(SET<STRING> ids) FOR GRAPH IdentityGraph SYNTAX V2 {
+}
+CREATE OR REPLACE QUERY unknown(STRING p) FOR GRAPH UnknownGraph {
+}
+// CREATE OR REPLACE QUERY commented(STRING p) FOR GRAPH SocialGraph {
+";
+    let required = required_queries(&[migration]);
+    assert_eq!(required[&Graph::SocialGraph], names(&["neighbors"]));
+    assert_eq!(required[&Graph::IdentityGraph], names(&["owners_by_ids"]));
+    assert_eq!(required.len(), 2);
+}
+
+#[test]
+fn test_required_queries_from_migrations() {
+    let social = &REQUIRED_QUERIES[&Graph::SocialGraph];
+    assert!(social.contains("find_identity_graph"));
+    assert!(social.contains("upsert_hyper_vertex"));
+    assert!(REQUIRED_QUERIES[&Graph::IdentityGraph].contains("delete_vertex_and_edge"));
+}
+
+#[tokio::test]
+async fn test_readiness_report() {
+    let checks = vec![
+        check("ok", async { Ok(()) }).await,
+        check("failed", async { Err(Error::ParamMissing("token".into())) }).await,
+    ];
+    assert!(checks[0].ok);
+    assert_eq!(checks[0].message, None);
+    assert!(!checks[1].ok);
+    assert!(checks[1].message.is_some());
+
+    let report = ReadinessReport::new(checks);
+    assert!(!report.ready);
+    assert!(ReadinessReport::new(vec![]).ready);
+}
@@ -1,14 +1,34 @@
+#[cfg(test)]
+mod tests;
+
+use crate::auth::{API_KEYS, API_KEY_HEADER};
 use crate::controller::{
-    error_response, healthz, Body as OurBody, Request as OurRequest, Response as OurResponse,
+    error_response, healthz, json_parse_body, json_response, rest,
+    tigergraphql::{build_schema, RelationSchema},
+    Body as OurBody, Request as OurRequest, Response as OurResponse,
 };
 use crate::error::Error;
+use crate::telemetry;
+use crate::tigergraph::expiry;
+use crate::util::background::wait_background_tasks;
+use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
 use http::{Method, StatusCode};
 use lambda_http::{
-    Body as LambdaBody, Error as LambdaError, IntoResponse, Request as LambdaRequest,
-    Response as LambdaResponse,
+    Body as LambdaBody, Error as LambdaError, Request as LambdaRequest, Response as LambdaResponse,
 };
-use std::future::Future;
-use tracing::info;
+use std::{future::Future, time::Duration};
+use tracing::{info, info_span, Instrument};
+
+/// Max time to wait for background tasks before returning a response.
+/// Kept short so responses are not held back by refetching: the runtime is frozen once
+/// the response is returned, and unfinished tasks resume in the next invocation.
+/// Background tasks spawned here are safe to freeze anywhere (see `spawn_refresh`).
+const BACKGROUND_TIMEOUT: Duration = Duration::from_millis(300);
+
+lazy_static! {
+    /// Built once per execution environment, reused by all invocations.
+    static ref SCHEMA: RelationSchema = build_schema();
+}
 
 /// Translate between `lambda_http` `Body` and our `Body`.
 async fn parse<F>(req: LambdaRequest, controller: fn(OurRequest) -> F) -> LambdaResponse<LambdaBody>
@@ -33,19 +53,75 @@ where
     }
 }
 
+/// Check and count the API key of a request. Returns the name of key owner.
+fn check_api_key(req: &OurRequest) -> Result<String, Error> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let api_key = API_KEYS.check(header(API_KEY_HEADER), header("origin"), chrono::Utc::now())?;
+    Ok(api_key.map_or("anonymous".to_string(), |key| key.name))
+}
+
+/// `POST /` or `POST /graphql`: execute a GraphQL request.
+async fn graphql(req: OurRequest) -> Result<OurResponse, Error> {
+    let key_name = check_api_key(&req)?;
+    let request: async_graphql::Request = json_parse_body(&req)?;
+    let span = info_span!("graphql", api_key = %key_name);
+    telemetry::set_parent(&span, req.headers());
+    let response = SCHEMA.execute(request).instrument(span).await;
+    json_response(StatusCode::OK, &response)
+}
+
+/// `GET /api/v1/...`: REST API.
+async fn rest_api(req: OurRequest) -> Result<OurResponse, Error> {
+    let key_name = check_api_key(&req)?;
+    let span = info_span!("rest", api_key = %key_name);
+    telemetry::set_parent(&span, req.headers());
+    rest::controller(req).instrument(span).await
+}
+
+/// `GET /`: GraphQL playground.
+async fn playground(_req: OurRequest) -> Result<OurResponse, Error> {
+    http::Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "text/html")
+        .body(playground_source(GraphQLPlaygroundConfig::new("/")))
+        .map_err(|e| e.into())
+}
+
 /// Main entrypoint for `lambda_http`.
-pub async fn entrypoint(req: LambdaRequest) -> Result<impl IntoResponse, LambdaError> {
+pub async fn entrypoint(req: LambdaRequest) -> Result<LambdaResponse<LambdaBody>, LambdaError> {
     info!(
         "{} {}",
         req.method().to_string(),
         req.uri().path().to_string()
     );
 
-    Ok(match (req.method(), req.uri().path()) {
+    let response = match (req.method(), req.uri().path()) {
         (&Method::GET, "/api/healthz") => parse(req, healthz::controller).await,
+        (&Method::GET, "/api/readyz") => parse(req, healthz::readiness).await,
+        (&Method::GET, "/") => parse(req, playground).await,
+        (&Method::POST, "/" | "/graphql") => parse(req, graphql).await,
+        (&Method::GET, path) if path.starts_with(rest::API_PREFIX) => parse(req, rest_api).await,
         _ => LambdaResponse::builder()
             .status(StatusCode::NOT_FOUND)
             .body("Not Found".into())
             .expect("Failed to render response"),
-    })
+    };
+
+    // Frozen between invocations, so domains past their grace period are swept from here.
+    expiry::sweep_if_due().await;
+    // e.g. refetching outdated records. Give them a short head start before being frozen.
+    wait_background_tasks(BACKGROUND_TIMEOUT).await;
+    telemetry::flush().await;
+    Ok(response)
+}
+
+/// Local harness: run `entrypoint` with an API Gateway event (JSON),
+/// as if it is invoked by AWS Lambda.
+pub async fn invoke(event: &str) -> Result<LambdaResponse<LambdaBody>, LambdaError> {
+    let req = lambda_http::request::from_str(event)?;
+    entrypoint(req).await
 }
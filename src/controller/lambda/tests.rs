@@ -0,0 +1,41 @@
+use super::*;
+use crate::error::Result;
+use crate::util::background::spawn_background;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[tokio::test]
+async fn test_healthz() -> Result<()> {
+    let response = invoke(include_str!("events/healthz.json"))
+        .await
+        .expect("Failed to invoke");
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = serde_json::from_slice(response.body())?;
+    assert_eq!(body["hello"], "kv server");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_not_found() {
+    let response = invoke(include_str!("events/not_found.json"))
+        .await
+        .expect("Failed to invoke");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_waits_for_background_tasks() {
+    let done = Arc::new(AtomicBool::new(false));
+    let task_done = done.clone();
+    spawn_background(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        task_done.store(true, Ordering::SeqCst);
+        Ok(())
+    });
+    invoke(include_str!("events/healthz.json"))
+        .await
+        .expect("Failed to invoke");
+    assert!(done.load(Ordering::SeqCst));
+}
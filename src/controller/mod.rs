@@ -1,4 +1,5 @@
 pub mod healthz;
+pub mod rest;
 pub mod tigergraphql;
 
 use crate::upstream::{ContractCategory, Platform};
@@ -97,11 +98,14 @@ pub fn vec_string_to_vec_category(vec_string: Vec<String>) -> Result<Vec<Contrac
 
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
+    /// See `Error::code`.
+    pub code: &'static str,
     pub message: String,
 }
 
 pub fn error_response(err: Error) -> Response {
     let resp = ErrorResponse {
+        code: err.code(),
         message: err.to_string(),
     };
     let body: String = serde_json::to_string(&resp).unwrap();
@@ -0,0 +1,125 @@
+use crate::{
+    controller::{
+        json_response, query_parse,
+        tigergraphql::{
+            check_confidence, find_domain, find_identity, find_identity_graph, find_nft,
+        },
+        Request, Response,
+    },
+    error::Error,
+    upstream::{track_failures, Chain, ContractCategory, DomainNameSystem, Platform},
+};
+use http::{HeaderValue, StatusCode};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// All REST routes are under this prefix.
+pub const API_PREFIX: &str = "/api/v1/";
+
+/// Upstreams failed during the request, as `data_source:code` separated by `,`.
+/// The result may be partial if present.
+pub const UPSTREAM_FAILURES_HEADER: &str = "x-upstream-failures";
+
+/// REST API, sharing resolvers with GraphQL. All routes are `GET`:
+///
+/// - `/api/v1/identity/{platform}/{identity}`
+/// - `/api/v1/graph/{platform}/{identity}?reverse=true&include_expired=true&min_confidence=0.5`
+/// - `/api/v1/domain/{system}/{name}?include_expired=true`
+/// - `/api/v1/nft/{chain}/{category}/{id}?address=0x...`
+pub async fn controller(req: Request) -> Result<Response, Error> {
+    let (result, failures) = track_failures(route(req)).await;
+    let mut resp = result?;
+    if !failures.is_empty() {
+        let failures: Vec<String> = failures
+            .iter()
+            .map(|failure| format!("{}:{}", failure.data_source, failure.code))
+            .collect();
+        if let Ok(value) = HeaderValue::from_str(&failures.join(",")) {
+            let headers = resp.headers_mut();
+            headers.insert(UPSTREAM_FAILURES_HEADER, value);
+            headers.insert(
+                "Access-Control-Expose-Headers",
+                HeaderValue::from_static(UPSTREAM_FAILURES_HEADER),
+            );
+        }
+    }
+    Ok(resp)
+}
+
+async fn route(req: Request) -> Result<Response, Error> {
+    let segments = req
+        .uri()
+        .path()
+        .trim_start_matches(API_PREFIX)
+        .trim_end_matches('/')
+        .split('/')
+        .map(|segment| {
+            urlencoding::decode(segment)
+                .map(|decoded| decoded.into_owned())
+                .map_err(|err| Error::ParamError(format!("Invalid path: {}", err)))
+        })
+        .collect::<Result<Vec<String>, Error>>()?;
+    let query = query_parse(req);
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match segments.as_slice() {
+        ["identity", platform, identity] => {
+            let platform: Platform = platform.to_lowercase().parse()?;
+            found(find_identity(platform, identity.to_string()).await?)
+        }
+        ["graph", platform, identity] => {
+            let platform: Platform = platform.to_lowercase().parse()?;
+            let reverse = bool_param(&query, "reverse")?;
+            let include_expired = bool_param(&query, "include_expired")?.unwrap_or(false);
+            let min_confidence = check_confidence(float_param(&query, "min_confidence")?)?;
+            found(
+                find_identity_graph(
+                    platform,
+                    identity.to_string(),
+                    reverse,
+                    include_expired,
+                    min_confidence,
+                )
+                .await?,
+            )
+        }
+        ["domain", system, name] => {
+            let system: DomainNameSystem = system.to_lowercase().parse()?;
+            let include_expired = bool_param(&query, "include_expired")?.unwrap_or(false);
+            found(find_domain(system, name.to_string(), include_expired).await?)
+        }
+        ["nft", chain, category, id] => {
+            let chain: Chain = chain.to_lowercase().parse()?;
+            let category: ContractCategory = category.to_lowercase().parse()?;
+            let address = query.get("address").cloned();
+            found(find_nft(chain, category, id.to_string(), address).await?)
+        }
+        _ => Err(Error::General("Not Found".into(), StatusCode::NOT_FOUND)),
+    }
+}
+
+/// Optional `true` / `false` query parameter.
+fn bool_param(query: &HashMap<String, String>, name: &str) -> Result<Option<bool>, Error> {
+    query
+        .get(name)
+        .map(|value| value.parse::<bool>())
+        .transpose()
+        .map_err(|_| Error::ParamError(format!("{} should be true or false", name)))
+}
+
+/// Optional number query parameter.
+fn float_param(query: &HashMap<String, String>, name: &str) -> Result<Option<f64>, Error> {
+    query
+        .get(name)
+        .map(|value| value.parse::<f64>())
+        .transpose()
+        .map_err(|_| Error::ParamError(format!("{} should be a number", name)))
+}
+
+/// `200` with the record, or `404` if nothing is found.
+fn found<T: Serialize>(record: Option<T>) -> Result<Response, Error> {
+    match record {
+        Some(record) => json_response(StatusCode::OK, &record),
+        None => Err(Error::General("Not Found".into(), StatusCode::NOT_FOUND)),
+    }
+}
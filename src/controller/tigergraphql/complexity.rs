@@ -0,0 +1,107 @@
+//! Cost model of GraphQL fields, used by `Schema::limit_complexity`.
+//!
+//! Fields resolved from data already loaded by their parent cost 1 (the default),
+//! others are annotated with `#[graphql(complexity = "...")]` using helpers below.
+//! Besides the whole-query budget, `depth` and `limit` arguments
+//! are capped individually (see `check_depth` / `check_limit`).
+
+use crate::{
+    config::C,
+    error::{Error, Result},
+};
+
+/// Field loaded by a dataloader, batched with its siblings.
+pub const COST_LOADER: usize = 2;
+/// Field which runs one TigerGraph query.
+pub const COST_QUERY: usize = 10;
+/// Field which may trigger upstream fetching when nothing is found in TigerGraph.
+pub const COST_FETCH: usize = 100;
+
+/// Max `depth` argument of traversal fields.
+pub const MAX_DEPTH: u16 = 5;
+/// Max `limit` argument of paginated fields.
+pub const MAX_LIMIT: u16 = 1000;
+/// Estimated amount of vertices (or edges) in an identity graph, which is not known
+/// before it is queried.
+pub const GRAPH_SIZE: usize = 20;
+
+/// Default of `[web] max_query_depth`.
+const DEFAULT_MAX_QUERY_DEPTH: usize = 15;
+/// Default of `[web] max_query_complexity`.
+const DEFAULT_MAX_QUERY_COMPLEXITY: usize = 2000;
+
+/// Max nesting level of a query.
+pub fn max_query_depth() -> usize {
+    C.web.max_query_depth.unwrap_or(DEFAULT_MAX_QUERY_DEPTH)
+}
+
+/// Max total cost of a query.
+pub fn max_query_complexity() -> usize {
+    C.web
+        .max_query_complexity
+        .unwrap_or(DEFAULT_MAX_QUERY_COMPLEXITY)
+}
+
+/// Cost of a field which may fetch from upstreams.
+pub fn fetch(child_complexity: usize) -> usize {
+    COST_FETCH + child_complexity
+}
+
+/// Cost of a field which returns a list of records, found by `n` fetch-triggering lookups.
+pub fn fetch_many(n: usize, child_complexity: usize) -> usize {
+    n.max(1) * fetch(child_complexity)
+}
+
+/// Cost of a traversal of `depth` hops. Every hop runs a query
+/// and roughly multiplies the amount of returned records.
+pub fn traversal(depth: Option<u16>, child_complexity: usize) -> usize {
+    let depth = depth.unwrap_or(1).clamp(1, MAX_DEPTH) as usize;
+    depth * (COST_QUERY + child_complexity)
+}
+
+/// Cost of a paginated field returning at most `limit` (or `default`) records.
+/// Children are counted once per record.
+pub fn paginated(limit: Option<u16>, default: u16, child_complexity: usize) -> usize {
+    let limit = limit.unwrap_or(default).min(MAX_LIMIT) as usize;
+    COST_QUERY + limit.max(1) * child_complexity
+}
+
+/// Cost of vertices (or edges) of an identity graph, already loaded by the graph itself.
+/// Children are counted once per vertex.
+pub fn graph_members(child_complexity: usize) -> usize {
+    GRAPH_SIZE * child_complexity
+}
+
+/// Validate `depth` argument of traversal fields. Returns `1` if omitted.
+pub fn check_depth(depth: Option<u16>) -> Result<u16> {
+    match depth.unwrap_or(1) {
+        0 => Err(Error::ParamError("depth should be at least 1".into())),
+        depth if depth > MAX_DEPTH => Err(Error::ParamError(format!(
+            "depth should be at most {}, got {}",
+            MAX_DEPTH, depth
+        ))),
+        depth => Ok(depth),
+    }
+}
+
+/// Validate `minConfidence` argument, which should be from 0 to 1.
+pub fn check_confidence(min_confidence: Option<f64>) -> Result<Option<f64>> {
+    match min_confidence {
+        Some(min) if !(0.0..=1.0).contains(&min) => Err(Error::ParamError(format!(
+            "minConfidence should be from 0 to 1, got {}",
+            min
+        ))),
+        min_confidence => Ok(min_confidence),
+    }
+}
+
+/// Validate `limit` argument of paginated fields. Returns `default` if omitted.
+pub fn check_limit(limit: Option<u16>, default: u16) -> Result<u16> {
+    match limit.unwrap_or(default) {
+        limit if limit > MAX_LIMIT => Err(Error::ParamError(format!(
+            "limit should be at most {}, got {}",
+            MAX_LIMIT, limit
+        ))),
+        limit => Ok(limit),
+    }
+}
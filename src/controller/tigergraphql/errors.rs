@@ -0,0 +1,48 @@
+use crate::{error::Error, upstream::track_failures};
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
+    Response, ServerError,
+};
+use std::sync::Arc;
+
+/// Code of errors raised by async-graphql itself, e.g. parsing, validation or query limits.
+const GRAPHQL_VALIDATION_FAILED: &str = "GRAPHQL_VALIDATION_FAILED";
+
+/// Adds `code` (see `Error::code`) and HTTP `status` into `extensions` of every error,
+/// and lists upstreams failed during the request in `extensions.failedUpstreams` of the response,
+/// since its result may be partial.
+pub struct ErrorCodes;
+
+impl ExtensionFactory for ErrorCodes {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ErrorCodesExtension)
+    }
+}
+
+struct ErrorCodesExtension;
+
+#[async_trait::async_trait]
+impl Extension for ErrorCodesExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let (mut response, failures) = track_failures(next.run(ctx)).await;
+        response.errors.iter_mut().for_each(with_code);
+        if !failures.is_empty() {
+            if let Ok(failures) = async_graphql::to_value(&failures) {
+                response = response.extension("failedUpstreams", failures);
+            }
+        }
+        response
+    }
+}
+
+fn with_code(err: &mut ServerError) {
+    let (code, status) = match err.source::<Error>() {
+        Some(source) => (source.code(), Some(source.http_status().as_u16())),
+        None => (GRAPHQL_VALIDATION_FAILED, None),
+    };
+    let extensions = err.extensions.get_or_insert_with(Default::default);
+    extensions.set("code", code);
+    if let Some(status) = status {
+        extensions.set("status", status);
+    }
+}
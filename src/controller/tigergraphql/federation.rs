@@ -0,0 +1,61 @@
+use crate::{
+    error::{Error, Result},
+    tigergraph::{
+        edge::{Resolve, ResolveEdge},
+        vertex::{ContractLoadFn, ContractRecord, IdentityLoadFn, IdentityRecord},
+    },
+    upstream::{normalize, normalize_address, normalize_name, Chain, DomainNameSystem, Platform},
+    util::make_http_client,
+};
+use async_graphql::{Context, Object};
+use dataloader::non_cached::Loader;
+
+/// `_entities` resolvers for Apollo Federation. They only read records already
+/// in TigerGraph: a gateway resolving references shouldn't trigger upstream fetching.
+#[derive(Default)]
+pub struct EntityQuery;
+
+#[Object]
+impl EntityQuery {
+    /// `IdentityRecord @key(fields: "platform identity")`
+    #[graphql(entity)]
+    async fn find_identity_by_platform_and_identity(
+        &self,
+        ctx: &Context<'_>,
+        platform: Platform,
+        identity: String,
+    ) -> Result<Option<IdentityRecord>> {
+        let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
+            ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
+        Ok(loader
+            .load(format!("{},{}", platform, normalize(platform, &identity)))
+            .await)
+    }
+
+    /// `ContractRecord @key(fields: "chain address")`
+    #[graphql(entity)]
+    async fn find_contract_by_chain_and_address(
+        &self,
+        ctx: &Context<'_>,
+        chain: Chain,
+        address: String,
+    ) -> Result<Option<ContractRecord>> {
+        let loader: &Loader<String, Option<ContractRecord>, ContractLoadFn> =
+            ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
+        Ok(loader
+            .load(format!("{},{}", chain, normalize_address(chain, &address)))
+            .await)
+    }
+
+    /// `ResolveEdge @key(fields: "system name")`
+    #[graphql(entity)]
+    async fn find_resolve_by_system_and_name(
+        &self,
+        system: DomainNameSystem,
+        name: String,
+    ) -> Result<Option<ResolveEdge>> {
+        let client = make_http_client();
+        let name = normalize_name(system, &name);
+        Resolve::find_by_name_system(&client, &name, &system).await
+    }
+}
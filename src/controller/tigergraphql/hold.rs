@@ -1,18 +1,21 @@
 use crate::{
+    controller::tigergraphql::complexity,
     error::{Error, Result},
     tigergraph::{
         delete_vertex_and_edge,
         edge::{Hold, HoldRecord},
         vertex::{ContractLoadFn, ContractRecord, IdentityLoadFn, IdentityRecord},
     },
-    upstream::{fetch_all, Chain, ContractCategory, DataFetcher, DataSource, Target},
-    util::make_http_client,
+    upstream::{
+        fetch_all, normalize, normalize_address, validate, validate_address, Chain,
+        ContractCategory, DataFetcher, DataSource, Platform, Target,
+    },
+    util::{background::spawn_refresh, make_http_client},
 };
 
 use async_graphql::{Context, Object};
 use dataloader::non_cached::Loader;
 use strum::IntoEnumIterator;
-use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 
 #[Object]
@@ -60,6 +63,7 @@ impl HoldRecord {
     }
 
     /// NFT Category. See `availableNftCategories` for all values available.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn category(&self, ctx: &Context<'_>) -> Result<ContractCategory> {
         let loader: &Loader<String, Option<ContractRecord>, ContractLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -71,6 +75,7 @@ impl HoldRecord {
 
     /// On which chain?
     /// See `availableChains` for all chains supported by RelationService.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn chain(&self, ctx: &Context<'_>) -> Result<Chain> {
         let loader: &Loader<String, Option<ContractRecord>, ContractLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -82,6 +87,7 @@ impl HoldRecord {
     }
 
     /// Contract address of this Contract. Usually `0xHEX_STRING`.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn address(&self, ctx: &Context<'_>) -> Result<String> {
         let loader: &Loader<String, Option<ContractRecord>, ContractLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -92,6 +98,7 @@ impl HoldRecord {
     }
 
     /// Token symbol (if any).
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn symbol(&self, ctx: &Context<'_>) -> Result<Option<String>> {
         let loader: &Loader<String, Option<ContractRecord>, ContractLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -102,6 +109,7 @@ impl HoldRecord {
     }
 
     /// Which `Identity` does this NFT belong to.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn owner(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
         let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -118,6 +126,7 @@ impl HoldRecord {
     }
 
     /// Which `IdentityRecord` does this connection starts at.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn from(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
         let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -128,6 +137,7 @@ impl HoldRecord {
     }
 
     /// Which `IdentityRecord` does this connection ends at.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn to(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
         let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -154,6 +164,7 @@ impl HoldQuery {
     }
 
     /// Search an NFT.
+    #[graphql(complexity = "complexity::fetch(child_complexity)")]
     #[tracing::instrument(level = "trace", skip(self, _ctx))]
     async fn nft(
         &self,
@@ -175,30 +186,50 @@ impl HoldQuery {
         )]
         address: Option<String>,
     ) -> Result<Option<HoldRecord>> {
-        let client = make_http_client();
-        let contract_address = address
-            .or(category.default_contract_address())
-            .ok_or(Error::GraphQLError("Contract address is required.".into()))?;
-        let target = Target::NFT(chain, category, contract_address.clone(), id.clone());
-        match Hold::find_by_id_chain_address(&client, &id, &chain, &contract_address).await? {
-            Some(hold) => {
-                let v_id = hold.from_id.clone();
-                if hold.is_outdated() {
-                    tokio::spawn(async move {
-                        // Delete and Refetch in the background
-                        sleep(Duration::from_secs(10)).await;
-                        delete_vertex_and_edge(&client, v_id).await?;
-                        fetch_all(vec![target], Some(3)).await?;
-                        Ok::<_, Error>(())
-                    });
-                }
-                Ok(Some(hold))
-            }
+        find_nft(chain, category, id, address).await
+    }
+}
 
-            None => {
-                let _ = fetch_all(vec![target], Some(3)).await;
-                Hold::find_by_id_chain_address(&client, &id, &chain, &contract_address).await
+/// Find an NFT by its chain, category, ID and contract address
+/// (defaults to the category's well-known contract). Fetch from upstreams if not found.
+/// Outdated records are refreshed in the background.
+pub(crate) async fn find_nft(
+    chain: Chain,
+    category: ContractCategory,
+    id: String,
+    address: Option<String>,
+) -> Result<Option<HoldRecord>> {
+    let client = make_http_client();
+    let contract_address = address
+        .or(category.default_contract_address())
+        .ok_or(Error::GraphQLError("Contract address is required.".into()))?;
+    let contract_address = normalize_address(chain, &contract_address);
+    validate_address(chain, &contract_address)?;
+    let id = match category {
+        ContractCategory::ENS => {
+            let name = normalize(Platform::ENS, &id);
+            validate(Platform::ENS, &name)?;
+            name
+        }
+        _ => id,
+    };
+    let target = Target::NFT(chain, category, contract_address.clone(), id.clone());
+    match Hold::find_by_id_chain_address(&client, &id, &chain, &contract_address).await? {
+        Some(hold) => {
+            let v_id = hold.from_id.clone();
+            if hold.is_outdated() {
+                // Delete and Refetch in the background
+                spawn_refresh(
+                    async move { delete_vertex_and_edge(&client, v_id).await },
+                    fetch_all(vec![target], Some(3)),
+                );
             }
+            Ok(Some(hold))
+        }
+
+        None => {
+            let _ = fetch_all(vec![target], Some(3)).await;
+            Hold::find_by_id_chain_address(&client, &id, &chain, &contract_address).await
         }
     }
 }
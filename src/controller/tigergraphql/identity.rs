@@ -1,20 +1,27 @@
 use crate::{
+    controller::tigergraphql::{
+        complexity::{self, check_confidence, check_depth, check_limit},
+        identity_graph::{find_identity_graph, find_identity_graphs},
+    },
     error::{Error, Result},
+    search::{index_entries, SearchEntry},
     tigergraph::{
-        edge::{resolve::ResolveReverse, EdgeUnion, HoldRecord},
+        edge::{resolve::ResolveReverse, EdgeUnion, HoldRecord, ProofRecord},
         upsert::delete_graph_inner_connection,
         vertex::{
             ExpandIdentityRecord, IdentityGraph, IdentityRecord, IdentityWithSource, OwnerLoadFn,
         },
     },
-    upstream::{fetch_all, Chain, ContractCategory, DataSource, Platform, Target},
-    util::make_http_client,
+    upstream::{
+        detect, fetch_all, normalize, resolve_input, validate, ContractCategory, DataSource,
+        Detected, ExpiryStatus, Platform, Target,
+    },
+    util::{background::spawn_refresh, make_http_client},
 };
 
 use async_graphql::{Context, Object};
 use dataloader::non_cached::Loader;
 use strum::IntoEnumIterator;
-use tokio::time::{sleep, Duration};
 use tracing::{event, Level};
 use uuid::Uuid;
 
@@ -47,6 +54,12 @@ impl IdentityWithSource {
         self.reverse.clone()
     }
 
+    /// Confidence of this identity relative to the queried one, from 0 to 1.
+    /// Product of base confidences of connections along the most confident path between them.
+    async fn confidence(&self) -> Option<f64> {
+        self.confidence
+    }
+
     async fn identity(&self) -> IdentityRecord {
         self.identity.clone()
     }
@@ -141,6 +154,7 @@ impl IdentityRecord {
     }
 
     /// Neighbor identity from current. Flattened.
+    #[graphql(complexity = "complexity::traversal(depth, child_complexity)")]
     async fn neighbor(
         &self,
         _ctx: &Context<'_>,
@@ -156,23 +170,30 @@ impl IdentityRecord {
         When `reverse=false`, Only `non-primary domain` will be returned, which is the inverse set of reverse=true."
         )]
         reverse: Option<bool>,
+        #[graphql(desc = "Min confidence relative to this identity, from 0 to 1.")]
+        min_confidence: Option<f64>,
     ) -> Result<Vec<IdentityWithSource>> {
+        let depth = check_depth(depth)?;
+        let min_confidence = check_confidence(min_confidence)?;
         let client = make_http_client();
-        self.neighbors(&client, depth.unwrap_or(1), reverse).await
+        self.neighbors_with_confidence(&client, depth, reverse, min_confidence)
+            .await
     }
 
     /// Neighbor identity from current. The entire topology can be restored by return records.
+    #[graphql(complexity = "complexity::traversal(depth, child_complexity)")]
     async fn neighbor_with_traversal(
         &self,
         _ctx: &Context<'_>,
         #[graphql(desc = "Depth of traversal. 1 if omitted")] depth: Option<u16>,
     ) -> Result<Vec<EdgeUnion>> {
+        let depth = check_depth(depth)?;
         let client = make_http_client();
-        self.neighbors_with_traversal(&client, depth.unwrap_or(1))
-            .await
+        self.neighbors_with_traversal(&client, depth).await
     }
 
     /// Identity graph from current.
+    #[graphql(complexity = "complexity::fetch(child_complexity)")]
     async fn identity_graph(
         &self,
         _ctx: &Context<'_>,
@@ -183,62 +204,47 @@ impl IdentityRecord {
     When `reverse=false`, Only `non-primary domain` will be returned, which is the inverse set of reverse=true."
         )]
         reverse: Option<bool>,
+        #[graphql(desc = "Also return domain names past their grace period. Defaults to false.")]
+        include_expired: Option<bool>,
+        #[graphql(desc = "Min confidence relative to this identity, from 0 to 1.")]
+        min_confidence: Option<f64>,
     ) -> Result<Option<IdentityGraph>> {
-        let client = make_http_client();
-        match IdentityGraph::find_graph_by_platform_identity(
-            &client,
-            &self.platform,
-            &self.identity,
+        find_identity_graph(
+            self.platform,
+            self.identity.clone(),
             reverse,
+            include_expired.unwrap_or(false),
+            check_confidence(min_confidence)?,
         )
-        .await?
-        {
-            None => {
-                let target = match self.platform {
-                    Platform::ENS => Target::NFT(
-                        Chain::Ethereum,
-                        ContractCategory::ENS,
-                        ContractCategory::ENS.default_contract_address().unwrap(),
-                        self.identity.clone(),
-                    ),
-                    _ => Target::Identity(self.platform.clone(), self.identity.clone()),
-                };
-                let fetch_result = fetch_all(vec![target], Some(3)).await;
-                if fetch_result.is_err() {
-                    event!(
-                        Level::WARN,
-                        ?self.platform,
-                        self.identity,
-                        err = fetch_result.unwrap_err().to_string(),
-                        "Failed to fetch_all"
-                    );
-                }
-                Ok(IdentityGraph::find_graph_by_platform_identity(
-                    &client,
-                    &self.platform,
-                    &self.identity,
-                    reverse,
-                )
-                .await?)
-            }
-            Some(identity_graph) => Ok(Some(identity_graph)),
-        }
+        .await
     }
 
     /// Return primary domain names where they would typically only show addresses.
+    #[graphql(complexity = "complexity::COST_QUERY + child_complexity")]
     async fn reverse_records(&self, _ctx: &Context<'_>) -> Result<Vec<ResolveReverse>> {
         let client = make_http_client();
         self.resolve_reverse_domains(&client).await
     }
 
+    /// Links claimed by this identity or claiming it, e.g. from text records of an ENS name.
+    /// Unlike proofs, they are `selfAsserted` and their identities don't join the identity graph.
+    #[graphql(complexity = "complexity::COST_QUERY + child_complexity")]
+    async fn claims(&self, _ctx: &Context<'_>) -> Result<Vec<ProofRecord>> {
+        let client = make_http_client();
+        self.find_claims(&client).await
+    }
+
     /// The expiry date for the domain, from either the registration, or the wrapped domain if PCC is burned
     async fn expired_at(&self) -> Option<i64> {
-        if !vec![Platform::Dotbit, Platform::ENS, Platform::Genome].contains(&self.platform) {
-            return None;
-        }
         self.expired_at.map(|dt| dt.and_utc().timestamp())
     }
 
+    /// Whether the domain is active, in its grace period, or expired (released).
+    /// `null` if not a domain, or it never expires.
+    async fn expiry_status(&self) -> Option<ExpiryStatus> {
+        self.attributes.expiry_status()
+    }
+
     /// reverse flag can be used as a filtering for Identity which type is domain system.
     /// If `reverse=None` if omitted, there is no need to filter anything.
     /// When `reverse=true`, just return `primary domain` related identities.
@@ -252,6 +258,7 @@ impl IdentityRecord {
             Platform::Crossbell,
             Platform::Ethereum,
             Platform::ENS,
+            Platform::Basenames,
             Platform::Solana,
             Platform::SNS,
             Platform::Genome,
@@ -264,6 +271,7 @@ impl IdentityRecord {
     }
 
     /// there's only `platform: lens, dotbit, unstoppabledomains, farcaster, space_id` identity `ownedBy` is not null
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn owned_by(&self, ctx: &Context<'_>) -> Result<Option<IdentityRecord>> {
         if !vec![
             Platform::Lens,
@@ -273,6 +281,7 @@ impl IdentityRecord {
             Platform::SpaceId,
             Platform::Crossbell,
             Platform::ENS,
+            Platform::Basenames,
             Platform::SNS,
             Platform::Genome,
         ]
@@ -295,6 +304,7 @@ impl IdentityRecord {
     /// NFTs owned by this identity.
     /// For now, there's only `platform: ethereum` identity has NFTs.
     /// If `category` is provided, only NFTs of that category will be returned.
+    #[graphql(complexity = "complexity::paginated(limit, 100, child_complexity)")]
     async fn nft(
         &self,
         _ctx: &Context<'_>,
@@ -324,14 +334,90 @@ impl IdentityRecord {
                     .collect::<Result<Vec<ContractCategory>>>()
             })
             .transpose()?;
-        self.nfts(&client, category, limit.unwrap_or(100), offset.unwrap_or(0))
+        let limit = check_limit(limit, 100)?;
+        self.nfts(&client, category, limit, offset.unwrap_or(0))
             .await
     }
+
+    /// Identities following this identity.
+    /// For now, only `platform: farcaster` and `platform: lens` identities have followers.
+    #[graphql(complexity = "complexity::paginated(limit, 100, child_complexity)")]
+    async fn followers(
+        &self,
+        _ctx: &Context<'_>,
+        #[graphql(
+            desc = "If `true`, only followers which are followed back by this identity will be returned. It defaults to false."
+        )]
+        mutual: Option<bool>,
+        #[graphql(
+            desc = "`limit` used to control the maximum number of records returned by query. It defaults to 100"
+        )]
+        limit: Option<u16>,
+        #[graphql(
+            desc = "`offset` determines the starting position from which the records are retrieved in query. It defaults to 0."
+        )]
+        offset: Option<u16>,
+    ) -> Result<Vec<IdentityRecord>> {
+        let client = make_http_client();
+        let limit = check_limit(limit, 100)?;
+        self.follows(
+            &client,
+            true,
+            mutual.unwrap_or(false),
+            limit,
+            offset.unwrap_or(0),
+        )
+        .await
+    }
+
+    /// Identities followed by this identity.
+    /// For now, only `platform: farcaster` and `platform: lens` identities follow others.
+    #[graphql(complexity = "complexity::paginated(limit, 100, child_complexity)")]
+    async fn following(
+        &self,
+        _ctx: &Context<'_>,
+        #[graphql(
+            desc = "If `true`, only identities which follow this identity back will be returned. It defaults to false."
+        )]
+        mutual: Option<bool>,
+        #[graphql(
+            desc = "`limit` used to control the maximum number of records returned by query. It defaults to 100"
+        )]
+        limit: Option<u16>,
+        #[graphql(
+            desc = "`offset` determines the starting position from which the records are retrieved in query. It defaults to 0."
+        )]
+        offset: Option<u16>,
+    ) -> Result<Vec<IdentityRecord>> {
+        let client = make_http_client();
+        let limit = check_limit(limit, 100)?;
+        self.follows(
+            &client,
+            false,
+            mutual.unwrap_or(false),
+            limit,
+            offset.unwrap_or(0),
+        )
+        .await
+    }
 }
 
 #[derive(Default)]
 pub struct IdentityQuery;
 
+#[Object]
+impl Detected {
+    /// Platform inferred from input.
+    async fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Identity on inferred platform, normalized.
+    async fn identity(&self) -> String {
+        self.identity.clone()
+    }
+}
+
 #[Object]
 impl IdentityQuery {
     /// Returns a list of all platforms supported by RelationService.
@@ -345,6 +431,7 @@ impl IdentityQuery {
     }
 
     /// Query an `identity` by given `platform` and `identity`.
+    #[graphql(complexity = "complexity::fetch(child_complexity)")]
     #[tracing::instrument(level = "trace", skip(self, _ctx))]
     async fn identity(
         &self,
@@ -352,54 +439,105 @@ impl IdentityQuery {
         #[graphql(desc = "Platform to query")] platform: String,
         #[graphql(desc = "Identity on target Platform")] identity: String,
     ) -> Result<Option<ExpandIdentityRecord>> {
-        let client = make_http_client();
-
         let platform: Platform = platform.to_lowercase().parse()?;
+        find_identity(platform, identity).await
+    }
+
+    /// Infer candidate `(platform, identity)` pairs from free-form input,
+    /// e.g. `0xabc…`, `vitalik.eth`, `name.bit`, `@handle`, `bc1…`, `twitter:handle`.
+    /// Candidates are ordered from the most to the least likely one.
+    async fn detect_platforms(
+        &self,
+        #[graphql(desc = "Any identity input, platform is not needed.")] input: String,
+    ) -> Result<Vec<Detected>> {
+        Ok(detect(&input))
+    }
+
+    /// Query identity graphs by free-form input, without knowing its platform.
+    /// Every candidate from `detectPlatforms` (plus `fid:123` for Farcaster) is tried,
+    /// graphs found by more than one candidate are returned once.
+    // A bare handle yields up to 3 candidates, fetched together if not saved yet.
+    #[graphql(complexity = "complexity::fetch_many(3, child_complexity)")]
+    #[tracing::instrument(level = "trace", skip(self, _ctx))]
+    async fn identity_graphs_by_input(
+        &self,
+        _ctx: &Context<'_>,
+        #[graphql(desc = "Any identity input, platform is not needed.")] input: String,
+        #[graphql(
+            desc = "`reverse` is the same as in `identityGraph`. Only primary domains are returned if `true`."
+        )]
+        reverse: Option<bool>,
+        #[graphql(desc = "Also return domain names past their grace period. Defaults to false.")]
+        include_expired: Option<bool>,
+        #[graphql(desc = "Min confidence relative to the matched candidate, from 0 to 1.")]
+        min_confidence: Option<f64>,
+    ) -> Result<Vec<IdentityGraph>> {
+        if input.trim().is_empty() {
+            return Err(Error::ParamMissing("input".to_string()));
+        }
+        let min_confidence = check_confidence(min_confidence)?;
+        let candidates = resolve_input(&input)
+            .await?
+            .into_iter()
+            .map(|Detected { platform, identity }| (platform, identity))
+            .collect();
+        Ok(find_identity_graphs(
+            candidates,
+            reverse,
+            include_expired.unwrap_or(false),
+            min_confidence,
+        )
+        .await)
+    }
+}
 
-        let target = match platform {
-            Platform::ENS => Target::NFT(
-                Chain::Ethereum,
-                ContractCategory::ENS,
-                ContractCategory::ENS.default_contract_address().unwrap(),
-                identity.clone(),
-            ),
-            _ => Target::Identity(platform, identity.clone()),
-        };
-        // FIXME: Still kinda dirty. Should be in an background queue/worker-like shape.
-
-        match IdentityGraph::find_expand_identity(&client, &platform, &identity).await? {
-            None => {
-                let fetch_result = fetch_all(vec![target], Some(3)).await;
-                if fetch_result.is_err() {
-                    event!(
-                        Level::WARN,
-                        ?platform,
-                        identity,
-                        err = fetch_result.unwrap_err().to_string(),
-                        "Failed to fetch"
-                    );
-                }
-                Ok(IdentityGraph::find_expand_identity(&client, &platform, &identity).await?)
+/// Find an identity. Fetch from upstreams if not found.
+/// Outdated records are refreshed in the background.
+pub(crate) async fn find_identity(
+    platform: Platform,
+    identity: String,
+) -> Result<Option<ExpandIdentityRecord>> {
+    let identity = normalize(platform, &identity);
+    validate(platform, &identity)?;
+    let client = make_http_client();
+    let target = Target::from_platform_identity(platform, &identity);
+    // FIXME: Still kinda dirty. Should be in an background queue/worker-like shape.
+
+    match IdentityGraph::find_expand_identity(&client, &platform, &identity).await? {
+        None => {
+            let fetch_result = fetch_all(vec![target], Some(3)).await;
+            if fetch_result.is_err() {
+                event!(
+                    Level::WARN,
+                    ?platform,
+                    identity,
+                    err = fetch_result.unwrap_err().to_string(),
+                    "Failed to fetch"
+                );
             }
-            Some(found) => {
-                if found.is_outdated() {
-                    event!(
-                        Level::DEBUG,
-                        ?platform,
-                        identity,
-                        "Outdated. Delete and Refetching."
-                    );
-                    let v_id = found.v_id.clone();
-                    tokio::spawn(async move {
-                        // Delete and Refetch in the background
-                        sleep(Duration::from_secs(10)).await;
-                        delete_graph_inner_connection(&client, v_id).await?;
-                        fetch_all(vec![target], Some(3)).await?;
-                        Ok::<_, Error>(())
-                    });
-                }
-                Ok(Some(found))
+            let found = IdentityGraph::find_expand_identity(&client, &platform, &identity).await?;
+            if let Some(found) = found.as_ref() {
+                index_entries(vec![SearchEntry::from(&found.record.attributes)]).await;
+            }
+            Ok(found)
+        }
+        Some(found) => {
+            index_entries(vec![SearchEntry::from(&found.record.attributes)]).await;
+            if found.is_outdated() {
+                event!(
+                    Level::DEBUG,
+                    ?platform,
+                    identity,
+                    "Outdated. Delete and Refetching."
+                );
+                let v_id = found.v_id.clone();
+                // Delete and Refetch in the background
+                spawn_refresh(
+                    async move { delete_graph_inner_connection(&client, v_id).await },
+                    fetch_all(vec![target], Some(3)),
+                );
             }
+            Ok(Some(found))
         }
     }
 }
@@ -1,5 +1,9 @@
 use crate::{
-    controller::tigergraphql::identity::DataStatus,
+    config::C,
+    controller::tigergraphql::{
+        complexity::{self, check_confidence, check_depth, check_limit},
+        identity::DataStatus,
+    },
     error::{Error, Result},
     tigergraph::{
         edge::{EdgeUnion, HoldRecord},
@@ -8,11 +12,17 @@ use crate::{
             IdentityWithSource, OwnerLoadFn,
         },
     },
-    upstream::{fetch_all, Chain, ContractCategory, DataSource, Platform, Target},
+    upstream::{
+        fetch_all, normalize, validate, Chain, ContractCategory, DataSource, ExpiryStatus,
+        Platform, Target,
+    },
     util::make_http_client,
 };
 use async_graphql::{Context, Object};
 use dataloader::non_cached::Loader;
+use futures::future::join_all;
+use hyper::{client::HttpConnector, Client};
+use std::collections::HashSet;
 use tracing::{event, Level};
 use uuid::Uuid;
 
@@ -27,11 +37,13 @@ impl IdentityGraph {
     }
 
     /// The set of vertices forming a identity graph.
+    #[graphql(complexity = "complexity::graph_members(child_complexity)")]
     async fn vertices(&self) -> &Vec<ExpandIdentityRecord> {
         &self.vertices
     }
 
     /// The set of edges forming a identity graph.
+    #[graphql(complexity = "complexity::graph_members(child_complexity)")]
     async fn edges(&self) -> &Vec<IdentityConnection> {
         &self.edges
     }
@@ -140,12 +152,15 @@ impl ExpandIdentityRecord {
 
     /// The expiry date for the domain, from either the registration, or the wrapped domain if PCC is burned
     async fn expired_at(&self) -> Option<i64> {
-        if !vec![Platform::Dotbit, Platform::ENS, Platform::Genome].contains(&self.platform) {
-            return None;
-        }
         self.expired_at.map(|dt| dt.and_utc().timestamp())
     }
 
+    /// Whether the domain is active, in its grace period, or expired (released).
+    /// `null` if not a domain, or it never expires.
+    async fn expiry_status(&self) -> Option<ExpiryStatus> {
+        self.attributes.expiry_status()
+    }
+
     /// reverse flag can be used as a filtering for Identity which type is domain system.
     /// If `reverse=None` if omitted, there is no need to filter anything.
     /// When `reverse=true`, just return `primary domain` related identities.
@@ -159,6 +174,7 @@ impl ExpandIdentityRecord {
             Platform::Crossbell,
             Platform::Ethereum,
             Platform::ENS,
+            Platform::Basenames,
             Platform::Solana,
             Platform::SNS,
             Platform::Genome,
@@ -171,6 +187,7 @@ impl ExpandIdentityRecord {
     }
 
     /// there's only `platform: lens, dotbit, unstoppabledomains, farcaster, space_id` identity `ownedBy` is not null
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn owned_by(&self, ctx: &Context<'_>) -> Result<Option<IdentityRecord>> {
         if !vec![
             Platform::Lens,
@@ -180,6 +197,7 @@ impl ExpandIdentityRecord {
             Platform::SpaceId,
             Platform::Crossbell,
             Platform::ENS,
+            Platform::Basenames,
             Platform::SNS,
             Platform::Genome,
         ]
@@ -203,6 +221,7 @@ impl ExpandIdentityRecord {
     /// NFTs owned by this identity.
     /// For now, there's only `platform: ethereum` identity has NFTs.
     /// If `category` is provided, only NFTs of that category will be returned.
+    #[graphql(complexity = "complexity::paginated(limit, 100, child_complexity)")]
     async fn nft(
         &self,
         _ctx: &Context<'_>,
@@ -232,13 +251,15 @@ impl ExpandIdentityRecord {
                     .collect::<Result<Vec<ContractCategory>>>()
             })
             .transpose()?;
-        self.nfts(
-            &client,
-            parsed_category,
-            limit.unwrap_or(100),
-            offset.unwrap_or(0),
-        )
-        .await
+        let limit = check_limit(limit, 100)?;
+        self.nfts(&client, parsed_category, limit, offset.unwrap_or(0))
+            .await
+    }
+
+    /// Confidence of this identity relative to the queried one, from 0 to 1.
+    /// Product of base confidences of connections along the most confident path between them.
+    async fn confidence(&self) -> Option<f64> {
+        self.confidence
     }
 
     async fn owner_address(&self) -> Option<Vec<Address>> {
@@ -250,6 +271,7 @@ impl ExpandIdentityRecord {
     }
 
     /// Neighbor identity from current. Flattened.
+    #[graphql(complexity = "complexity::traversal(depth, child_complexity)")]
     async fn neighbor(
         &self,
         _ctx: &Context<'_>,
@@ -265,23 +287,30 @@ impl ExpandIdentityRecord {
         When `reverse=false`, Only `non-primary domain` will be returned, which is the inverse set of reverse=true."
         )]
         reverse: Option<bool>,
+        #[graphql(desc = "Min confidence relative to this identity, from 0 to 1.")]
+        min_confidence: Option<f64>,
     ) -> Result<Vec<IdentityWithSource>> {
+        let depth = check_depth(depth)?;
+        let min_confidence = check_confidence(min_confidence)?;
         let client = make_http_client();
-        self.neighbors(&client, depth.unwrap_or(1), reverse).await
+        self.neighbors_with_confidence(&client, depth, reverse, min_confidence)
+            .await
     }
 
     /// Neighbor identity from current. The entire topology can be restored by return records.
+    #[graphql(complexity = "complexity::traversal(depth, child_complexity)")]
     async fn neighbor_with_traversal(
         &self,
         _ctx: &Context<'_>,
         #[graphql(desc = "Depth of traversal. 1 if omitted")] depth: Option<u16>,
     ) -> Result<Vec<EdgeUnion>> {
+        let depth = check_depth(depth)?;
         let client = make_http_client();
-        self.neighbors_with_traversal(&client, depth.unwrap_or(1))
-            .await
+        self.neighbors_with_traversal(&client, depth).await
     }
 
     /// Identity graph from current.
+    #[graphql(complexity = "complexity::fetch(child_complexity)")]
     async fn identity_graph(
         &self,
         _ctx: &Context<'_>,
@@ -292,46 +321,19 @@ impl ExpandIdentityRecord {
     When `reverse=false`, Only `non-primary domain` will be returned, which is the inverse set of reverse=true."
         )]
         reverse: Option<bool>,
+        #[graphql(desc = "Also return domain names past their grace period. Defaults to false.")]
+        include_expired: Option<bool>,
+        #[graphql(desc = "Min confidence relative to this identity, from 0 to 1.")]
+        min_confidence: Option<f64>,
     ) -> Result<Option<IdentityGraph>> {
-        let client = make_http_client();
-        match IdentityGraph::find_graph_by_platform_identity(
-            &client,
-            &self.platform,
-            &self.identity,
+        find_identity_graph(
+            self.platform,
+            self.identity.clone(),
             reverse,
+            include_expired.unwrap_or(false),
+            check_confidence(min_confidence)?,
         )
-        .await?
-        {
-            None => {
-                let target = match self.platform {
-                    Platform::ENS => Target::NFT(
-                        Chain::Ethereum,
-                        ContractCategory::ENS,
-                        ContractCategory::ENS.default_contract_address().unwrap(),
-                        self.identity.clone(),
-                    ),
-                    _ => Target::Identity(self.platform.clone(), self.identity.clone()),
-                };
-                let fetch_result = fetch_all(vec![target], Some(3)).await;
-                if fetch_result.is_err() {
-                    event!(
-                        Level::WARN,
-                        ?self.platform,
-                        self.identity,
-                        err = fetch_result.unwrap_err().to_string(),
-                        "Failed to fetch_all"
-                    );
-                }
-                Ok(IdentityGraph::find_graph_by_platform_identity(
-                    &client,
-                    &self.platform,
-                    &self.identity,
-                    reverse,
-                )
-                .await?)
-            }
-            Some(identity_graph) => Ok(Some(identity_graph)),
-        }
+        .await
     }
 }
 
@@ -347,6 +349,11 @@ impl IdentityConnection {
         self.data_source
     }
 
+    /// Base confidence of this connection from 0 to 1, by its data source and edge type.
+    async fn confidence(&self) -> f64 {
+        self.base_confidence(C.confidence.as_ref())
+    }
+
     /// The start node that forms the edge.
     async fn source(&self) -> String {
         self.source.clone()
@@ -357,3 +364,134 @@ impl IdentityConnection {
         self.target.clone()
     }
 }
+
+/// Find the identity graph of an identity. Fetch from upstreams if not found.
+/// Domain names past their grace period are left out unless `include_expired`.
+/// Vertices are scored by confidence relative to the identity, and the ones less confident
+/// than `min_confidence` are left out.
+pub(crate) async fn find_identity_graph(
+    platform: Platform,
+    identity: String,
+    reverse: Option<bool>,
+    include_expired: bool,
+    min_confidence: Option<f64>,
+) -> Result<Option<IdentityGraph>> {
+    let identity = normalize(platform, &identity);
+    validate(platform, &identity)?;
+    let client = make_http_client();
+    let found = match IdentityGraph::find_graph_by_platform_identity(
+        &client, &platform, &identity, reverse,
+    )
+    .await?
+    {
+        None => {
+            let target = Target::from_platform_identity(platform, &identity);
+            let fetch_result = fetch_all(vec![target], Some(3)).await;
+            if fetch_result.is_err() {
+                event!(
+                    Level::WARN,
+                    ?platform,
+                    identity,
+                    err = fetch_result.unwrap_err().to_string(),
+                    "Failed to fetch_all"
+                );
+            }
+            IdentityGraph::find_graph_by_platform_identity(&client, &platform, &identity, reverse)
+                .await?
+        }
+        Some(identity_graph) => Some(identity_graph),
+    };
+    Ok(found.map(|graph| {
+        prepare_identity_graph(graph, platform, &identity, include_expired, min_confidence)
+    }))
+}
+
+/// Find identity graphs of several candidates, e.g. guessed from one free-form input.
+/// Candidates not saved yet are fetched from upstreams together, in one `fetch_all`.
+/// Malformed or failing candidates are skipped, graphs found by more than one are returned once.
+/// Each graph is scored relative to the candidate it was found by, see `find_identity_graph`.
+pub(crate) async fn find_identity_graphs(
+    candidates: Vec<(Platform, String)>,
+    reverse: Option<bool>,
+    include_expired: bool,
+    min_confidence: Option<f64>,
+) -> Vec<IdentityGraph> {
+    let candidates: Vec<(Platform, String)> = candidates
+        .into_iter()
+        .map(|(platform, identity)| (platform, normalize(platform, &identity)))
+        .filter(|(platform, identity)| validate(*platform, identity).is_ok())
+        .collect();
+    let client = make_http_client();
+    let mut results = find_graphs(&client, &candidates, reverse).await;
+
+    let missing: Vec<usize> = (0..candidates.len())
+        .filter(|&i| matches!(results[i], Ok(None)))
+        .collect();
+    if !missing.is_empty() {
+        let unsaved: Vec<(Platform, String)> =
+            missing.iter().map(|&i| candidates[i].clone()).collect();
+        let targets = unsaved
+            .iter()
+            .map(|(platform, identity)| Target::from_platform_identity(*platform, identity))
+            .collect();
+        if let Err(err) = fetch_all(targets, Some(3)).await {
+            event!(
+                Level::WARN,
+                err = err.to_string(),
+                "Failed to fetch_all candidates"
+            );
+        }
+        let refound = find_graphs(&client, &unsaved, reverse).await;
+        for (i, result) in missing.into_iter().zip(refound) {
+            results[i] = result;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut graphs = vec![];
+    for ((platform, identity), result) in candidates.into_iter().zip(results) {
+        match result {
+            Ok(Some(graph)) if seen.insert(graph.graph_id.clone()) => graphs.push(
+                prepare_identity_graph(graph, platform, &identity, include_expired, min_confidence),
+            ),
+            Ok(_) => {}
+            Err(err) => event!(
+                Level::WARN,
+                ?platform,
+                identity,
+                err = err.to_string(),
+                "Failed to find identity graph of candidate"
+            ),
+        }
+    }
+    graphs
+}
+
+/// Find identity graphs of `candidates` in database, without fetching.
+async fn find_graphs(
+    client: &Client<HttpConnector>,
+    candidates: &[(Platform, String)],
+    reverse: Option<bool>,
+) -> Vec<Result<Option<IdentityGraph>>> {
+    join_all(candidates.iter().map(|(platform, identity)| {
+        IdentityGraph::find_graph_by_platform_identity(client, platform, identity, reverse)
+    }))
+    .await
+}
+
+/// Leave out domain names past their grace period unless `include_expired`,
+/// and score vertices by confidence relative to `(platform, identity)`.
+fn prepare_identity_graph(
+    graph: IdentityGraph,
+    platform: Platform,
+    identity: &str,
+    include_expired: bool,
+    min_confidence: Option<f64>,
+) -> IdentityGraph {
+    let v_id = format!("{},{}", platform, identity);
+    let graph = match include_expired {
+        true => graph,
+        false => graph.without_expired(&v_id),
+    };
+    graph.with_confidence(&v_id, min_confidence, C.confidence.as_ref())
+}
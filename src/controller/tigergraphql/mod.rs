@@ -1,15 +1,39 @@
+mod complexity;
 mod contract;
+mod errors;
+mod federation;
 mod hold;
 mod identity;
 mod identity_graph;
 mod proof;
 mod relation;
 mod resolve;
+mod search;
+#[cfg(test)]
+mod tests;
 
-use self::{hold::HoldQuery, identity::IdentityQuery, proof::ProofQuery, resolve::ResolveQuery};
-use async_graphql::{MergedObject, Object};
+use self::{
+    errors::ErrorCodes, federation::EntityQuery, hold::HoldQuery, identity::IdentityQuery,
+    proof::ProofQuery, relation::RelationQuery, resolve::ResolveQuery, search::SearchQuery,
+};
+use crate::{
+    metrics::GraphQLMetrics,
+    tigergraph::vertex::{ContractLoadFn, IdentityLoadFn, OwnerLoadFn},
+    util::make_http_client,
+};
+use async_graphql::{EmptyMutation, EmptySubscription, MergedObject, Object, Schema};
+pub(crate) use complexity::check_confidence;
+pub use complexity::{max_query_complexity, max_query_depth};
+use dataloader::non_cached::Loader;
+pub(crate) use hold::find_nft;
+pub(crate) use identity::find_identity;
+pub(crate) use identity_graph::find_identity_graph;
+pub(crate) use resolve::find_domain;
 const API_VERSION: &str = "0.1";
 
+/// GraphQL schema served by both standalone server and lambda.
+pub type RelationSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
 /// Base struct of GraphQL query request.
 #[derive(MergedObject, Default)]
 pub struct Query(
@@ -18,6 +42,9 @@ pub struct Query(
     ResolveQuery,
     ProofQuery,
     HoldQuery,
+    RelationQuery,
+    SearchQuery,
+    EntityQuery,
 );
 
 #[derive(Default)]
@@ -33,3 +60,38 @@ impl GeneralQuery {
         API_VERSION
     }
 }
+
+/// Build the GraphQL schema (an Apollo Federation subgraph), with dataloaders,
+/// query limits, metrics and error codes.
+pub fn build_schema() -> RelationSchema {
+    let client = make_http_client();
+    let contract_loader_fn = ContractLoadFn {
+        client: client.to_owned(),
+    };
+    let identity_loader_fn = IdentityLoadFn {
+        client: client.to_owned(),
+    };
+    let owner_loader_fn = OwnerLoadFn {
+        client: client.to_owned(),
+    };
+    let contract_loader = Loader::new(contract_loader_fn)
+        .with_max_batch_size(500)
+        .with_yield_count(100);
+    let identity_loader = Loader::new(identity_loader_fn)
+        .with_max_batch_size(500)
+        .with_yield_count(100);
+    let owner_loader = Loader::new(owner_loader_fn)
+        .with_max_batch_size(500)
+        .with_yield_count(100);
+
+    Schema::build(Query::default(), EmptyMutation, EmptySubscription)
+        .data(contract_loader)
+        .data(identity_loader)
+        .data(owner_loader)
+        .limit_depth(max_query_depth())
+        .limit_complexity(max_query_complexity())
+        .extension(GraphQLMetrics)
+        .extension(ErrorCodes)
+        .enable_federation()
+        .finish()
+}
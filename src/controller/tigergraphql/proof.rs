@@ -1,11 +1,12 @@
 use crate::{
+    controller::tigergraphql::complexity,
     error::{Error, Result},
     tigergraph::{
         edge::{Edge, ProofRecord},
         vertex::{IdentityLoadFn, IdentityRecord},
     },
-    upstream::{DataFetcher, DataSource},
-    util::make_http_client,
+    upstream::{DataFetcher, DataSource, ProofLevel},
+    util::{background::spawn_background, make_http_client},
 };
 
 use async_graphql::{Context, Object};
@@ -26,6 +27,16 @@ impl ProofRecord {
         self.source
     }
 
+    /// Confidence level of this connection.
+    async fn level(&self) -> ProofLevel {
+        self.level
+    }
+
+    /// Claimed by one side only, not verified (e.g. ENS text records).
+    async fn self_asserted(&self) -> bool {
+        self.source.is_self_asserted()
+    }
+
     /// ID of this connection in upstream platform to locate (if any).
     async fn record_id(&self) -> Option<String> {
         self.record_id.clone()
@@ -48,6 +59,7 @@ impl ProofRecord {
     }
 
     /// Which `IdentityRecord` does this connection starts at.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn from(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
         let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -58,6 +70,7 @@ impl ProofRecord {
     }
 
     /// Which `IdentityRecord` does this connection ends at.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
     async fn to(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
         let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
             ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
@@ -74,6 +87,7 @@ pub struct ProofQuery;
 
 #[Object]
 impl ProofQuery {
+    #[graphql(complexity = "complexity::COST_QUERY + child_complexity")]
     async fn proof(
         &self,
         _ctx: &Context<'_>,
@@ -91,10 +105,9 @@ impl ProofQuery {
     }
 
     /// Prefetch proofs which are prefetchable, e.g. SybilList.
+    #[graphql(complexity = "complexity::COST_FETCH")]
     async fn prefetch_proof(&self) -> Result<String> {
-        tokio::spawn(async move {
-            let _ = crate::upstream::prefetch().await;
-        });
+        spawn_background(crate::upstream::prefetch());
         Ok("Fetching".into())
     }
 }
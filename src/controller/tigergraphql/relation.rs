@@ -1,44 +1,62 @@
 use std::vec;
 
 use crate::{
+    controller::tigergraphql::complexity::{self, check_depth},
     error::{Error, Result},
     tigergraph::{
         delete_vertex_and_edge,
         edge::{RelationUniqueTX, RelationUniqueTXRecord},
-        vertex::{Identity, IdentityRecord},
+        vertex::{Identity, IdentityLoadFn, IdentityRecord},
     },
-    upstream::{fetch_all, Platform, Target},
-    util::make_http_client,
+    upstream::{fetch_all, normalize, validate, Platform, Target},
+    util::{background::spawn_refresh, make_http_client},
 };
 use async_graphql::{Context, Object};
-use tokio::time::{sleep, Duration};
+use dataloader::non_cached::Loader;
 use tracing::{event, Level};
 
 #[Object]
 impl RelationUniqueTXRecord {
-    async fn count(&self) -> u32 {
+    /// Amount of unique transactions.
+    async fn count(&self) -> i64 {
         self.count.clone()
     }
 
-    async fn sum(&self) -> u32 {
+    /// Sum of transaction values, in Gwei.
+    async fn sum(&self) -> i64 {
         self.sum.clone()
     }
 
-    async fn max(&self) -> u32 {
+    /// Max value of a single transaction, in Gwei.
+    async fn max(&self) -> i64 {
         self.max.clone()
     }
 
-    async fn min(&self) -> u32 {
+    /// Min value of a single transaction, in Gwei.
+    async fn min(&self) -> i64 {
         self.min.clone()
     }
 
-    async fn from(&self, _ctx: &Context<'_>) -> Result<IdentityRecord> {
-        todo!()
+    /// Which `IdentityRecord` does this connection starts at.
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
+    async fn from(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
+        let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
+            ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
+        match loader.load(self.from_id.clone()).await {
+            Some(value) => Ok(value),
+            None => Err(Error::GraphQLError("record from no found.".to_string())),
+        }
     }
 
     /// Which `IdentityRecord` does this connection ends at.
-    async fn to(&self, _ctx: &Context<'_>) -> Result<IdentityRecord> {
-        todo!()
+    #[graphql(complexity = "complexity::COST_LOADER + child_complexity")]
+    async fn to(&self, ctx: &Context<'_>) -> Result<IdentityRecord> {
+        let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
+            ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
+        match loader.load(self.to_id.clone()).await {
+            Some(value) => Ok(value),
+            None => Err(Error::GraphQLError("record to no found.".to_string())),
+        }
     }
 }
 
@@ -48,6 +66,9 @@ pub struct RelationQuery;
 
 #[Object]
 impl RelationQuery {
+    #[graphql(
+        complexity = "complexity::fetch_many(2, complexity::traversal(depth, child_complexity))"
+    )]
     #[tracing::instrument(level = "trace", skip(self, _ctx))]
     async fn relation(
         &self,
@@ -58,9 +79,14 @@ impl RelationQuery {
         #[graphql(desc = "Target Identity")] target_identity: String,
         #[graphql(desc = "Depth of traversal. 1 if omitted")] depth: Option<u16>,
     ) -> Result<Vec<RelationUniqueTXRecord>> {
+        let depth = check_depth(depth)?;
         let client = make_http_client();
         let source_platform: Platform = source_platform.parse()?;
         let target_platform: Platform = target_platform.parse()?;
+        let source_identity = normalize(source_platform, &source_identity);
+        let target_identity = normalize(target_platform, &target_identity);
+        validate(source_platform, &source_identity)?;
+        validate(target_platform, &target_identity)?;
         let source_fetch = Target::Identity(source_platform, source_identity.clone());
         let target_fetch = Target::Identity(target_platform, target_identity.clone());
         let source =
@@ -90,13 +116,11 @@ impl RelationQuery {
                             "Outdated. Delete and Refetching."
                         );
                         let v_id = found.v_id.clone();
-                        tokio::spawn(async move {
-                            // Delete and Refetch in the background
-                            sleep(Duration::from_secs(10)).await;
-                            delete_vertex_and_edge(&client, v_id).await?;
-                            fetch_all(vec![source_fetch], Some(3)).await?;
-                            Ok::<_, Error>(())
-                        });
+                        // Delete and Refetch in the background
+                        spawn_refresh(
+                            async move { delete_vertex_and_edge(&client, v_id).await },
+                            fetch_all(vec![source_fetch], Some(3)),
+                        );
                     }
                     Some(found)
                 }
@@ -130,13 +154,11 @@ impl RelationQuery {
                             "Outdated. Delete and Refetching."
                         );
                         let v_id = found.v_id.clone();
-                        tokio::spawn(async move {
-                            // Delete and Refetch in the background
-                            sleep(Duration::from_secs(10)).await;
-                            delete_vertex_and_edge(&client, v_id).await?;
-                            fetch_all(vec![target_fetch], Some(3)).await?;
-                            Ok::<_, Error>(())
-                        });
+                        // Delete and Refetch in the background
+                        spawn_refresh(
+                            async move { delete_vertex_and_edge(&client, v_id).await },
+                            fetch_all(vec![target_fetch], Some(3)),
+                        );
                     }
                     Some(found)
                 }
@@ -145,16 +167,12 @@ impl RelationQuery {
             return Ok(vec![]);
         }
         let client = make_http_client();
-        let relation = RelationUniqueTX::relation(
-            &client,
-            &source.unwrap(),
-            &target.unwrap(),
-            depth.unwrap_or(1),
-        )
-        .await?;
+        let relation =
+            RelationUniqueTX::relation(&client, &source.unwrap(), &target.unwrap(), depth).await?;
         Ok(relation)
     }
 
+    #[graphql(complexity = "complexity::fetch(complexity::traversal(depth, child_complexity))")]
     async fn expand(
         &self,
         _ctx: &Context<'_>,
@@ -162,9 +180,12 @@ impl RelationQuery {
         #[graphql(desc = "Identity on target Platform")] identity: String,
         #[graphql(desc = "Depth of traversal. 1 if omitted")] depth: Option<u16>,
     ) -> Result<Vec<RelationUniqueTXRecord>> {
+        let depth = check_depth(depth)?;
         let client = make_http_client();
 
         let platform: Platform = platform.parse()?;
+        let identity = normalize(platform, &identity);
+        validate(platform, &identity)?;
         let target = Target::Identity(platform, identity.clone());
         let source =
             match Identity::find_by_platform_identity(&client, &platform, &identity).await? {
@@ -190,13 +211,11 @@ impl RelationQuery {
                             "Outdated. Delete and Refetching."
                         );
                         let v_id = found.v_id.clone();
-                        tokio::spawn(async move {
-                            // Delete and Refetch in the background
-                            sleep(Duration::from_secs(10)).await;
-                            delete_vertex_and_edge(&client, v_id).await?;
-                            fetch_all(vec![target], Some(3)).await?;
-                            Ok::<_, Error>(())
-                        });
+                        // Delete and Refetch in the background
+                        spawn_refresh(
+                            async move { delete_vertex_and_edge(&client, v_id).await },
+                            fetch_all(vec![target], Some(3)),
+                        );
                     }
                     Some(found)
                 }
@@ -205,8 +224,7 @@ impl RelationQuery {
             return Ok(vec![]);
         }
         let client = make_http_client();
-        let expand =
-            RelationUniqueTX::expand(&client, &source.unwrap(), depth.unwrap_or(1)).await?;
+        let expand = RelationUniqueTX::expand(&client, &source.unwrap(), depth).await?;
         Ok(expand)
     }
 }
@@ -1,4 +1,5 @@
 use crate::{
+    controller::tigergraphql::complexity,
     error::{Error, Result},
     tigergraph::{
         delete_vertex_and_edge,
@@ -6,13 +7,13 @@ use crate::{
         vertex::IdentityRecord,
     },
     upstream::{
-        fetch_all, Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem, Target,
+        fetch_all, normalize_name, validate, DataFetcher, DataSource, DomainNameSystem,
+        ExpiryStatus, Target,
     },
-    util::make_http_client,
+    util::{background::spawn_refresh, make_http_client, naive_now},
 };
 use async_graphql::{Context, Object};
 use strum::IntoEnumIterator;
-use tokio::time::{sleep, Duration};
 use uuid::Uuid;
 
 #[Object]
@@ -53,6 +54,12 @@ impl ResolveReverse {
     async fn reverse(&self) -> bool {
         self.reverse.clone()
     }
+
+    /// `verified`: The primary name also resolves to the address claiming it.
+    /// Reverse records failing this are spoofed, and never make `reverse` true.
+    async fn verified(&self) -> bool {
+        self.verified
+    }
 }
 
 #[Object]
@@ -107,6 +114,12 @@ impl ResolveEdge {
         self.reverse.clone()
     }
 
+    /// `verified`: The primary name also resolves to the address claiming it.
+    /// Reverse records failing this are spoofed, and never make `reverse` true.
+    async fn verified(&self) -> bool {
+        self.verified
+    }
+
     /// `reverseRecord`: Only have one primary domain linked to an address.
     async fn reverse_record(&self) -> Option<IdentityRecord> {
         self.reverse_record.clone()
@@ -116,6 +129,12 @@ impl ResolveEdge {
     async fn expired_at(&self) -> Option<i64> {
         self.expired_at.map(|dt| dt.and_utc().timestamp())
     }
+
+    /// `expiryStatus` Active, in its grace period, or expired (released).
+    /// `null` if this domain name never expires.
+    async fn expiry_status(&self) -> Option<ExpiryStatus> {
+        self.system.expiry_status(self.expired_at, naive_now())
+    }
 }
 
 #[derive(Default)]
@@ -129,6 +148,7 @@ impl ResolveQuery {
             .collect()
     }
 
+    #[graphql(complexity = "complexity::fetch(child_complexity)")]
     #[tracing::instrument(level = "trace", skip(self, _ctx))]
     async fn domain(
         &self,
@@ -141,71 +161,53 @@ impl ResolveQuery {
             desc = "Name of domain. For example the name is (name: \"abc.eth\") or (name: \"abc.bit\") or (name: \"abc.bnb\")"
         )]
         name: String,
+        #[graphql(desc = "Also return the domain if past its grace period. Defaults to false.")]
+        include_expired: Option<bool>,
     ) -> Result<Option<ResolveEdge>> {
-        let client = make_http_client();
-        match domain_system {
-            DomainNameSystem::ENS => {
-                let target = Target::NFT(
-                    Chain::Ethereum,
-                    ContractCategory::ENS,
-                    ContractCategory::ENS.default_contract_address().unwrap(),
-                    name.clone(),
+        find_domain(domain_system, name, include_expired.unwrap_or(false)).await
+    }
+}
+
+/// Find a domain by its name system and name. Fetch from upstreams if not found.
+/// Outdated records are refreshed in the background.
+/// A domain past its grace period is not found unless `include_expired`.
+pub(crate) async fn find_domain(
+    domain_system: DomainNameSystem,
+    name: String,
+    include_expired: bool,
+) -> Result<Option<ResolveEdge>> {
+    let name = normalize_name(domain_system, &name);
+    validate(domain_system.into(), &name)?;
+    let client = make_http_client();
+    let Some(target) = Target::from_domain(domain_system, &name) else {
+        return Ok(None);
+    };
+    let found = match Resolve::find_by_name_system(&client, &name, &domain_system).await? {
+        None => {
+            let _ = fetch_all(vec![target], Some(3)).await;
+            Resolve::find_by_name_system(&client, &name, &domain_system).await?
+        }
+        Some(resolve) => {
+            if resolve.is_outdated() {
+                let v_id: String = resolve
+                    .owner
+                    .as_ref()
+                    .map(|f| f.v_id.clone())
+                    .unwrap_or_default();
+                // Delete and Refetch in the background
+                spawn_refresh(
+                    async move { delete_vertex_and_edge(&client, v_id).await },
+                    fetch_all(vec![target], Some(3)),
                 );
-                match Resolve::find_by_name_system(&client, &name, &domain_system).await? {
-                    None => {
-                        let _ = fetch_all(vec![target], Some(3)).await;
-                        Resolve::find_by_name_system(&client, &name, &domain_system).await
-                    }
-                    Some(resolve) => {
-                        if resolve.is_outdated() {
-                            let v_id: String = resolve
-                                .clone()
-                                .owner
-                                .and_then(|f| Some(f.v_id.clone()))
-                                .unwrap_or("".to_string());
-                            tokio::spawn(async move {
-                                // Delete and Refetch in the background
-                                sleep(Duration::from_secs(10)).await;
-                                delete_vertex_and_edge(&client, v_id).await?;
-                                fetch_all(vec![target], Some(3)).await?;
-                                Ok::<_, Error>(())
-                            });
-                        }
-                        Ok(Some(resolve))
-                    }
-                }
-            }
-            DomainNameSystem::DotBit
-            | DomainNameSystem::Lens
-            | DomainNameSystem::UnstoppableDomains
-            | DomainNameSystem::SpaceId => {
-                let platform = domain_system.into();
-                let target = Target::Identity(platform, name.clone());
-                match Resolve::find_by_name_system(&client, &name, &domain_system).await? {
-                    None => {
-                        let _ = fetch_all(vec![target], Some(3)).await;
-                        Resolve::find_by_name_system(&client, &name, &domain_system).await
-                    }
-                    Some(resolve) => {
-                        if resolve.is_outdated() {
-                            let v_id: String = resolve
-                                .clone()
-                                .owner
-                                .and_then(|f| Some(f.v_id.clone()))
-                                .unwrap_or("".to_string());
-                            tokio::spawn(async move {
-                                // Delete and Refetch in the background
-                                sleep(Duration::from_secs(10)).await;
-                                delete_vertex_and_edge(&client, v_id).await?;
-                                fetch_all(vec![target], Some(3)).await?;
-                                Ok::<_, Error>(())
-                            });
-                        }
-                        Ok(Some(resolve))
-                    }
-                }
             }
-            _ => Ok(None),
+            Some(resolve)
         }
-    }
+    };
+    Ok(found.filter(|resolve| {
+        include_expired
+            || resolve
+                .system
+                .expiry_status(resolve.expired_at, naive_now())
+                != Some(ExpiryStatus::Expired)
+    }))
 }
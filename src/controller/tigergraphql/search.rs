@@ -0,0 +1,87 @@
+use crate::{
+    controller::tigergraphql::complexity,
+    error::{Error, Result},
+    search::{search, MatchKind, SearchField, SearchHit},
+    tigergraph::vertex::{IdentityLoadFn, IdentityRecord},
+    upstream::Platform,
+};
+use async_graphql::{Context, Object};
+use dataloader::non_cached::Loader;
+
+/// Max amount of results returned by one search.
+const MAX_SEARCH_LIMIT: u16 = 100;
+
+#[Object]
+impl SearchHit {
+    /// Relevance of this result, higher is better. In range `(0, 1]`.
+    async fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Which field matched the keyword.
+    async fn field(&self) -> SearchField {
+        self.field
+    }
+
+    /// How the keyword matched: `exact`, `prefix` or `fuzzy`.
+    async fn kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    /// Platform of matched identity.
+    async fn platform(&self) -> Platform {
+        self.entry.platform
+    }
+
+    /// Identity on target platform.
+    async fn identity(&self) -> String {
+        self.entry.identity.clone()
+    }
+
+    /// Display name of matched identity (if any).
+    async fn display_name(&self) -> Option<String> {
+        self.entry.display_name.clone()
+    }
+
+    /// Uid of matched identity (if any).
+    async fn uid(&self) -> Option<String> {
+        self.entry.uid.clone()
+    }
+
+    /// Full `IdentityRecord` loaded from database.
+    async fn record(&self, ctx: &Context<'_>) -> Result<Option<IdentityRecord>> {
+        let loader: &Loader<String, Option<IdentityRecord>, IdentityLoadFn> =
+            ctx.data().map_err(|err| Error::GraphQLError(err.message))?;
+        Ok(loader.load(self.entry.v_id.clone()).await)
+    }
+}
+
+#[derive(Default)]
+pub struct SearchQuery;
+
+#[Object]
+impl SearchQuery {
+    /// Search identities across all platforms by `identity`, `displayName` and `uid`.
+    /// Supports prefix (`vita` => `vitalik.eth`) and fuzzy (`vitalk` => `vitalik`) matching.
+    #[graphql(complexity = "complexity::paginated(limit, 20, child_complexity)")]
+    #[tracing::instrument(level = "trace", skip(self, _ctx))]
+    async fn search(
+        &self,
+        _ctx: &Context<'_>,
+        #[graphql(desc = "Keyword to search.")] keyword: String,
+        #[graphql(
+            desc = "Only return identities on these platforms. All platforms will be searched if omitted or empty."
+        )]
+        platforms: Option<Vec<Platform>>,
+        #[graphql(
+            desc = "`limit` used to control the maximum number of records returned by query. It defaults to 20, at most 100."
+        )]
+        limit: Option<u16>,
+    ) -> Result<Vec<SearchHit>> {
+        if keyword.trim().is_empty() {
+            return Err(Error::ParamMissing("keyword".to_string()));
+        }
+        let limit = limit.unwrap_or(20).min(MAX_SEARCH_LIMIT) as usize;
+        Ok(search(&keyword, &platforms.unwrap_or_default(), limit).await)
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    controller::tigergraphql::{build_schema, complexity},
+    error::Error,
+    tigergraph::{
+        batch_upsert,
+        edge::{Proof, Wrapper, CLAIM},
+        vertex::Identity,
+        EdgeWrapperEnum,
+    },
+    upstream::{DataFetcher, DataSource, Platform, ProofLevel},
+    util::make_http_client,
+};
+use async_graphql::SDLExportOptions;
+
+#[test]
+fn test_paginated_counts_every_record() {
+    let child = 3;
+    assert_eq!(
+        complexity::paginated(Some(50), 100, child),
+        complexity::COST_QUERY + 50 * child
+    );
+    assert_eq!(
+        complexity::paginated(None, 100, child),
+        complexity::COST_QUERY + 100 * child
+    );
+    assert_eq!(
+        complexity::paginated(Some(u16::MAX), 100, child),
+        complexity::COST_QUERY + complexity::MAX_LIMIT as usize * child
+    );
+}
+
+#[tokio::test]
+async fn test_complexity_rejects_large_pages() {
+    let schema = build_schema();
+    let query = r#"{
+        identity(platform: "ethereum", identity: "0x934b510d4c9103e6a87aef13b816fb080286d649") {
+            nft(limit: 1000) { id category chain }
+        }
+    }"#;
+    let response = schema.execute(query).await;
+    assert!(response
+        .errors
+        .iter()
+        .any(|err| err.message.contains("too complex")));
+}
+
+#[tokio::test]
+async fn test_complexity_counts_graph_vertices() {
+    let schema = build_schema();
+    let query = r#"{
+        identity(platform: "ethereum", identity: "0x934b510d4c9103e6a87aef13b816fb080286d649") {
+            identityGraph {
+                vertices {
+                    nft(limit: 100) { id category chain }
+                }
+            }
+        }
+    }"#;
+    let response = schema.execute(query).await;
+    assert!(response
+        .errors
+        .iter()
+        .any(|err| err.message.contains("too complex")));
+}
+
+#[tokio::test]
+async fn test_saved_claim_is_self_asserted() -> Result<(), Error> {
+    let name = Identity::new_domain(Platform::ENS, "claimtest.eth", false);
+    let mut linked = Identity::default();
+    linked.platform = Platform::Twitter;
+    linked.identity = "claimtest".to_string();
+    let claim = Proof {
+        source: DataSource::ENSTextRecord,
+        level: ProofLevel::Insecure,
+        record_id: Some("com.twitter".to_string()),
+        fetcher: DataFetcher::RelationService,
+        ..Default::default()
+    };
+    let edges = vec![EdgeWrapperEnum::new_claim(
+        claim.wrapper(&name, &linked, CLAIM),
+    )];
+    batch_upsert(&make_http_client(), edges).await?;
+
+    let query = r#"{
+        identity(platform: "ens", identity: "claimtest.eth") {
+            claims { source selfAsserted to { platform identity } }
+        }
+    }"#;
+    let response = build_schema().execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json()?;
+    let claims = data["identity"]["claims"].as_array().unwrap();
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims[0]["source"], "ens_text_record");
+    assert_eq!(claims[0]["selfAsserted"], true);
+    assert_eq!(claims[0]["to"]["identity"], "claimtest");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_relation_query_is_merged() {
+    let sdl = build_schema().sdl();
+    assert!(sdl.contains("relation("));
+}
+
+/// Federation SDL served to the gateway.
+/// After changing the schema on purpose, regenerate it with `UPDATE_SNAPSHOTS=1 cargo test`.
+#[tokio::test]
+async fn test_federation_sdl_snapshot() {
+    let sdl = build_schema().sdl_with_options(SDLExportOptions::new().federation());
+    for key in [
+        r#"type IdentityRecord @key(fields: "platform identity")"#,
+        r#"type ContractRecord @key(fields: "chain address")"#,
+        r#"type ResolveEdge @key(fields: "system name")"#,
+    ] {
+        assert!(sdl.contains(key), "Missing `{}` in SDL", key);
+    }
+
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/controller/tigergraphql/federation.graphql"
+    );
+    if std::env::var("UPDATE_SNAPSHOTS").is_ok() {
+        std::fs::write(path, &sdl).unwrap();
+    }
+    let snapshot = std::fs::read_to_string(path).unwrap_or_default();
+    assert!(
+        sdl == snapshot,
+        "SDL differs from {}, run with UPDATE_SNAPSHOTS=1 to update it",
+        path
+    );
+}
@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 use lambda_http::http::StatusCode;
 use thiserror::Error;
 
@@ -27,6 +30,8 @@ pub enum Error {
     HttpClientError(#[from] hyper::Error),
     #[error("ManualHttpClientError error: {0}")]
     ManualHttpClientError(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
     #[error("UUID parse error: {0}")]
     UuidError(#[from] uuid::Error),
     #[error("Parse error: {0}")]
@@ -64,6 +69,7 @@ impl Error {
             Error::ConfigError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::HttpClientError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::ManualHttpClientError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::DatabaseError(_) => StatusCode::SERVICE_UNAVAILABLE,
             Error::SignatureValidationError(_) => StatusCode::BAD_REQUEST,
             Error::EnumParseError(_) => StatusCode::BAD_REQUEST,
             Error::GraphQLError(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -78,6 +84,47 @@ impl Error {
             Error::ParsePubkeyError(_) => StatusCode::BAD_REQUEST,
         }
     }
+
+    /// Stable, machine-readable code of this error for API clients,
+    /// given in GraphQL error `extensions` and REST error bodies.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::General(_, status) => match *status {
+                StatusCode::NOT_FOUND => "NOT_FOUND",
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => "UNAUTHORIZED",
+                StatusCode::TOO_MANY_REQUESTS => "RATE_LIMITED",
+                StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => "TIMEOUT",
+                StatusCode::SERVICE_UNAVAILABLE => "UNAVAILABLE",
+                status if status.is_client_error() => "BAD_REQUEST",
+                _ => "INTERNAL_ERROR",
+            },
+            Error::ParamMissing(_) => "PARAM_MISSING",
+            Error::ParamError(_) => "PARAM_INVALID",
+            Error::BodyMissing => "BODY_MISSING",
+            Error::NoResult => "NO_RESULT",
+            Error::JSONParseError(_) => "JSON_PARSE_ERROR",
+            Error::HttpError(_) => "HTTP_ERROR",
+            Error::ConfigError(_) => "CONFIG_ERROR",
+            Error::SignatureValidationError(_) => "SIGNATURE_INVALID",
+            Error::HttpClientError(err) if err.is_timeout() => "UPSTREAM_TIMEOUT",
+            Error::HttpClientError(_) => "UPSTREAM_ERROR",
+            Error::ManualHttpClientError(_) => "UPSTREAM_ERROR",
+            Error::DatabaseError(_) => "DATABASE_ERROR",
+            Error::UuidError(_) => "UUID_PARSE_ERROR",
+            // e.g. unknown platform, chain or name system.
+            Error::EnumParseError(_) => "UNKNOWN_ENUM_VALUE",
+            Error::ParseIntError(_) => "INT_PARSE_ERROR",
+            Error::DatetimeParseError(_) => "DATETIME_PARSE_ERROR",
+            Error::GraphQLError(_) => "GRAPHQL_ERROR",
+            Error::PoolError(_) => "POOL_ERROR",
+            Error::IsahcError(err) if err.is_timeout() => "UPSTREAM_TIMEOUT",
+            Error::IsahcError(_) => "UPSTREAM_ERROR",
+            Error::StdIOError(_) => "IO_ERROR",
+            Error::SnsError(_) => "SNS_ERROR",
+            Error::SolanaClientError(_) => "SOLANA_RPC_ERROR",
+            Error::ParsePubkeyError(_) => "PUBKEY_PARSE_ERROR",
+        }
+    }
 }
 
 impl warp::reject::Reject for Error {}
@@ -0,0 +1,23 @@
+use super::*;
+
+#[test]
+fn test_code() {
+    assert_eq!(Error::ParamError("bad".into()).code(), "PARAM_INVALID");
+    assert_eq!(
+        Error::EnumParseError(strum::ParseError::VariantNotFound).code(),
+        "UNKNOWN_ENUM_VALUE"
+    );
+    assert_eq!(Error::DatabaseError("down".into()).code(), "DATABASE_ERROR");
+    assert_eq!(
+        Error::General("Not Found".into(), StatusCode::NOT_FOUND).code(),
+        "NOT_FOUND"
+    );
+    assert_eq!(
+        Error::General("Timeout".into(), StatusCode::REQUEST_TIMEOUT).code(),
+        "TIMEOUT"
+    );
+    assert_eq!(
+        Error::General("Oops".into(), StatusCode::INTERNAL_SERVER_ERROR).code(),
+        "INTERNAL_ERROR"
+    );
+}
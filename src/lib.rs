@@ -1,9 +1,14 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod auth;
+pub mod cache;
 pub mod config;
 pub mod controller;
 pub mod error;
+pub mod metrics;
+pub mod search;
+pub mod telemetry;
 pub mod tigergraph;
 pub mod util;
 
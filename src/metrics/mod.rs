@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    cache,
+    error::Error,
+    upstream::{DataSource, FETCHING},
+    util::background::background_tasks,
+};
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo},
+    ServerResult, Value,
+};
+use http::StatusCode;
+use prometheus::{
+    register_histogram_vec, register_int_gauge, register_int_gauge_vec, HistogramVec, IntGauge,
+    IntGaugeVec, TextEncoder,
+};
+use std::{future::Future, sync::Arc};
+
+/// `Content-Type` of `render()`.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+lazy_static! {
+    /// Time spent resolving each GraphQL field, incl. its dataloader / TigerGraph calls.
+    pub static ref GRAPHQL_FIELD_DURATION: HistogramVec = register_histogram_vec!(
+        "relation_graphql_field_duration_seconds",
+        "Time spent resolving a GraphQL field.",
+        &["parent_type", "field"]
+    )
+    .unwrap();
+    /// Time spent on each TigerGraph request, by installed query.
+    pub static ref TIGERGRAPH_QUERY_DURATION: HistogramVec = register_histogram_vec!(
+        "relation_tigergraph_query_duration_seconds",
+        "Time spent on a TigerGraph request.",
+        &["query"]
+    )
+    .unwrap();
+    /// Time spent on each upstream `batch_fetch`.
+    pub static ref UPSTREAM_FETCH_DURATION: HistogramVec = register_histogram_vec!(
+        "relation_upstream_fetch_duration_seconds",
+        "Time spent fetching a target from an upstream.",
+        &["data_source", "result"],
+        vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]
+    )
+    .unwrap();
+    /// Size of `upstream::FETCHING`.
+    static ref FETCHING_TARGETS: IntGauge = register_int_gauge!(
+        "relation_upstream_fetching_targets",
+        "Targets being fetched from upstreams."
+    )
+    .unwrap();
+    static ref BACKGROUND_TASKS: IntGauge = register_int_gauge!(
+        "relation_background_tasks",
+        "Background tasks still running, e.g. refetching outdated records."
+    )
+    .unwrap();
+    /// Snapshot of `cache::stats()`.
+    static ref RESPONSE_CACHE: IntGaugeVec = register_int_gauge_vec!(
+        "relation_response_cache",
+        "Response cache counters (hits, misses, evictions, invalidations) and entries.",
+        &["cache", "stat"]
+    )
+    .unwrap();
+}
+
+/// Run an upstream fetch, observing its duration into `UPSTREAM_FETCH_DURATION`.
+pub async fn observe_fetch<F, T>(source: DataSource, fetch: F) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let timer = std::time::Instant::now();
+    let result = fetch.await;
+    let outcome = if result.is_ok() { "success" } else { "error" };
+    UPSTREAM_FETCH_DURATION
+        .with_label_values(&[&source.to_string(), outcome])
+        .observe(timer.elapsed().as_secs_f64());
+    result
+}
+
+/// Label of a TigerGraph request in `TIGERGRAPH_QUERY_DURATION`, by its URI path:
+/// name of the installed query (`/query/{graph}/{name}`), or kind of the built-in endpoint.
+pub fn tigergraph_query_name(path: &str) -> &str {
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["query", _graph, name, ..] => name,
+        ["graph", _graph] => "upsert",
+        ["graph", _graph, kind, ..] => kind,
+        ["endpoints", ..] => "endpoints",
+        ["id_allocation", ..] => "id_allocation",
+        _ => "other",
+    }
+}
+
+/// Render all metrics in Prometheus text format.
+pub async fn render() -> Result<String, Error> {
+    FETCHING_TARGETS.set(FETCHING.lock().await.len() as i64);
+    BACKGROUND_TASKS.set(background_tasks() as i64);
+    for (name, stats) in cache::stats() {
+        for (stat, value) in [
+            ("hits", stats.hits as i64),
+            ("misses", stats.misses as i64),
+            ("evictions", stats.evictions as i64),
+            ("invalidations", stats.invalidations as i64),
+            ("entries", stats.entries as i64),
+        ] {
+            RESPONSE_CACHE.with_label_values(&[name, stat]).set(value);
+        }
+    }
+
+    TextEncoder::new()
+        .encode_to_string(&prometheus::gather())
+        .map_err(|err| {
+            Error::General(
+                format!("Failed to render metrics: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })
+}
+
+/// GraphQL extension observing every resolved field into `GRAPHQL_FIELD_DURATION`.
+pub struct GraphQLMetrics;
+
+impl ExtensionFactory for GraphQLMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GraphQLMetricsExtension)
+    }
+}
+
+struct GraphQLMetricsExtension;
+
+#[async_trait::async_trait]
+impl Extension for GraphQLMetricsExtension {
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        if info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+        let timer = GRAPHQL_FIELD_DURATION
+            .with_label_values(&[info.parent_type, info.name])
+            .start_timer();
+        let result = next.run(ctx, info).await;
+        timer.observe_duration();
+        result
+    }
+}
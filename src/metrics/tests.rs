@@ -0,0 +1,43 @@
+use super::*;
+
+#[test]
+fn test_tigergraph_query_name() {
+    assert_eq!(
+        tigergraph_query_name("/query/SocialGraph/find_identity_graph"),
+        "find_identity_graph"
+    );
+    assert_eq!(tigergraph_query_name("/graph/SocialGraph"), "upsert");
+    assert_eq!(
+        tigergraph_query_name("/graph/SocialGraph/vertices/Identities"),
+        "vertices"
+    );
+    assert_eq!(
+        tigergraph_query_name("/graph/SocialGraph/edges/Identities/abc/Hold_Identity"),
+        "edges"
+    );
+    assert_eq!(tigergraph_query_name("/endpoints/SocialGraph"), "endpoints");
+    assert_eq!(
+        tigergraph_query_name("/id_allocation/allocation"),
+        "id_allocation"
+    );
+    assert_eq!(tigergraph_query_name("/echo"), "other");
+}
+
+#[tokio::test]
+async fn test_render() -> Result<(), Error> {
+    observe_fetch(DataSource::Keybase, async { Ok(()) }).await?;
+    let failed: Result<(), Error> =
+        observe_fetch(DataSource::Keybase, async { Err(Error::NoResult) }).await;
+    assert!(failed.is_err());
+
+    let rendered = render().await?;
+    assert!(rendered.contains(
+        r#"relation_upstream_fetch_duration_seconds_count{data_source="keybase",result="success"}"#
+    ));
+    assert!(rendered.contains(
+        r#"relation_upstream_fetch_duration_seconds_count{data_source="keybase",result="error"}"#
+    ));
+    assert!(rendered.contains("relation_background_tasks"));
+    assert!(rendered.contains(r#"relation_response_cache{cache="identity",stat="hits"}"#));
+    Ok(())
+}
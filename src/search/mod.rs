@@ -0,0 +1,503 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    config::C,
+    error::Error,
+    tigergraph::{
+        timed_request, vertex::Identity, vertex::Vertex, BaseResponse, EdgeWrapperEnum, Graph,
+    },
+    upstream::Platform,
+    util::{make_http_client, parse_body},
+};
+use http::uri::InvalidUri;
+use hyper::{Body, Method};
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::RwLock;
+use tracing::{error, info, trace, warn};
+
+/// Max edit distance accepted by fuzzy matching.
+const MAX_FUZZY_DISTANCE: usize = 2;
+/// Tokens shorter than this are never fuzzy-matched (too many false positives).
+const MIN_FUZZY_LENGTH: usize = 4;
+/// Default of `[search] capacity`.
+const DEFAULT_CAPACITY: usize = 100_000;
+
+lazy_static! {
+    /// Global search index of identities known by this instance.
+    /// Loaded by `warm_up`, maintained by `batch_upsert` and identity queries.
+    pub static ref SEARCH_INDEX: Arc<RwLock<SearchIndex>> =
+        Arc::new(RwLock::new(SearchIndex::new(capacity())));
+}
+
+/// Max identities in the global index, read from `[search] capacity`.
+fn capacity() -> usize {
+    C.search
+        .as_ref()
+        .and_then(|search| search.capacity)
+        .unwrap_or(DEFAULT_CAPACITY)
+}
+
+/// Which field of an identity matched the search keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, async_graphql::Enum)]
+pub enum SearchField {
+    #[graphql(name = "identity")]
+    Identity,
+    #[graphql(name = "display_name")]
+    DisplayName,
+    #[graphql(name = "uid")]
+    Uid,
+}
+
+impl SearchField {
+    /// Fields are weighted: a hit on `identity` is worth more than a hit on `display_name`.
+    fn weight(&self) -> f64 {
+        match self {
+            SearchField::Identity => 1.0,
+            SearchField::DisplayName => 0.9,
+            SearchField::Uid => 0.8,
+        }
+    }
+}
+
+/// How the keyword matched the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, async_graphql::Enum)]
+pub enum MatchKind {
+    #[graphql(name = "exact")]
+    Exact,
+    #[graphql(name = "prefix")]
+    Prefix,
+    #[graphql(name = "fuzzy")]
+    Fuzzy,
+}
+
+/// A searchable snapshot of an `Identity` vertex.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchEntry {
+    /// `v_id` of the vertex in TigerGraph, i.e. `"{platform},{identity}"`.
+    pub v_id: String,
+    pub platform: Platform,
+    pub identity: String,
+    pub display_name: Option<String>,
+    pub uid: Option<String>,
+}
+
+impl From<&Identity> for SearchEntry {
+    fn from(identity: &Identity) -> Self {
+        SearchEntry {
+            v_id: identity.primary_key(),
+            platform: identity.platform,
+            identity: identity.identity.clone(),
+            display_name: identity
+                .display_name
+                .clone()
+                .filter(|name| !name.is_empty()),
+            uid: identity.uid.clone().filter(|uid| !uid.is_empty()),
+        }
+    }
+}
+
+/// One ranked result of `SearchIndex::search`.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub entry: SearchEntry,
+    pub score: f64,
+    pub field: SearchField,
+    pub kind: MatchKind,
+}
+
+struct Indexed {
+    entry: SearchEntry,
+    /// Value of `SearchIndex::tick` when this entry was last indexed.
+    indexed_at: u64,
+}
+
+/// In-memory inverted index over `identity`, `display_name` and `uid`.
+/// Terms are lowercased; a `BTreeMap` keeps them sorted so prefix lookups are a range scan.
+/// Holds at most `capacity` entries: the least recently indexed one is evicted when full.
+pub struct SearchIndex {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, Indexed>,
+    /// `v_id` of every entry by `indexed_at`, oldest first.
+    recency: BTreeMap<u64, String>,
+    terms: BTreeMap<String, HashSet<(String, SearchField)>>,
+    /// Terms by their length in chars, so fuzzy matching only compares terms of similar length.
+    lengths: BTreeMap<usize, HashSet<String>>,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        SearchIndex::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl SearchIndex {
+    pub fn new(capacity: usize) -> Self {
+        SearchIndex {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            terms: BTreeMap::new(),
+            lengths: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert or replace an entry, which becomes the most recently indexed one.
+    pub fn upsert(&mut self, entry: SearchEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some(existing) = self.entries.get_mut(&entry.v_id) {
+            self.recency.remove(&existing.indexed_at);
+            self.recency.insert(self.tick, entry.v_id.clone());
+            existing.indexed_at = self.tick;
+            if existing.entry == entry {
+                return;
+            }
+            let existing = std::mem::replace(&mut existing.entry, entry.clone());
+            self.remove_terms(&existing);
+            self.insert_terms(&entry);
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        self.insert_terms(&entry);
+        self.recency.insert(self.tick, entry.v_id.clone());
+        self.entries.insert(
+            entry.v_id.clone(),
+            Indexed {
+                entry,
+                indexed_at: self.tick,
+            },
+        );
+    }
+
+    /// Remove an entry by its `v_id`.
+    pub fn remove(&mut self, v_id: &str) {
+        if let Some(existing) = self.entries.remove(v_id) {
+            self.recency.remove(&existing.indexed_at);
+            self.remove_terms(&existing.entry);
+        }
+    }
+
+    /// Remove the least recently indexed entry.
+    fn evict(&mut self) {
+        if let Some((_, v_id)) = self.recency.pop_first() {
+            if let Some(existing) = self.entries.remove(&v_id) {
+                self.remove_terms(&existing.entry);
+            }
+        }
+    }
+
+    fn insert_terms(&mut self, entry: &SearchEntry) {
+        for (term, field) in terms_of(entry) {
+            self.lengths
+                .entry(term.chars().count())
+                .or_default()
+                .insert(term.clone());
+            self.terms
+                .entry(term)
+                .or_default()
+                .insert((entry.v_id.clone(), field));
+        }
+    }
+
+    fn remove_terms(&mut self, entry: &SearchEntry) {
+        for (term, field) in terms_of(entry) {
+            if let Some(postings) = self.terms.get_mut(&term) {
+                postings.remove(&(entry.v_id.clone(), field));
+                if postings.is_empty() {
+                    self.terms.remove(&term);
+                    let length = term.chars().count();
+                    if let Some(terms) = self.lengths.get_mut(&length) {
+                        terms.remove(&term);
+                        if terms.is_empty() {
+                            self.lengths.remove(&length);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Search `keyword` with prefix and fuzzy matching.
+    /// Results are ranked by score (desc), only the best match per entry is kept.
+    /// If `platforms` is not empty, only entries on those platforms are returned.
+    pub fn search(&self, keyword: &str, platforms: &[Platform], limit: usize) -> Vec<SearchHit> {
+        let keyword = normalize_term(keyword);
+        if keyword.is_empty() || limit == 0 {
+            return vec![];
+        }
+
+        let mut best: HashMap<String, SearchHit> = HashMap::new();
+        let mut consider = |v_id: &String, field: SearchField, kind: MatchKind, base: f64| {
+            let entry = match self.entries.get(v_id) {
+                Some(indexed) => &indexed.entry,
+                None => return,
+            };
+            if !platforms.is_empty() && !platforms.contains(&entry.platform) {
+                return;
+            }
+            let score = base * field.weight();
+            let replace = best.get(v_id).is_none_or(|hit| hit.score < score);
+            if replace {
+                best.insert(
+                    v_id.clone(),
+                    SearchHit {
+                        entry: entry.clone(),
+                        score,
+                        field,
+                        kind,
+                    },
+                );
+            }
+        };
+
+        // Exact and prefix matches: range scan from `keyword`.
+        for (term, postings) in self.terms.range(keyword.clone()..) {
+            if !term.starts_with(&keyword) {
+                break;
+            }
+            let (kind, base) = if term == &keyword {
+                (MatchKind::Exact, 1.0)
+            } else {
+                // Shorter completions rank higher: "vitalik" beats "vitalik-fan-club" for "vita".
+                let ratio = keyword.chars().count() as f64 / term.chars().count() as f64;
+                (MatchKind::Prefix, 0.5 + 0.3 * ratio)
+            };
+            for (v_id, field) in postings {
+                consider(v_id, *field, kind, base);
+            }
+        }
+
+        // Fuzzy matches: bounded edit distance over terms of similar length.
+        let keyword_len = keyword.chars().count();
+        if keyword_len >= MIN_FUZZY_LENGTH {
+            let lengths = keyword_len - MAX_FUZZY_DISTANCE..=keyword_len + MAX_FUZZY_DISTANCE;
+            for term in self.lengths.range(lengths).flat_map(|(_, terms)| terms) {
+                if term.starts_with(&keyword) {
+                    continue;
+                }
+                let distance = levenshtein(&keyword, term);
+                if distance == 0 || distance > MAX_FUZZY_DISTANCE {
+                    continue;
+                }
+                let base = 0.45 - 0.1 * distance as f64;
+                for (v_id, field) in self.terms.get(term).into_iter().flatten() {
+                    consider(v_id, *field, MatchKind::Fuzzy, base);
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = best.into_values().collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.entry.v_id.cmp(&b.entry.v_id))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Lowercase and trim, as all terms are stored this way.
+fn normalize_term(s: &str) -> String {
+    s.trim().trim_start_matches('@').to_lowercase()
+}
+
+/// All (term, field) pairs an entry should be found by.
+/// Besides the full value, the part before the first `.` and every word are indexed too,
+/// so that "vitalik" matches `vitalik.eth` / `vitalik.lens` / "Vitalik Buterin" exactly.
+fn terms_of(entry: &SearchEntry) -> Vec<(String, SearchField)> {
+    let mut terms = vec![];
+    let mut push = |value: &str, field: SearchField| {
+        let term = normalize_term(value);
+        if term.is_empty() {
+            return;
+        }
+        if let Some((label, _)) = term.split_once('.') {
+            if !label.is_empty() {
+                terms.push((label.to_string(), field));
+            }
+        }
+        let words: Vec<&str> = term.split_whitespace().collect();
+        if words.len() > 1 {
+            for word in words {
+                terms.push((word.to_string(), field));
+            }
+        }
+        terms.push((term, field));
+    };
+    push(&entry.identity, SearchField::Identity);
+    if let Some(display_name) = &entry.display_name {
+        push(display_name, SearchField::DisplayName);
+    }
+    if let Some(uid) = &entry.uid {
+        push(uid, SearchField::Uid);
+    }
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+/// Classic Levenshtein distance over chars.
+pub(crate) fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr: Vec<usize> = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Add every `Identity` vertex in `edges` into the global index.
+pub async fn index_edges(edges: &[EdgeWrapperEnum]) {
+    let entries: Vec<SearchEntry> = edges
+        .iter()
+        .flat_map(|edge| vec![edge.source(), edge.target()])
+        .filter_map(|vertex| vertex.as_any().downcast_ref::<Identity>())
+        .map(SearchEntry::from)
+        .collect();
+    index_entries(entries).await;
+}
+
+/// Add `entries` into the global index.
+pub async fn index_entries(entries: Vec<SearchEntry>) {
+    if entries.is_empty() {
+        return;
+    }
+    let mut index = SEARCH_INDEX.write().await;
+    for entry in entries {
+        index.upsert(entry);
+    }
+    trace!(indexed = index.len(), "Search index updated.");
+}
+
+/// Search the global index.
+pub async fn search(keyword: &str, platforms: &[Platform], limit: usize) -> Vec<SearchHit> {
+    SEARCH_INDEX.read().await.search(keyword, platforms, limit)
+}
+
+#[derive(Debug, Deserialize)]
+struct WarmUpResponse {
+    #[serde(flatten)]
+    base: BaseResponse,
+    results: Option<Vec<WarmUpVertex>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WarmUpVertex {
+    v_id: String,
+    attributes: WarmUpAttributes,
+}
+
+/// Only the searchable attributes of an `Identity` vertex.
+#[derive(Debug, Deserialize)]
+struct WarmUpAttributes {
+    platform: Platform,
+    identity: String,
+    display_name: Option<String>,
+    uid: Option<String>,
+}
+
+impl From<WarmUpVertex> for SearchEntry {
+    fn from(vertex: WarmUpVertex) -> Self {
+        SearchEntry {
+            v_id: vertex.v_id,
+            platform: vertex.attributes.platform,
+            identity: vertex.attributes.identity,
+            display_name: vertex
+                .attributes
+                .display_name
+                .filter(|name| !name.is_empty()),
+            uid: vertex.attributes.uid.filter(|uid| !uid.is_empty()),
+        }
+    }
+}
+
+/// Load at most `limit` most recently updated identities from TigerGraph.
+async fn load_identities(limit: usize) -> Result<Vec<SearchEntry>, Error> {
+    let client = make_http_client();
+    // Builtins: http://server:9000/graph/{GraphName}/vertices/{VertexName}?select=...&sort=...
+    let uri: http::Uri = format!(
+        "{}/graph/{}/vertices/Identities?select={}&sort=-updated_at&limit={}",
+        C.tdb.host,
+        Graph::SocialGraph,
+        "platform,identity,display_name,uid",
+        limit,
+    )
+    .parse()
+    .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+    let req = hyper::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Authorization", Graph::SocialGraph.token())
+        .body(Body::empty())
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+    let mut resp = timed_request(&client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "load search index | Fail to request: {:?}",
+            err.to_string()
+        ))
+    })?;
+    let r = parse_body::<WarmUpResponse>(&mut resp).await?;
+    if r.base.error {
+        let err_message = format!(
+            "load search index error | Code: {:?}, Message: {:?}",
+            r.base.code, r.base.message
+        );
+        error!(err_message);
+        return Err(Error::General(err_message, resp.status()));
+    }
+    Ok(r.results
+        .unwrap_or_default()
+        .into_iter()
+        .map(SearchEntry::from)
+        .collect())
+}
+
+/// Fill the global index with identities already saved in TigerGraph,
+/// up to `[search] warm_up` of them. Should be spawned once at startup.
+pub async fn warm_up() {
+    let limit = C
+        .search
+        .as_ref()
+        .and_then(|search| search.warm_up)
+        .unwrap_or_else(capacity)
+        .min(capacity());
+    if limit == 0 {
+        return;
+    }
+    match load_identities(limit).await {
+        Ok(entries) => {
+            let loaded = entries.len();
+            // Oldest first, so that the most recently updated ones are evicted last.
+            index_entries(entries.into_iter().rev().collect()).await;
+            info!(loaded, "Search index loaded");
+        }
+        Err(err) => warn!(err = err.to_string(), "Failed to load search index"),
+    }
+}
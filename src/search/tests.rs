@@ -0,0 +1,111 @@
+use super::*;
+
+fn entry(platform: Platform, identity: &str, display_name: Option<&str>) -> SearchEntry {
+    SearchEntry {
+        v_id: format!("{},{}", platform, identity),
+        platform,
+        identity: identity.to_string(),
+        display_name: display_name.map(String::from),
+        uid: None,
+    }
+}
+
+fn fixture() -> SearchIndex {
+    let mut index = SearchIndex::default();
+    index.upsert(entry(Platform::ENS, "vitalik.eth", Some("vitalik.eth")));
+    index.upsert(entry(
+        Platform::Farcaster,
+        "vitalik.eth",
+        Some("Vitalik Buterin"),
+    ));
+    index.upsert(entry(
+        Platform::Twitter,
+        "vitalikbuterin",
+        Some("vitalik.eth"),
+    ));
+    index.upsert(entry(
+        Platform::Ethereum,
+        "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+        Some("vitalik.eth"),
+    ));
+    index.upsert(entry(Platform::Lens, "stani.lens", None));
+    index
+}
+
+#[test]
+fn test_search_prefix() {
+    let index = fixture();
+    let hits = index.search("vital", &[], 10);
+    assert_eq!(hits.len(), 4);
+    assert!(hits.iter().all(|hit| hit.kind == MatchKind::Prefix));
+    // `identity` hits rank above `display_name` hits.
+    assert_eq!(hits[0].field, SearchField::Identity);
+}
+
+#[test]
+fn test_search_exact_label() {
+    let index = fixture();
+    let hits = index.search("stani", &[], 10);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entry.platform, Platform::Lens);
+    assert_eq!(hits[0].kind, MatchKind::Exact);
+}
+
+#[test]
+fn test_search_fuzzy() {
+    let index = fixture();
+    let hits = index.search("vitalk", &[], 10);
+    assert!(!hits.is_empty());
+    assert!(hits.iter().all(|hit| hit.kind == MatchKind::Fuzzy));
+}
+
+#[test]
+fn test_search_platform_filter() {
+    let index = fixture();
+    let hits = index.search("vitalik", &[Platform::Twitter], 10);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].entry.identity, "vitalikbuterin");
+}
+
+#[test]
+fn test_search_upsert_replaces_terms() {
+    let mut index = fixture();
+    index.upsert(entry(Platform::Lens, "stani.lens", Some("Stani Kulechov")));
+    assert_eq!(index.len(), 5);
+    let hits = index.search("kulechov", &[], 10);
+    assert_eq!(hits.len(), 1);
+
+    index.upsert(entry(Platform::Lens, "stani.lens", None));
+    assert!(index.search("kulechov", &[], 10).is_empty());
+
+    index.remove("lens,stani.lens");
+    assert!(index.search("stani", &[], 10).is_empty());
+}
+
+#[test]
+fn test_levenshtein() {
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+    assert_eq!(levenshtein("vitalik", "vitalik"), 0);
+}
+
+#[test]
+fn test_search_index_capacity() {
+    let mut index = SearchIndex::new(2);
+    index.upsert(entry(Platform::Lens, "stani.lens", None));
+    index.upsert(entry(Platform::ENS, "vitalik.eth", None));
+    // Indexed again, so "stani.lens" is more recent than "vitalik.eth" now.
+    index.upsert(entry(Platform::Lens, "stani.lens", None));
+    index.upsert(entry(Platform::Farcaster, "dwr.eth", None));
+
+    assert_eq!(index.len(), 2);
+    assert!(index.search("vitalik", &[], 10).is_empty());
+    assert_eq!(index.search("stani", &[], 10).len(), 1);
+    assert_eq!(index.search("dwr", &[], 10).len(), 1);
+    // Terms of evicted entries are no longer fuzzy-matched either.
+    assert!(index.search("vitalk", &[], 10).is_empty());
+
+    let mut disabled = SearchIndex::new(0);
+    disabled.upsert(entry(Platform::Lens, "stani.lens", None));
+    assert!(disabled.is_empty());
+}
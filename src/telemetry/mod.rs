@@ -0,0 +1,121 @@
+//! Optional OpenTelemetry export of `tracing` spans, configured by `[tracing]` section.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{config::C, error::Error};
+use http::{HeaderMap, StatusCode};
+use opentelemetry::{global, propagation::Extractor, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    runtime,
+    trace::{self, Sampler, Tracer, TracerProvider},
+    Resource,
+};
+use std::sync::OnceLock;
+use tracing::{Span, Subscriber};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Default of `[tracing] service_name`.
+const DEFAULT_SERVICE_NAME: &str = "relation_server";
+
+/// Set once OTLP export is enabled, to flush spans on demand.
+static PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+/// `tracing` layer exporting spans to the OTLP collector in `[tracing]` section.
+/// `None` if export is not configured.
+pub fn layer<S>() -> Result<Option<OpenTelemetryLayer<S, Tracer>>, Error>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let Some(tracing_config) = C.tracing.as_ref() else {
+        return Ok(None);
+    };
+    let service_name = tracing_config
+        .service_name
+        .clone()
+        .unwrap_or(DEFAULT_SERVICE_NAME.to_string());
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&tracing_config.otlp_endpoint),
+        )
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler(tracing_config.sample_ratio)?)
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    service_name,
+                )])),
+        )
+        .install_batch(runtime::Tokio)
+        .map_err(|err| {
+            Error::General(
+                format!("Failed to set up OTLP exporter: {}", err),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })?;
+    if let Some(provider) = tracer.provider() {
+        let _ = PROVIDER.set(provider);
+    }
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Sample `ratio` of new traces, or follow the decision of parent span if there is one.
+fn sampler(ratio: Option<f64>) -> Result<Sampler, Error> {
+    let ratio = ratio.unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(Error::General(
+            format!(
+                "[tracing] sample_ratio should be in 0.0 ~ 1.0, got {}",
+                ratio
+            ),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ));
+    }
+    Ok(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        ratio,
+    ))))
+}
+
+/// Make `span` a child of the trace context (`traceparent` header) of an incoming request.
+pub fn set_parent(span: &Span, headers: &HeaderMap) {
+    let parent =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    span.set_parent(parent);
+}
+
+/// Export all finished spans now. A Lambda invocation should call this before returning,
+/// since the batch exporter won't run while the runtime is frozen.
+pub async fn flush() {
+    if let Some(provider) = PROVIDER.get().cloned() {
+        // `force_flush` blocks until the exporter is done.
+        let _ = tokio::task::spawn_blocking(move || provider.force_flush()).await;
+    }
+}
+
+/// Export remaining spans before the process exits.
+pub fn shutdown() {
+    if PROVIDER.get().is_some() {
+        global::shutdown_tracer_provider();
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
@@ -0,0 +1,33 @@
+use super::*;
+use opentelemetry::{propagation::TextMapPropagator, trace::TraceContextExt};
+
+#[test]
+fn test_sampler() {
+    assert!(sampler(None).is_ok());
+    assert!(sampler(Some(0.0)).is_ok());
+    assert!(sampler(Some(0.25)).is_ok());
+    assert!(sampler(Some(1.5)).is_err());
+    assert!(sampler(Some(-0.1)).is_err());
+}
+
+#[test]
+fn test_extract_trace_context() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "traceparent",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+            .parse()
+            .unwrap(),
+    );
+    let context = TraceContextPropagator::new().extract(&HeaderExtractor(&headers));
+    let span_context = context.span().span_context().clone();
+    assert!(span_context.is_remote());
+    assert!(span_context.is_sampled());
+    assert_eq!(
+        span_context.trace_id().to_string(),
+        "4bf92f3577b34da6a3ce929d0e0e4736"
+    );
+
+    let context = TraceContextPropagator::new().extract(&HeaderExtractor(&HeaderMap::new()));
+    assert!(!context.span().span_context().is_valid());
+}
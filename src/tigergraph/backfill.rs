@@ -0,0 +1,123 @@
+//! Domains stored before names were normalized (see `upstream::normalize_name`) can't be
+//! found by their canonical form. The backfill deletes them with their edges, and fetches
+//! them again from upstreams in canonical form.
+
+use crate::{
+    cache,
+    config::C,
+    error::Error,
+    tigergraph::{timed_request, BaseResponse, Graph},
+    upstream::{fetch_all, handle_suffix, DomainNameSystem, Platform, Target},
+    util::{make_http_client, parse_body},
+};
+use http::uri::InvalidUri;
+use hyper::{client::HttpConnector, Body, Client, Method};
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+use tracing::{error, info, warn};
+
+/// Max domains backfilled per TigerGraph query if not given.
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Deserialize)]
+struct DeleteNonCanonicalResponse {
+    #[serde(flatten)]
+    base: BaseResponse,
+    results: Option<Vec<DeleteNonCanonicalResult>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DeleteNonCanonicalResult {
+    /// Names of the domains deleted.
+    names: Vec<String>,
+    /// Vertex ids of the domains deleted.
+    deleted: Vec<String>,
+}
+
+/// Platforms whose identities are domain names.
+pub fn domain_platforms() -> Vec<Platform> {
+    let mut platforms: Vec<Platform> = DomainNameSystem::iter()
+        .map(Platform::from)
+        .filter(|platform| *platform != Platform::Unknown)
+        .collect();
+    platforms.dedup();
+    platforms
+}
+
+/// Delete at most `limit` domains on `platform` whose names are not in canonical form.
+/// Returns their names, and their vertex ids.
+pub async fn delete_non_canonical_domains(
+    client: &Client<HttpConnector>,
+    platform: Platform,
+    limit: usize,
+) -> Result<(Vec<String>, Vec<String>), Error> {
+    let suffix_pattern = match handle_suffix(platform) {
+        Some(suffix) => format!("%{}", suffix),
+        None => "%".to_string(),
+    };
+    let uri: http::Uri = format!(
+        "{}/query/{}/delete_non_canonical_domains?platform={}&suffix_pattern={}&lim={}",
+        C.tdb.host,
+        Graph::SocialGraph,
+        platform,
+        urlencoding::encode(&suffix_pattern),
+        limit,
+    )
+    .parse()
+    .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+    let req = hyper::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Authorization", Graph::SocialGraph.token())
+        .body(Body::empty())
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "delete_non_canonical_domains | Fail to request: {:?}",
+            err.to_string()
+        ))
+    })?;
+
+    let r = parse_body::<DeleteNonCanonicalResponse>(&mut resp).await?;
+    if r.base.error {
+        let err_message = format!(
+            "delete_non_canonical_domains error | Code: {:?}, Message: {:?}",
+            r.base.code, r.base.message
+        );
+        error!(err_message);
+        return Err(Error::General(err_message, resp.status()));
+    }
+    match r.results.and_then(|results| results.into_iter().next()) {
+        Some(result) => Ok((result.names, result.deleted)),
+        None => Ok((vec![], vec![])),
+    }
+}
+
+/// Replace non-canonical domains on `platform` by their canonical form, `batch_size` at a time.
+/// Returns the amount of domains replaced.
+pub async fn backfill_platform(platform: Platform, batch_size: usize) -> Result<usize, Error> {
+    let client = make_http_client();
+    let mut total = 0;
+    loop {
+        let (names, deleted) = delete_non_canonical_domains(&client, platform, batch_size).await?;
+        cache::invalidate(&deleted);
+        total += names.len();
+        let done = names.len() < batch_size;
+        let targets: Vec<Target> = names
+            .iter()
+            .map(|name| Target::from_platform_identity(platform, name))
+            .collect();
+        if targets.is_empty() {
+            break;
+        }
+        if let Err(err) = fetch_all(targets, Some(1)).await {
+            // Deleted already, so they are fetched again when queried next time.
+            warn!(%platform, err = err.to_string(), "Backfill: fail to refetch");
+        }
+        if done {
+            break;
+        }
+    }
+    info!(%platform, total, "Backfill: domains normalized");
+    Ok(total)
+}
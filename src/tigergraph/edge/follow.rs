@@ -0,0 +1,231 @@
+use crate::{
+    error::Error,
+    tigergraph::{
+        edge::{Edge, EdgeRecord, EdgeWrapper, FromWithParams, Wrapper},
+        vertex::{Identity, Vertex, VertexRecord},
+        Attribute, OpCode, Transfer,
+    },
+    upstream::DataSource,
+    util::{naive_datetime_from_string, naive_datetime_to_string, naive_now},
+};
+
+use chrono::NaiveDateTime;
+use hyper::{client::HttpConnector, Client};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_json::value::{Map, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const FOLLOW: &str = "Follow";
+pub const IS_DIRECTED: bool = true;
+
+/// Social follow between two `Identity`s, e.g. a Farcaster user following another one.
+/// It is saved between the `IdentitiesGraph`s of both identities, and `original_from`
+/// / `original_to` keep which identities of them are following / followed.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct Follow {
+    /// Data source (upstream) which provides this follow.
+    pub source: DataSource,
+    /// Primary key of the following identity. Filled by `wrapper()`.
+    pub original_from: String,
+    /// Primary key of the followed identity. Filled by `wrapper()`.
+    pub original_to: String,
+    /// When this follow is fetched by us RelationService.
+    #[serde(deserialize_with = "naive_datetime_from_string")]
+    #[serde(serialize_with = "naive_datetime_to_string")]
+    pub updated_at: NaiveDateTime,
+}
+
+impl Default for Follow {
+    fn default() -> Self {
+        Self {
+            source: DataSource::default(),
+            original_from: "".to_string(),
+            original_to: "".to_string(),
+            updated_at: naive_now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowRecord(pub EdgeRecord<Follow>);
+
+impl FromWithParams<Follow> for EdgeRecord<Follow> {
+    fn from_with_params(
+        e_type: String,
+        directed: bool,
+        from_id: String,
+        from_type: String,
+        to_id: String,
+        to_type: String,
+        attributes: Follow,
+    ) -> Self {
+        EdgeRecord {
+            e_type,
+            directed,
+            from_id,
+            from_type,
+            to_id,
+            to_type,
+            discriminator: None,
+            attributes,
+        }
+    }
+}
+
+impl From<EdgeRecord<Follow>> for FollowRecord {
+    fn from(record: EdgeRecord<Follow>) -> Self {
+        FollowRecord(record)
+    }
+}
+
+impl std::ops::Deref for FollowRecord {
+    type Target = EdgeRecord<Follow>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for FollowRecord {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl std::ops::Deref for EdgeRecord<Follow> {
+    type Target = Follow;
+
+    fn deref(&self) -> &Self::Target {
+        &self.attributes
+    }
+}
+
+impl std::ops::DerefMut for EdgeRecord<Follow> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.attributes
+    }
+}
+
+impl Transfer for FollowRecord {
+    fn to_attributes_map(&self) -> HashMap<String, Attribute> {
+        let mut attributes_map = HashMap::new();
+        attributes_map.insert(
+            "original_from".to_string(),
+            Attribute {
+                value: json!(self.attributes.original_from),
+                op: None,
+            },
+        );
+        attributes_map.insert(
+            "original_to".to_string(),
+            Attribute {
+                value: json!(self.attributes.original_to),
+                op: None,
+            },
+        );
+        attributes_map.insert(
+            "source".to_string(),
+            Attribute {
+                value: json!(self.attributes.source.to_string()),
+                op: None,
+            },
+        );
+        attributes_map.insert(
+            "updated_at".to_string(),
+            Attribute {
+                value: json!(self.attributes.updated_at),
+                op: Some(OpCode::Max),
+            },
+        );
+        attributes_map
+    }
+
+    fn to_json_value(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("original_from".to_string(), json!(self.original_from));
+        map.insert("original_to".to_string(), json!(self.original_to));
+        map.insert("source".to_string(), json!(self.source));
+        map.insert("updated_at".to_string(), json!(self.updated_at));
+        map
+    }
+}
+
+impl Wrapper<FollowRecord, Identity, Identity> for Follow {
+    fn wrapper(
+        &self,
+        from: &Identity,
+        to: &Identity,
+        name: &str,
+    ) -> EdgeWrapper<FollowRecord, Identity, Identity> {
+        let mut follow = self.to_owned();
+        follow.original_from = from.primary_key();
+        follow.original_to = to.primary_key();
+        let record = EdgeRecord::from_with_params(
+            name.to_string(),
+            IS_DIRECTED,
+            from.primary_key(),
+            from.vertex_type(),
+            to.primary_key(),
+            to.vertex_type(),
+            follow,
+        );
+        EdgeWrapper {
+            edge: FollowRecord(record),
+            source: from.to_owned(),
+            target: to.to_owned(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Edge<Identity, Identity, FollowRecord> for FollowRecord {
+    fn e_type(&self) -> String {
+        self.e_type.clone()
+    }
+
+    fn directed(&self) -> bool {
+        // TODO: query from server is the best solution
+        self.directed
+    }
+
+    /// Find an edge by UUID.
+    async fn find_by_uuid(
+        _client: &Client<HttpConnector>,
+        _uuid: &Uuid,
+    ) -> Result<Option<FollowRecord>, Error> {
+        todo!()
+    }
+
+    /// Find `EdgeRecord` by source and target
+    async fn find_by_from_to(
+        &self,
+        _client: &Client<HttpConnector>,
+        _from: &VertexRecord<Identity>,
+        _to: &VertexRecord<Identity>,
+        _filter: Option<HashMap<String, String>>,
+    ) -> Result<Option<Vec<FollowRecord>>, Error> {
+        todo!()
+    }
+
+    /// Connect 2 vertex.
+    async fn connect(
+        &self,
+        _client: &Client<HttpConnector>,
+        _from: &Identity,
+        _to: &Identity,
+    ) -> Result<(), Error> {
+        todo!()
+    }
+
+    /// Connect 2 vertex. For digraph and has reverse edge.
+    async fn connect_reverse(
+        &self,
+        _client: &Client<HttpConnector>,
+        _from: &Identity,
+        _to: &Identity,
+    ) -> Result<(), Error> {
+        todo!()
+    }
+}
@@ -3,7 +3,7 @@ use crate::{
     error::Error,
     tigergraph::{
         edge::{Edge, EdgeRecord, EdgeWrapper, FromWithParams, Wrapper},
-        upsert_graph,
+        timed_request, upsert_graph,
         vertex::{contract::VERTEX_NAME as CONTRACTS, Contract, Identity, Vertex, VertexRecord},
         Attribute, BaseResponse, Edges, Graph, OpCode, Transfer, UpsertGraph,
     },
@@ -500,8 +500,8 @@ impl Hold {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query find_by_from_to | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -555,8 +555,8 @@ impl Hold {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query holder | Fail to request: {:?}",
                 err.to_string()
             ))
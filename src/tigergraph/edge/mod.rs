@@ -1,12 +1,15 @@
+pub mod follow;
 pub mod hold;
 pub mod part_of_identities_graph;
 pub mod proof;
 pub mod relation;
 pub mod resolve;
+pub use follow::{Follow, FollowRecord, FOLLOW};
 pub use hold::{Hold, HoldRecord, HOLD_CONTRACT, HOLD_IDENTITY};
 pub use part_of_identities_graph::{HyperEdge, HyperEdgeRecord, HYPER_EDGE, HYPER_EDGE_REVERSE};
 pub use proof::{
-    Proof, ProofRecord, EDGE_NAME as PROOF_EDGE, REVERSE_EDGE_NAME as PROOF_REVERSE_EDGE,
+    Proof, ProofRecord, CLAIM_EDGE_NAME as CLAIM, EDGE_NAME as PROOF_EDGE,
+    REVERSE_EDGE_NAME as PROOF_REVERSE_EDGE,
 };
 pub use relation::{RelationUniqueTX, RelationUniqueTXRecord, EDGE_NAME as RELATION_UNIQUE_TX};
 pub use resolve::{
@@ -23,6 +23,10 @@ use uuid::Uuid;
 
 pub const EDGE_NAME: &str = "Proof_Forward";
 pub const REVERSE_EDGE_NAME: &str = "Proof_Backward";
+/// A `Proof` only asserted by its source identity, e.g. a text record of an ENS name.
+/// Saved apart from `Proof_Forward`: only the `claims` query reads it, and its target does not
+/// join the identity graph of its source.
+pub const CLAIM_EDGE_NAME: &str = "Claim";
 pub const IS_DIRECTED: bool = true;
 
 /// Edge to connect two `Identity`s.
@@ -2,9 +2,10 @@ use crate::{
     config::C,
     error::Error,
     tigergraph::{
-        edge::{Edge, EdgeRecord},
+        edge::{Edge, EdgeRecord, EdgeWrapper, FromWithParams, Wrapper},
+        timed_request,
         vertex::{Identity, Vertex, VertexRecord},
-        BaseResponse, Graph,
+        Attribute, BaseResponse, Graph, OpCode, Transfer,
     },
     util::{naive_datetime_from_string, naive_datetime_to_string, naive_now, parse_body},
 };
@@ -13,6 +14,8 @@ use chrono::NaiveDateTime;
 use http::uri::InvalidUri;
 use hyper::{client::HttpConnector, Body, Client, Method};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_json::value::{Map, Value};
 use std::collections::HashMap;
 use tracing::error;
 use uuid::Uuid;
@@ -21,12 +24,22 @@ pub const EDGE_NAME: &str = "Relation_Unique_TX";
 pub const IS_DIRECTED: bool = true;
 
 /// Edge to connect two `Identity`s.
+/// Statistics of unique transactions sent from one Ethereum address to another.
+/// Values are in Gwei, since Wei overflows `INT` in TigerGraph.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelationUniqueTX {
-    pub count: u32,
-    pub sum: u32,
-    pub max: u32,
-    pub min: u32,
+    /// Amount of transactions.
+    #[serde(rename = "tx_count")]
+    pub count: i64,
+    /// Sum of values.
+    #[serde(rename = "tx_sum")]
+    pub sum: i64,
+    /// Max value of a single transaction.
+    #[serde(rename = "tx_max")]
+    pub max: i64,
+    /// Min value of a single transaction.
+    #[serde(rename = "tx_min")]
+    pub min: i64,
     #[serde(deserialize_with = "naive_datetime_from_string")]
     #[serde(serialize_with = "naive_datetime_to_string")]
     pub updated_at: NaiveDateTime,
@@ -47,6 +60,29 @@ impl Default for RelationUniqueTX {
     }
 }
 
+impl FromWithParams<RelationUniqueTX> for EdgeRecord<RelationUniqueTX> {
+    fn from_with_params(
+        e_type: String,
+        directed: bool,
+        from_id: String,
+        from_type: String,
+        to_id: String,
+        to_type: String,
+        attributes: RelationUniqueTX,
+    ) -> Self {
+        EdgeRecord {
+            e_type,
+            directed,
+            from_id,
+            from_type,
+            to_id,
+            to_type,
+            discriminator: None,
+            attributes,
+        }
+    }
+}
+
 impl From<EdgeRecord<RelationUniqueTX>> for RelationUniqueTXRecord {
     fn from(record: EdgeRecord<RelationUniqueTX>) -> Self {
         RelationUniqueTXRecord(record)
@@ -81,6 +117,84 @@ impl std::ops::DerefMut for EdgeRecord<RelationUniqueTX> {
     }
 }
 
+impl Transfer for RelationUniqueTXRecord {
+    /// Statistics of newly processed transactions are merged into the existing ones,
+    /// so every transaction must be upserted only once.
+    fn to_attributes_map(&self) -> HashMap<String, Attribute> {
+        let mut attributes_map = HashMap::new();
+        attributes_map.insert(
+            "tx_count".to_string(),
+            Attribute {
+                value: json!(self.attributes.count),
+                op: Some(OpCode::Add),
+            },
+        );
+        attributes_map.insert(
+            "tx_sum".to_string(),
+            Attribute {
+                value: json!(self.attributes.sum),
+                op: Some(OpCode::Add),
+            },
+        );
+        attributes_map.insert(
+            "tx_max".to_string(),
+            Attribute {
+                value: json!(self.attributes.max),
+                op: Some(OpCode::Max),
+            },
+        );
+        attributes_map.insert(
+            "tx_min".to_string(),
+            Attribute {
+                value: json!(self.attributes.min),
+                op: Some(OpCode::Min),
+            },
+        );
+        attributes_map.insert(
+            "updated_at".to_string(),
+            Attribute {
+                value: json!(self.attributes.updated_at),
+                op: Some(OpCode::Max),
+            },
+        );
+        attributes_map
+    }
+
+    fn to_json_value(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("tx_count".to_string(), json!(self.count));
+        map.insert("tx_sum".to_string(), json!(self.sum));
+        map.insert("tx_max".to_string(), json!(self.max));
+        map.insert("tx_min".to_string(), json!(self.min));
+        map.insert("updated_at".to_string(), json!(self.updated_at));
+        map
+    }
+}
+
+impl Wrapper<RelationUniqueTXRecord, Identity, Identity> for RelationUniqueTX {
+    fn wrapper(
+        &self,
+        from: &Identity,
+        to: &Identity,
+        name: &str,
+    ) -> EdgeWrapper<RelationUniqueTXRecord, Identity, Identity> {
+        let record = EdgeRecord::from_with_params(
+            name.to_string(),
+            IS_DIRECTED,
+            from.primary_key(),
+            from.vertex_type(),
+            to.primary_key(),
+            to.vertex_type(),
+            self.to_owned(),
+        );
+        EdgeWrapper {
+            edge: RelationUniqueTXRecord(record),
+            source: from.to_owned(),
+            target: to.to_owned(),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl Edge<Identity, Identity, RelationUniqueTXRecord> for RelationUniqueTXRecord {
     fn e_type(&self) -> String {
@@ -180,8 +294,8 @@ impl RelationUniqueTX {
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query relation | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -233,8 +347,8 @@ impl RelationUniqueTX {
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query relation | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -3,11 +3,11 @@ use crate::{
     error::Error,
     tigergraph::{
         edge::{Edge, EdgeRecord, EdgeWrapper, FromWithParams, HoldRecord, Wrapper},
-        upsert_graph,
+        timed_request, upsert_graph,
         vertex::{Contract, Identity, IdentityRecord, Vertex, VertexRecord},
         Attribute, BaseResponse, Edges, Graph, OpCode, Transfer, UpsertGraph,
     },
-    upstream::{DataFetcher, DataSource, DomainNameSystem},
+    upstream::{normalize_name, validate, DataFetcher, DataSource, DomainNameSystem, Platform},
     util::{naive_datetime_from_string, naive_datetime_to_string, naive_now, parse_body},
 };
 
@@ -108,6 +108,23 @@ impl From<EdgeRecord<Resolve>> for ResolveRecord {
     }
 }
 
+impl ResolveRecord {
+    /// Bring `name` into its canonical form in `system`.
+    pub fn normalize(&mut self) {
+        self.0.attributes.name = normalize_name(self.system, &self.name);
+    }
+
+    /// Check if `name` is a well-formed ENS name, if in ENS or Basenames.
+    pub fn has_valid_name(&self) -> bool {
+        match self.system {
+            DomainNameSystem::ENS | DomainNameSystem::Basenames => {
+                validate(Platform::from(self.system), &self.name).is_ok()
+            }
+            _ => true,
+        }
+    }
+}
+
 impl std::ops::Deref for ResolveRecord {
     type Target = EdgeRecord<Resolve>;
 
@@ -474,7 +491,10 @@ pub enum HoldRecordObject {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct ResolveReverse {
     pub record: Resolve,
+    /// Primary name of the address it resolves to. Only set if `verified`.
     pub reverse: bool,
+    /// A reverse record claims this name, and the name resolves back to the address claiming it.
+    pub verified: bool,
 }
 
 impl std::ops::Deref for ResolveReverse {
@@ -496,6 +516,7 @@ impl From<Resolve> for ResolveReverse {
         ResolveReverse {
             record,
             reverse: false,
+            verified: false,
         }
     }
 }
@@ -534,6 +555,18 @@ impl From<ResolveReverse> for ResolveEdge {
 }
 
 impl Resolve {
+    /// New record of `name` in `system` from `source`, fetched by this service.
+    pub fn new(source: DataSource, system: DomainNameSystem, name: &str) -> Self {
+        Resolve {
+            uuid: Uuid::new_v4(),
+            source,
+            system,
+            name: name.to_string(),
+            fetcher: DataFetcher::RelationService,
+            updated_at: naive_now(),
+        }
+    }
+
     pub fn is_outdated(&self) -> bool {
         let outdated_in = Duration::try_days(1).unwrap();
         self.updated_at
@@ -563,8 +596,8 @@ impl Resolve {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query domain | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -608,10 +641,8 @@ impl Resolve {
                                                     updated_at: hold.attributes.updated_at,
                                                 }));
 
-                                            resolve_edge.reverse = domain.reverse;
-                                            resolve_edge.reverse_record = domain
-                                                .reverse_record
-                                                .and_then(|records| records.first().cloned());
+                                            // Resolving nowhere, any reverse record is spoofed.
+                                            resolve_edge.reverse = false;
                                             resolve_edge.expired_at = hold.attributes.expired_at;
                                             resolve_edge.owner = domain.owner.first().cloned();
                                             resolve_edge.resolved = None;
@@ -628,15 +659,24 @@ impl Resolve {
                                                     updated_at: record.attributes.updated_at,
                                                 }));
 
-                                            resolve_edge.reverse = domain.reverse;
-                                            resolve_edge.reverse_record = domain
-                                                .reverse_record
-                                                .and_then(|records| records.first().cloned());
                                             resolve_edge.expired_at = hold.attributes.expired_at;
                                             resolve_edge.owner = domain.owner.first().cloned();
                                             resolve_edge.resolved = domain
                                                 .resolved
                                                 .and_then(|resolves| resolves.first().cloned());
+                                            // Reverse records not resolving back are spoofed.
+                                            resolve_edge.reverse_record = domain
+                                                .reverse_record
+                                                .and_then(|records| records.first().cloned())
+                                                .filter(|record| {
+                                                    resolve_edge.resolved.as_ref().is_some_and(
+                                                        |resolved| resolved.v_id == record.v_id,
+                                                    )
+                                                });
+                                            resolve_edge.verified =
+                                                resolve_edge.reverse_record.is_some();
+                                            resolve_edge.reverse =
+                                                domain.reverse && resolve_edge.verified;
                                             resolve_edge
                                         }
                                     };
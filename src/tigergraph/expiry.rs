@@ -0,0 +1,220 @@
+//! Domain names past their grace period are released, and may be registered by anyone.
+//! A background sweeper disconnects them from their owners, resolved addresses
+//! and identity graphs. They are fetched again from upstreams when queried next time.
+//!
+//! A long-running server runs `run_sweeper`. On Lambda the runtime is frozen between
+//! invocations, so each invocation awaits `sweep_if_due` instead.
+
+use crate::{
+    cache,
+    config::C,
+    error::Error,
+    tigergraph::{timed_request, BaseResponse, Graph},
+    upstream::{DomainNameSystem, Platform},
+    util::{make_http_client, naive_now, parse_body, timestamp},
+};
+use chrono::NaiveDateTime;
+use http::uri::InvalidUri;
+use hyper::{client::HttpConnector, Body, Client, Method};
+use serde::Deserialize;
+use std::{
+    sync::atomic::{AtomicI64, Ordering},
+    time::Duration,
+};
+use strum::IntoEnumIterator;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// Seconds between sweeps if not configured.
+const DEFAULT_SWEEP_INTERVAL: u64 = 3600;
+/// Max domains swept per query if not configured.
+const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Unix timestamp when `sweep_if_due` last finished a sweep.
+static LAST_SWEEP: AtomicI64 = AtomicI64::new(0);
+
+lazy_static! {
+    /// Name system `sweep_if_due` sweeps next, as an index in `expiring_systems()`.
+    static ref SWEEP_CURSOR: Mutex<usize> = Mutex::new(0);
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DeleteExpiredResponse {
+    #[serde(flatten)]
+    base: BaseResponse,
+    results: Option<Vec<DeleteExpiredResult>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DeleteExpiredResult {
+    /// Vertex ids of the domains swept.
+    swept: Vec<String>,
+    /// Vertex ids of the identities they were connected to.
+    touched: Vec<String>,
+}
+
+/// Disconnect at most `limit` domain names on `platform` which expired before `expired_before`.
+/// Returns vertex ids of the domains swept, and of the identities they were connected to.
+pub async fn delete_expired_domains(
+    client: &Client<HttpConnector>,
+    platform: Platform,
+    expired_before: NaiveDateTime,
+    limit: usize,
+) -> Result<(Vec<String>, Vec<String>), Error> {
+    let expired_before = expired_before.format("%Y-%m-%d %H:%M:%S").to_string();
+    let uri: http::Uri = format!(
+        "{}/query/{}/delete_expired_domains?platform={}&expired_before={}&lim={}",
+        C.tdb.host,
+        Graph::SocialGraph,
+        platform,
+        urlencoding::encode(&expired_before),
+        limit,
+    )
+    .parse()
+    .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+    let req = hyper::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Authorization", Graph::SocialGraph.token())
+        .body(Body::empty())
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "delete_expired_domains | Fail to request: {:?}",
+            err.to_string()
+        ))
+    })?;
+
+    let r = parse_body::<DeleteExpiredResponse>(&mut resp).await?;
+    if r.base.error {
+        let err_message = format!(
+            "delete_expired_domains error | Code: {:?}, Message: {:?}",
+            r.base.code, r.base.message
+        );
+        error!(err_message);
+        return Err(Error::General(err_message, resp.status()));
+    }
+    match r.results.and_then(|results| results.into_iter().next()) {
+        Some(result) => Ok((result.swept, result.touched)),
+        None => Ok((vec![], vec![])),
+    }
+}
+
+/// Sweep domain names past their grace period on every name system where names expire.
+/// Returns the amount of domains swept.
+pub async fn sweep_expired_domains() -> Result<usize, Error> {
+    let client = make_http_client();
+    let batch_size = batch_size();
+    let mut total = 0;
+    for system in expiring_systems() {
+        loop {
+            let swept = sweep_batch(&client, system, batch_size).await?;
+            total += swept;
+            if swept < batch_size {
+                break;
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// Disconnect at most `batch_size` domain names of `system` past their grace period,
+/// and invalidate cached records of them and of identities they were connected to.
+/// Returns the amount of domains swept.
+async fn sweep_batch(
+    client: &Client<HttpConnector>,
+    system: DomainNameSystem,
+    batch_size: usize,
+) -> Result<usize, Error> {
+    let Some(grace_period) = system.grace_period() else {
+        return Ok(0);
+    };
+    let expired_before = naive_now() - grace_period;
+    let (swept, touched) =
+        delete_expired_domains(client, system.into(), expired_before, batch_size).await?;
+    cache::invalidate(&swept);
+    cache::invalidate(&touched);
+    Ok(swept.len())
+}
+
+/// Name systems where names expire.
+fn expiring_systems() -> Vec<DomainNameSystem> {
+    DomainNameSystem::iter()
+        .filter(|system| system.grace_period().is_some())
+        .collect()
+}
+
+/// `[expiry] batch_size`, max domains swept per query.
+fn batch_size() -> usize {
+    C.expiry
+        .as_ref()
+        .and_then(|expiry| expiry.batch_size)
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+}
+
+/// `[expiry] sweep_interval` in seconds, `None` if sweeping is disabled.
+fn sweep_interval() -> Option<u64> {
+    let interval = C
+        .expiry
+        .as_ref()
+        .and_then(|expiry| expiry.sweep_interval)
+        .unwrap_or(DEFAULT_SWEEP_INTERVAL);
+    (interval > 0).then_some(interval)
+}
+
+/// Sweep expired domain names every `[expiry] sweep_interval` seconds, until the process exits.
+/// Returns right away if it is `0`.
+pub async fn run_sweeper() {
+    let Some(interval) = sweep_interval() else {
+        info!("Expired domain sweeper disabled");
+        return;
+    };
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+    loop {
+        ticker.tick().await;
+        match sweep_expired_domains().await {
+            Ok(swept) => info!(swept, "Expired domains swept"),
+            Err(err) => warn!(err = err.to_string(), "Failed to sweep expired domains"),
+        }
+    }
+}
+
+/// Sweep one batch of expired domain names if the last sweep finished `[expiry] sweep_interval`
+/// seconds ago. For short-lived processes, where `run_sweeper` would be frozen between
+/// invocations: each invocation awaits one batch, so none is cut off halfway (before cached
+/// records are invalidated), and the sweep goes on in the following invocations until every
+/// name system is done. Each execution environment sweeps on its own.
+pub async fn sweep_if_due() {
+    let Some(interval) = sweep_interval() else {
+        return;
+    };
+    let now = timestamp();
+    if now - LAST_SWEEP.load(Ordering::SeqCst) < interval as i64 {
+        return;
+    }
+    // Another invocation is sweeping.
+    let Ok(mut cursor) = SWEEP_CURSOR.try_lock() else {
+        return;
+    };
+    let systems = expiring_systems();
+    let Some(&system) = systems.get(*cursor) else {
+        *cursor = 0;
+        LAST_SWEEP.store(now, Ordering::SeqCst);
+        return;
+    };
+    let batch_size = batch_size();
+    match sweep_batch(&make_http_client(), system, batch_size).await {
+        Ok(swept) => {
+            info!(%system, swept, "Expired domains swept");
+            if swept < batch_size {
+                *cursor += 1;
+            }
+            if *cursor >= systems.len() {
+                *cursor = 0;
+                LAST_SWEEP.store(now, Ordering::SeqCst);
+            }
+        }
+        // Retried by the next invocation.
+        Err(err) => warn!(%system, err = err.to_string(), "Failed to sweep expired domains"),
+    }
+}
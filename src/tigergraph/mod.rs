@@ -1,35 +1,45 @@
+pub mod backfill;
 pub mod edge;
+pub mod expiry;
 mod tests;
+pub mod unique_tx;
 pub mod upsert;
 pub mod vertex;
 
 use crate::{
+    cache,
     config::C,
     error::Error,
+    metrics::{tigergraph_query_name, TIGERGRAPH_QUERY_DURATION},
+    search,
     tigergraph::{
         edge::{
-            Edge, Hold, HoldRecord, HyperEdgeRecord, Proof, ProofRecord, Resolve, ResolveRecord,
-            Wrapper,
+            Edge, FollowRecord, Hold, HoldRecord, HyperEdgeRecord, Proof, ProofRecord, Resolve,
+            ResolveRecord, Wrapper,
         },
         edge::{
-            HOLD_CONTRACT, HOLD_IDENTITY, HYPER_EDGE_REVERSE, PROOF_EDGE, PROOF_REVERSE_EDGE,
-            RESOLVE, RESOLVE_CONTRACT, REVERSE_RESOLVE, REVERSE_RESOLVE_CONTRACT,
+            CLAIM, FOLLOW, HOLD_CONTRACT, HOLD_IDENTITY, HYPER_EDGE_REVERSE, PROOF_EDGE,
+            PROOF_REVERSE_EDGE, RESOLVE, RESOLVE_CONTRACT, REVERSE_RESOLVE,
+            REVERSE_RESOLVE_CONTRACT,
         },
-        vertex::{Contract, IdentitiesGraph, Identity, Vertex},
+        vertex::{Contract, FromWithJsonValue, IdentitiesGraph, Identity, Vertex, VertexRecord},
     },
     util::{make_client, parse_body},
 };
 
 use http::uri::InvalidUri;
 use hyper::Method;
-use hyper::{client::HttpConnector, Body, Client};
+use hyper::{
+    client::{connect::Connect, HttpConnector},
+    Body, Client,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::value::{Map, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use strum_macros::{Display, EnumIter, EnumString};
-use tracing::{error, trace};
+use tracing::{error, info_span, trace, warn, Instrument};
 use uuid::Uuid;
 
 #[derive(
@@ -117,6 +127,25 @@ impl Graph {
     }
 }
 
+/// `client.request(req)` in a `tigergraph` span, timed into `TIGERGRAPH_QUERY_DURATION`
+/// by installed query.
+pub async fn timed_request<C>(
+    client: &Client<C>,
+    req: hyper::Request<Body>,
+) -> Result<hyper::Response<Body>, hyper::Error>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    let query = tigergraph_query_name(req.uri().path());
+    let span = info_span!("tigergraph", query);
+    let timer = TIGERGRAPH_QUERY_DURATION
+        .with_label_values(&[query])
+        .start_timer();
+    let resp = client.request(req).instrument(span).await;
+    timer.observe_duration();
+    resp
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IdAllocation {
     pub graph_id: String,
@@ -139,10 +168,14 @@ pub struct IdAllocationResult {
     pub updated_nanosecond: i64,
 }
 
+/// id_allocation service runs on the same host of TigerGraph RESTPP, port 9002.
+fn id_allocation_url() -> String {
+    format!("{}:{}", C.tdb.host.trim_end_matches(":9000"), "9002")
+}
+
 pub async fn id_allocation(payload: &IdAllocation) -> Result<IdAllocationResult, Error> {
     let http_client = make_client();
-    let id_allocation_url = format!("{}:{}", C.tdb.host.trim_end_matches(":9000"), "9002");
-    let uri: http::Uri = format!("{}/id_allocation/allocation", id_allocation_url,)
+    let uri: http::Uri = format!("{}/id_allocation/allocation", id_allocation_url())
         .parse()
         .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
 
@@ -153,8 +186,8 @@ pub async fn id_allocation(payload: &IdAllocation) -> Result<IdAllocationResult,
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
 
-    let mut resp = http_client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(&http_client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail call allocation: {:?}",
             err.to_string()
         ))
@@ -202,10 +235,108 @@ pub async fn id_allocation(payload: &IdAllocation) -> Result<IdAllocationResult,
     }
 }
 
+/// Check if id_allocation service is reachable. Any HTTP response counts.
+pub async fn ping_id_allocation() -> Result<(), Error> {
+    let http_client = make_client();
+    let uri: http::Uri = id_allocation_url()
+        .parse()
+        .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+    let req = hyper::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .body(Body::empty())
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+    timed_request(&http_client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "TigerGraph | Fail to reach id_allocation: {:?}",
+            err.to_string()
+        ))
+    })?;
+    Ok(())
+}
+
+/// Names of queries installed on `graph`.
+/// Fails if TigerGraph is unreachable, or the token of `graph` is rejected.
+pub async fn installed_queries(
+    client: &Client<HttpConnector>,
+    graph: Graph,
+) -> Result<HashSet<String>, Error> {
+    let uri: http::Uri = format!(
+        "{}/endpoints/{}?builtin=false&dynamic=true&static=false",
+        C.tdb.host, graph
+    )
+    .parse()
+    .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+    let req = hyper::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Authorization", graph.token())
+        .body(Body::empty())
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "TigerGraph | Fail to request endpoints: {:?}",
+            err.to_string()
+        ))
+    })?;
+    let status = resp.status();
+    let endpoints: Value = parse_body(&mut resp).await?;
+    if !status.is_success() || endpoints["error"].as_bool().unwrap_or(false) {
+        let err_message = format!(
+            "TigerGraph endpoints of {} error | Status: {}, Message: {}",
+            graph,
+            status,
+            endpoints["message"].as_str().unwrap_or_default()
+        );
+        error!(err_message);
+        return Err(Error::General(err_message, status));
+    }
+    Ok(parse_installed_queries(&graph, &endpoints))
+}
+
+/// Pick query names of `graph` out of `/endpoints` response,
+/// whose keys are like `GET /query/SocialGraph/neighbors`.
+fn parse_installed_queries(graph: &Graph, endpoints: &Value) -> HashSet<String> {
+    let endpoints = match endpoints.get("results") {
+        Some(results) if results.is_object() => results,
+        _ => endpoints,
+    };
+    let prefix = format!("/query/{}/", graph);
+    endpoints
+        .as_object()
+        .map(|endpoints| {
+            endpoints
+                .keys()
+                .filter_map(|key| key.split_whitespace().last())
+                .filter_map(|path| path.strip_prefix(&prefix))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub async fn batch_upsert(
     client: &Client<HttpConnector>,
     edges: Vec<EdgeWrapperEnum>,
 ) -> Result<(), Error> {
+    // Upstreams may give the same identity in different forms.
+    // Follows do not join identities into one graph, so they are saved apart from others.
+    let (follows, edges): (Vec<EdgeWrapperEnum>, Vec<EdgeWrapperEnum>) = edges
+        .into_iter()
+        .map(EdgeWrapperEnum::normalized)
+        .filter(|edge| {
+            let valid = edge.has_valid_names();
+            if !valid {
+                warn!(
+                    source = edge.source().primary_key(),
+                    target = edge.target().primary_key(),
+                    "Skip edge with invalid ENS name"
+                );
+            }
+            valid
+        })
+        .partition(|edge| edge.e_type() == FOLLOW);
     // let json_raw = serde_json::to_string(&edges).map_err(|err| Error::JSONParseError(err))?;
     // trace!("edges = {}", json_raw);
     let mut graph: UpsertGraph = BatchEdges(edges.clone()).into();
@@ -251,8 +382,92 @@ pub async fn batch_upsert(
     // let json_raw = serde_json::to_string(&graph).map_err(|err| Error::JSONParseError(err))?;
     // trace!("graph = {}", json_raw);
     upsert_graph(client, &graph, Graph::SocialGraph).await?;
+    let mut affected: Vec<String> = edges
+        .iter()
+        .flat_map(|edge| vec![edge.source().primary_key(), edge.target().primary_key()])
+        .collect();
+    affected.push(final_identity_graph);
+    cache::invalidate(&affected);
+    search::index_edges(&edges).await;
     let contracts_req: ContractEdgesRequest = BatchEdges(edges).try_into()?;
     insert_contract_connection(client, &contracts_req, Graph::SocialGraph).await?;
+    save_follows(client, follows).await
+}
+
+/// Save follow edges, which do not join identities into one graph,
+/// and drop cached results of the identities they connect.
+pub async fn save_follows(
+    client: &Client<HttpConnector>,
+    follows: Vec<EdgeWrapperEnum>,
+) -> Result<(), Error> {
+    if follows.is_empty() {
+        return Ok(());
+    }
+    let follows: Vec<EdgeWrapperEnum> = follows
+        .into_iter()
+        .map(EdgeWrapperEnum::normalized)
+        .collect();
+    let follows_req: FollowEdgesRequest = BatchEdges(follows.clone()).try_into()?;
+    insert_follow_connection(client, &follows_req, Graph::SocialGraph).await?;
+    let affected: Vec<String> = follows
+        .iter()
+        .flat_map(|edge| vec![edge.source().primary_key(), edge.target().primary_key()])
+        .collect();
+    cache::invalidate(&affected);
+    search::index_edges(&follows).await;
+    Ok(())
+}
+
+/// Save follows between identities, each into their own identity graph.
+/// Identities not saved yet are saved as isolated vertices.
+pub async fn insert_follow_connection(
+    client: &Client<HttpConnector>,
+    payload: &FollowEdgesRequest,
+    graph_name: Graph,
+) -> Result<(), Error> {
+    let uri: http::Uri = format!(
+        "{}/query/{}/insert_follow_connection",
+        C.tdb.host, graph_name,
+    )
+    .parse()
+    .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+
+    let json_params = serde_json::to_string(&payload).map_err(Error::JSONParseError)?;
+    let req = hyper::Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Authorization", graph_name.token())
+        .body(Body::from(json_params))
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "TigerGraph | Fail to insert_follow_connection: {:?}",
+            err.to_string()
+        ))
+    })?;
+    let result = match parse_body::<ContractConnectionsResponse>(&mut resp).await {
+        Ok(result) => {
+            if result.base.error {
+                let err_message = format!(
+                    "TigerGraph fail to insert_follow_connection, Code: {:?}, Message: {:?}",
+                    result.base.code, result.base.message
+                );
+                error!(err_message);
+                return Err(Error::General(err_message, resp.status()));
+            }
+            result
+        }
+        Err(err) => {
+            let err_message = format!(
+                "TigerGraph insert_follow_connection parse_body error: {:?}",
+                err
+            );
+            error!(err_message);
+            return Err(err);
+        }
+    };
+    let json_raw = serde_json::to_string(&result).map_err(Error::JSONParseError)?;
+    trace!("TigerGraph insert_follow_connection {}...", json_raw);
     Ok(())
 }
 
@@ -277,8 +492,8 @@ pub async fn insert_contract_connection(
         .header("Authorization", graph_name.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to insert_contract_connection: {:?}",
             err.to_string()
         ))
@@ -322,8 +537,8 @@ pub async fn delete_vertex_and_edge(
         .header("Authorization", Graph::IdentityGraph.token())
         .body(Body::empty())
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "delete_vertex_and_edge | Fail to request: {:?}",
             err.to_string()
         ))
@@ -349,6 +564,7 @@ pub async fn delete_vertex_and_edge(
     // let json_raw = serde_json::to_string(&result).map_err(|err| Error::JSONParseError(err))?;
     // println!("{}", json_raw);
     trace!("TigerGraph  delete_vertex_and_edge...");
+    cache::invalidate(&[v_id]);
 
     Ok(())
 }
@@ -373,8 +589,8 @@ pub async fn upsert_graph(
         .header("Authorization", graph_name.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request upsert graph: {:?}",
             err.to_string()
         ))
@@ -608,6 +824,8 @@ pub enum EdgeWrapperEnum {
     ResolveContract(EdgeWrapper<ResolveRecord, Contract, Identity>),
     ReverseResolveContract(EdgeWrapper<ResolveRecord, Identity, Contract>),
     PartOfIdentitiesGraph(EdgeWrapper<HyperEdgeRecord, IdentitiesGraph, Identity>),
+    Follow(EdgeWrapper<FollowRecord, Identity, Identity>),
+    Claim(EdgeWrapper<ProofRecord, Identity, Identity>),
 }
 
 impl Transfer for EdgeWrapperEnum {
@@ -615,6 +833,7 @@ impl Transfer for EdgeWrapperEnum {
         match self {
             EdgeWrapperEnum::ProofForward(wrapper) => wrapper.edge.to_attributes_map(),
             EdgeWrapperEnum::ProofBackward(wrapper) => wrapper.edge.to_attributes_map(),
+            EdgeWrapperEnum::Claim(wrapper) => wrapper.edge.to_attributes_map(),
             EdgeWrapperEnum::HoldIdentity(wrapper) => wrapper.edge.to_attributes_map(),
             EdgeWrapperEnum::HoldContract(wrapper) => wrapper.edge.to_attributes_map(),
             EdgeWrapperEnum::Resolve(wrapper) => wrapper.edge.to_attributes_map(),
@@ -622,6 +841,7 @@ impl Transfer for EdgeWrapperEnum {
             EdgeWrapperEnum::ResolveContract(wrapper) => wrapper.edge.to_attributes_map(),
             EdgeWrapperEnum::ReverseResolveContract(wrapper) => wrapper.edge.to_attributes_map(),
             EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper) => wrapper.edge.to_attributes_map(),
+            EdgeWrapperEnum::Follow(wrapper) => wrapper.edge.to_attributes_map(),
         }
     }
 
@@ -629,6 +849,7 @@ impl Transfer for EdgeWrapperEnum {
         match self {
             EdgeWrapperEnum::ProofForward(wrapper) => wrapper.edge.to_json_value(),
             EdgeWrapperEnum::ProofBackward(wrapper) => wrapper.edge.to_json_value(),
+            EdgeWrapperEnum::Claim(wrapper) => wrapper.edge.to_json_value(),
             EdgeWrapperEnum::HoldIdentity(wrapper) => wrapper.edge.to_json_value(),
             EdgeWrapperEnum::HoldContract(wrapper) => wrapper.edge.to_json_value(),
             EdgeWrapperEnum::Resolve(wrapper) => wrapper.edge.to_json_value(),
@@ -636,6 +857,7 @@ impl Transfer for EdgeWrapperEnum {
             EdgeWrapperEnum::ResolveContract(wrapper) => wrapper.edge.to_json_value(),
             EdgeWrapperEnum::ReverseResolveContract(wrapper) => wrapper.edge.to_json_value(),
             EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper) => wrapper.edge.to_json_value(),
+            EdgeWrapperEnum::Follow(wrapper) => wrapper.edge.to_json_value(),
         }
     }
 }
@@ -645,6 +867,7 @@ impl EdgeWrapperEnum {
         match self {
             EdgeWrapperEnum::ProofForward(wrapper) => &wrapper.source,
             EdgeWrapperEnum::ProofBackward(wrapper) => &wrapper.source,
+            EdgeWrapperEnum::Claim(wrapper) => &wrapper.source,
             EdgeWrapperEnum::HoldIdentity(wrapper) => &wrapper.source,
             EdgeWrapperEnum::HoldContract(wrapper) => &wrapper.source,
             EdgeWrapperEnum::Resolve(wrapper) => &wrapper.source,
@@ -652,6 +875,7 @@ impl EdgeWrapperEnum {
             EdgeWrapperEnum::ResolveContract(wrapper) => &wrapper.source,
             EdgeWrapperEnum::ReverseResolveContract(wrapper) => &wrapper.source,
             EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper) => &wrapper.source,
+            EdgeWrapperEnum::Follow(wrapper) => &wrapper.source,
         }
     }
 
@@ -659,6 +883,7 @@ impl EdgeWrapperEnum {
         match self {
             EdgeWrapperEnum::ProofForward(wrapper) => &wrapper.target,
             EdgeWrapperEnum::ProofBackward(wrapper) => &wrapper.target,
+            EdgeWrapperEnum::Claim(wrapper) => &wrapper.target,
             EdgeWrapperEnum::HoldIdentity(wrapper) => &wrapper.target,
             EdgeWrapperEnum::HoldContract(wrapper) => &wrapper.target,
             EdgeWrapperEnum::Resolve(wrapper) => &wrapper.target,
@@ -666,6 +891,90 @@ impl EdgeWrapperEnum {
             EdgeWrapperEnum::ResolveContract(wrapper) => &wrapper.target,
             EdgeWrapperEnum::ReverseResolveContract(wrapper) => &wrapper.target,
             EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper) => &wrapper.target,
+            EdgeWrapperEnum::Follow(wrapper) => &wrapper.target,
+        }
+    }
+
+    /// Same edge, with identities and contracts on both ends in canonical form.
+    pub fn normalized(mut self) -> Self {
+        match &mut self {
+            EdgeWrapperEnum::ProofForward(wrapper)
+            | EdgeWrapperEnum::ProofBackward(wrapper)
+            | EdgeWrapperEnum::Claim(wrapper) => {
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+            }
+            EdgeWrapperEnum::HoldIdentity(wrapper) => {
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+            }
+            EdgeWrapperEnum::HoldContract(wrapper) => {
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+            }
+            EdgeWrapperEnum::Resolve(wrapper) | EdgeWrapperEnum::ReverseResolve(wrapper) => {
+                wrapper.edge.normalize();
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+            }
+            EdgeWrapperEnum::ResolveContract(wrapper) => {
+                wrapper.edge.normalize();
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+            }
+            EdgeWrapperEnum::ReverseResolveContract(wrapper) => {
+                wrapper.edge.normalize();
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+            }
+            EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper) => wrapper.target.normalize(),
+            EdgeWrapperEnum::Follow(wrapper) => {
+                wrapper.source.normalize();
+                wrapper.target.normalize();
+                // Keys of both ends may be changed by normalization.
+                wrapper.edge.original_from = wrapper.source.primary_key();
+                wrapper.edge.original_to = wrapper.target.primary_key();
+            }
+        }
+        self
+    }
+
+    /// Check if ENS names on this edge are normalized. Edges with invalid names are not saved.
+    pub fn has_valid_names(&self) -> bool {
+        let names_valid = match self {
+            EdgeWrapperEnum::Resolve(wrapper) | EdgeWrapperEnum::ReverseResolve(wrapper) => {
+                wrapper.edge.has_valid_name()
+            }
+            EdgeWrapperEnum::ResolveContract(wrapper) => wrapper.edge.has_valid_name(),
+            EdgeWrapperEnum::ReverseResolveContract(wrapper) => wrapper.edge.has_valid_name(),
+            _ => true,
+        };
+        names_valid
+            && [self.source(), self.target()]
+                .into_iter()
+                .filter_map(|vertex| vertex.as_any().downcast_ref::<Identity>())
+                .all(Identity::has_valid_name)
+    }
+
+    /// `Identity` vertices on the ends of this edge.
+    pub fn identities_mut(&mut self) -> Vec<&mut Identity> {
+        match self {
+            EdgeWrapperEnum::ProofForward(wrapper)
+            | EdgeWrapperEnum::ProofBackward(wrapper)
+            | EdgeWrapperEnum::Claim(wrapper) => {
+                vec![&mut wrapper.source, &mut wrapper.target]
+            }
+            EdgeWrapperEnum::HoldIdentity(wrapper) => {
+                vec![&mut wrapper.source, &mut wrapper.target]
+            }
+            EdgeWrapperEnum::HoldContract(wrapper) => vec![&mut wrapper.source],
+            EdgeWrapperEnum::Resolve(wrapper) | EdgeWrapperEnum::ReverseResolve(wrapper) => {
+                vec![&mut wrapper.source, &mut wrapper.target]
+            }
+            EdgeWrapperEnum::ResolveContract(wrapper) => vec![&mut wrapper.target],
+            EdgeWrapperEnum::ReverseResolveContract(wrapper) => vec![&mut wrapper.source],
+            EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper) => vec![&mut wrapper.target],
+            EdgeWrapperEnum::Follow(wrapper) => vec![&mut wrapper.source, &mut wrapper.target],
         }
     }
 
@@ -680,6 +989,8 @@ impl EdgeWrapperEnum {
             EdgeWrapperEnum::ResolveContract(_) => RESOLVE_CONTRACT,
             EdgeWrapperEnum::ReverseResolveContract(_) => REVERSE_RESOLVE_CONTRACT,
             EdgeWrapperEnum::PartOfIdentitiesGraph(_) => HYPER_EDGE_REVERSE,
+            EdgeWrapperEnum::Follow(_) => FOLLOW,
+            EdgeWrapperEnum::Claim(_) => CLAIM,
         }
     }
 }
@@ -724,6 +1035,14 @@ impl EdgeWrapperEnum {
     ) -> Self {
         EdgeWrapperEnum::PartOfIdentitiesGraph(wrapper)
     }
+
+    pub fn new_follow(wrapper: EdgeWrapper<FollowRecord, Identity, Identity>) -> Self {
+        EdgeWrapperEnum::Follow(wrapper)
+    }
+
+    pub fn new_claim(wrapper: EdgeWrapper<ProofRecord, Identity, Identity>) -> Self {
+        EdgeWrapperEnum::Claim(wrapper)
+    }
 }
 
 /// List edges.
@@ -763,6 +1082,44 @@ impl TryFrom<BatchEdges> for ContractEdgesRequest {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowEdgesRequest {
+    pub edges_str: String,       // STRING TO GSQL JSONArray
+    pub updated_nanosecond: i64, // for hyper vertices of identities not saved yet
+}
+
+impl TryFrom<BatchEdges> for FollowEdgesRequest {
+    type Error = Error;
+    fn try_from(edges: BatchEdges) -> Result<Self, Self::Error> {
+        let mut connections: Vec<Value> = Vec::new();
+        for edge_wrapper_enum in edges.0 {
+            if let EdgeWrapperEnum::Follow(wrapper) = edge_wrapper_enum {
+                let from = VertexRecord::from_with_json_value(
+                    wrapper.source.vertex_type(),
+                    wrapper.source.primary_key(),
+                    Value::Object(wrapper.source.to_json_value()),
+                );
+                let to = VertexRecord::from_with_json_value(
+                    wrapper.target.vertex_type(),
+                    wrapper.target.primary_key(),
+                    Value::Object(wrapper.target.to_json_value()),
+                );
+                let mut edge_attr_map = wrapper.edge.to_json_value();
+                edge_attr_map.insert("from".to_string(), json!(from));
+                edge_attr_map.insert("to".to_string(), json!(to));
+                connections.push(Value::Object(edge_attr_map))
+            }
+        }
+
+        let edges_str = serde_json::to_string(&connections).map_err(Error::JSONParseError)?;
+        let updated_nanosecond = chrono::Utc::now().naive_utc().and_utc().timestamp_micros();
+        Ok(FollowEdgesRequest {
+            edges_str,
+            updated_nanosecond,
+        })
+    }
+}
+
 impl From<BatchEdges> for UpsertGraph {
     fn from(edges: BatchEdges) -> Self {
         let mut edges_map = HashMap::new();
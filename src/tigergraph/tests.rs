@@ -1,21 +1,28 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::{HashMap, HashSet};
     use uuid::Uuid;
 
+    use crate::config::ConfigConfidence;
     use crate::error::Error;
     use crate::tigergraph::{
         create_contract_to_identity_resolve_record, create_identity_domain_resolve_record,
         create_identity_to_contract_hold_record, create_identity_to_identity_hold_record,
-        create_identity_to_identity_proof_two_way_binding,
+        create_identity_to_identity_proof_two_way_binding, parse_installed_queries, BatchEdges,
+        EdgeWrapperEnum, FollowEdgesRequest, Graph,
     };
     use crate::{
         tigergraph::{
-            edge::{Hold, Proof, Resolve},
-            vertex::{Contract, Identity, IdentityGraph, NeighborsResponse},
+            edge::{Follow, Hold, HyperEdge, Proof, Resolve, Wrapper, FOLLOW, HYPER_EDGE, RESOLVE},
+            vertex::{
+                Contract, ExpandIdentityRecord, IdentitiesGraph, Identity, IdentityConnection,
+                IdentityGraph, IdentityRecord, NeighborsResponse, VertexRecord,
+            },
         },
         upstream::{Chain, ContractCategory, DataSource, DomainNameSystem, Platform, ProofLevel},
-        util::make_http_client,
+        util::{make_http_client, naive_now},
     };
+    use chrono::Duration;
 
     #[tokio::test]
     async fn test_create_i2i_proof_two_way_binding() -> Result<(), Error> {
@@ -373,4 +380,264 @@ mod tests {
         println!("{:?}", record);
         Ok(())
     }
+
+    #[test]
+    fn test_parse_installed_queries() {
+        let endpoints = serde_json::json!({
+            "GET /query/SocialGraph/neighbors": {},
+            "POST /query/SocialGraph/upsert_hyper_vertex": {},
+            "GET /query/IdentityGraph/delete_vertex_and_edge": {},
+            "GET /echo": {},
+        });
+        let installed = parse_installed_queries(&Graph::SocialGraph, &endpoints);
+        assert_eq!(installed.len(), 2);
+        assert!(installed.contains("neighbors"));
+        assert!(installed.contains("upsert_hyper_vertex"));
+
+        let wrapped = serde_json::json!({ "error": false, "results": endpoints });
+        let installed = parse_installed_queries(&Graph::IdentityGraph, &wrapped);
+        assert_eq!(
+            installed,
+            HashSet::from(["delete_vertex_and_edge".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_identity_graph_without_expired() {
+        let vertex = |platform: Platform, identity: &str, expired_days: Option<i64>| {
+            let mut attributes = Identity::default();
+            attributes.platform = platform;
+            attributes.identity = identity.to_string();
+            attributes.expired_at =
+                expired_days.map(|days| naive_now() + Duration::try_days(days).unwrap());
+            ExpandIdentityRecord {
+                record: IdentityRecord(VertexRecord {
+                    v_type: "Identities".to_string(),
+                    v_id: format!("{},{}", platform, identity),
+                    attributes,
+                }),
+                owner_address: None,
+                resolve_address: None,
+                confidence: None,
+            }
+        };
+        let edge = |source: &str, target: &str| IdentityConnection {
+            edge_type: "Hold".to_string(),
+            data_source: DataSource::TheGraph,
+            source: source.to_string(),
+            target: target.to_string(),
+            level: None,
+        };
+        let graph = IdentityGraph {
+            graph_id: "graph".to_string(),
+            vertices: vec![
+                vertex(Platform::Ethereum, "0x01", None),
+                vertex(Platform::ENS, "active.eth", Some(30)),
+                vertex(Platform::ENS, "grace.eth", Some(-30)),
+                vertex(Platform::ENS, "expired.eth", Some(-100)),
+                // Connected only through expired.eth
+                vertex(Platform::Ethereum, "0x02", None),
+            ],
+            edges: vec![
+                edge("ethereum,0x01", "ens,active.eth"),
+                edge("ethereum,0x01", "ens,grace.eth"),
+                edge("ethereum,0x01", "ens,expired.eth"),
+                edge("ens,expired.eth", "ethereum,0x02"),
+            ],
+        };
+
+        let without_expired = graph.clone().without_expired("ethereum,0x01");
+        let vertices: Vec<&str> = without_expired
+            .vertices
+            .iter()
+            .map(|v| v.v_id.as_str())
+            .collect();
+        assert_eq!(
+            vertices,
+            vec!["ethereum,0x01", "ens,active.eth", "ens,grace.eth"]
+        );
+        assert_eq!(without_expired.edges.len(), 2);
+        assert!(without_expired
+            .edges
+            .iter()
+            .all(|e| e.source != "ens,expired.eth" && e.target != "ens,expired.eth"));
+
+        // Queried by the expired name itself
+        let without_expired = graph.without_expired("ens,expired.eth");
+        assert!(without_expired.vertices.is_empty());
+        assert!(without_expired.edges.is_empty());
+    }
+
+    #[test]
+    fn test_identity_graph_with_confidence() -> Result<(), Error> {
+        let vertex = |v_id: &str| {
+            let (platform, identity) = v_id.split_once(',').unwrap();
+            let mut attributes = Identity::default();
+            attributes.platform = platform.parse().unwrap();
+            attributes.identity = identity.to_string();
+            ExpandIdentityRecord {
+                record: IdentityRecord(VertexRecord {
+                    v_type: "Identities".to_string(),
+                    v_id: v_id.to_string(),
+                    attributes,
+                }),
+                owner_address: None,
+                resolve_address: None,
+                confidence: None,
+            }
+        };
+        let edge = |edge_type: &str, data_source: DataSource, source: &str, target: &str| {
+            IdentityConnection {
+                edge_type: edge_type.to_string(),
+                data_source,
+                source: source.to_string(),
+                target: target.to_string(),
+                level: None,
+            }
+        };
+        let proof = |data_source, source: &str, target: &str| {
+            edge("Proof_Forward", data_source, source, target)
+        };
+        let hold = |data_source, source: &str, target: &str| {
+            edge("Hold_Identity", data_source, source, target)
+        };
+        let graph = IdentityGraph {
+            graph_id: "graph".to_string(),
+            vertices: vec![
+                vertex("ethereum,0x01"),
+                vertex("twitter,alice"),
+                vertex("ens,alice.eth"),
+                vertex("github,alice"),
+                vertex("ethereum,0x02"),
+            ],
+            edges: vec![
+                proof(DataSource::NextID, "twitter,alice", "ethereum,0x01"),
+                hold(DataSource::TheGraph, "ethereum,0x01", "ens,alice.eth"),
+                proof(DataSource::Rss3, "ethereum,0x01", "github,alice"),
+                proof(DataSource::NextID, "twitter,alice", "github,alice"),
+                hold(DataSource::OpenSea, "github,alice", "ethereum,0x02"),
+            ],
+        };
+
+        let confidences = graph.confidences("ethereum,0x01", None);
+        assert_eq!(confidences["ethereum,0x01"], 1.0);
+        assert_eq!(confidences["twitter,alice"], 1.0);
+        assert_eq!(confidences["ens,alice.eth"], 0.8);
+        // Through twitter,alice rather than the less confident direct connection.
+        assert_eq!(confidences["github,alice"], 1.0);
+        assert!((confidences["ethereum,0x02"] - 0.4).abs() < 1e-9);
+
+        let config = ConfigConfidence {
+            sources: HashMap::from([("nextid".to_string(), 0.5)]),
+            edge_types: HashMap::from([("hold_identity".to_string(), 0.9)]),
+        };
+        assert_eq!(graph.edges[0].base_confidence(Some(&config)), 0.5);
+        assert_eq!(graph.edges[1].base_confidence(Some(&config)), 0.8);
+        assert_eq!(graph.edges[4].base_confidence(Some(&config)), 0.4);
+        let confidences = graph.confidences("ethereum,0x01", Some(&config));
+        assert_eq!(confidences["github,alice"], 0.6);
+
+        // The proof level stored on a connection wins over the default of its data source.
+        let stored: IdentityConnection = serde_json::from_value(serde_json::json!({
+            "edge_type": "Proof",
+            "data_source": "nextid",
+            "source_v": "twitter,alice",
+            "target_v": "ethereum,0x01",
+            "level": 1,
+        }))?;
+        assert_eq!(stored.level, Some(ProofLevel::Insecure));
+        assert!((stored.base_confidence(None) - 0.2).abs() < 1e-9);
+        // `0` for connections without a level.
+        let hold: IdentityConnection = serde_json::from_value(serde_json::json!({
+            "edge_type": "Hold",
+            "data_source": "the_graph",
+            "source_v": "ethereum,0x01",
+            "target_v": "ens,alice.eth",
+            "level": 0,
+        }))?;
+        assert_eq!(hold.level, None);
+        assert_eq!(hold.base_confidence(None), 0.8);
+
+        let graph = graph.with_confidence("ethereum,0x01", Some(0.5), None);
+        let vertices: Vec<(&str, Option<f64>)> = graph
+            .vertices
+            .iter()
+            .map(|v| (v.v_id.as_str(), v.confidence))
+            .collect();
+        assert_eq!(
+            vertices,
+            vec![
+                ("ethereum,0x01", Some(1.0)),
+                ("twitter,alice", Some(1.0)),
+                ("ens,alice.eth", Some(0.8)),
+                ("github,alice", Some(1.0)),
+            ]
+        );
+        assert_eq!(graph.edges.len(), 4);
+        assert!(graph.edges.iter().all(|e| e.target != "ethereum,0x02"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_follow_edges_request() -> Result<(), Error> {
+        let identity = |platform: Platform, identity: &str| {
+            let mut attributes = Identity::default();
+            attributes.platform = platform;
+            attributes.identity = identity.to_string();
+            attributes
+        };
+        let alice = identity(Platform::Farcaster, "@Alice");
+        let bob = identity(Platform::Farcaster, "bob");
+        let follow = Follow {
+            source: DataSource::Farcaster,
+            ..Default::default()
+        };
+        let wrapper = follow.wrapper(&alice, &bob, FOLLOW);
+        assert_eq!(wrapper.edge.original_from, "farcaster,@Alice");
+        assert_eq!(wrapper.edge.original_to, "farcaster,bob");
+
+        let edges = vec![
+            EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+                &IdentitiesGraph::default(),
+                &alice,
+                HYPER_EDGE,
+            )),
+            EdgeWrapperEnum::new_follow(wrapper).normalized(),
+        ];
+        let request: FollowEdgesRequest = BatchEdges(edges).try_into()?;
+        let follows: Vec<serde_json::Value> = serde_json::from_str(&request.edges_str)?;
+        assert_eq!(follows.len(), 1);
+        assert_eq!(follows[0]["original_from"], "farcaster,alice");
+        assert_eq!(follows[0]["original_to"], "farcaster,bob");
+        assert_eq!(follows[0]["from"]["v_id"], "farcaster,alice");
+        assert_eq!(follows[0]["to"]["attributes"]["identity"], "bob");
+        assert_eq!(follows[0]["source"], "farcaster");
+        Ok(())
+    }
+
+    #[test]
+    fn test_edges_with_invalid_ens_names() {
+        let identity = |platform: Platform, identity: &str| {
+            let mut attributes = Identity::default();
+            attributes.platform = platform;
+            attributes.identity = identity.to_string();
+            attributes
+        };
+        let resolve_edge = |name: &str| {
+            let resolve = Resolve {
+                system: DomainNameSystem::ENS,
+                name: name.to_string(),
+                source: DataSource::TheGraph,
+                ..Default::default()
+            };
+            let domain = identity(Platform::ENS, name);
+            let wallet = identity(Platform::Ethereum, "0x02");
+            EdgeWrapperEnum::new_resolve(resolve.wrapper(&domain, &wallet, RESOLVE)).normalized()
+        };
+
+        assert!(resolve_edge("Vitalik.eth").has_valid_names());
+        // Not normalizable: kept as given, then skipped instead of saved.
+        assert!(!resolve_edge("vitalik..eth").has_valid_names());
+        assert!(!resolve_edge("xn--vitalik.eth").has_valid_names());
+    }
 }
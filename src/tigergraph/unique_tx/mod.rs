@@ -0,0 +1,635 @@
+//! Statistics of unique transactions between Ethereum addresses, saved as `Relation_Unique_TX`.
+//! Transactions are read from exports (CSV or NDJSON, e.g. by ethereum-etl), or from blocks
+//! fetched through `[upstream.ethereum_rpc]`.
+//! Statistics are merged into existing edges, so every transaction must be counted only once:
+//! transactions counted are saved as `Transactions` vertices in the same upsert, and skipped
+//! when seen again. Blocks counted are kept in a checkpoint file, so they are not fetched again.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    cache,
+    config::C,
+    error::Error,
+    tigergraph::{
+        edge::{RelationUniqueTX, Wrapper, RELATION_UNIQUE_TX},
+        timed_request, upsert_graph,
+        vertex::{Identity, Vertex},
+        Attribute, BaseResponse, Edges, Graph, OpCode, UpsertGraph,
+    },
+    upstream::{EthereumRpc, Platform, RpcTransaction},
+    util::{make_http_client, naive_now, parse_body},
+};
+use futures::{StreamExt, TryStreamExt};
+use http::uri::InvalidUri;
+use hyper::{client::HttpConnector, Body, Client, Method};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+use tracing::{error, info, warn};
+
+/// Checkpoint file if not configured.
+const DEFAULT_CHECKPOINT: &str = "./config/unique_tx_checkpoint.json";
+/// Max transactions upserted per request if not configured.
+const DEFAULT_BATCH_SIZE: usize = 1000;
+/// Blocks fetched before each upsert if not configured.
+const DEFAULT_BLOCKS_PER_BATCH: u64 = 100;
+/// Blocks fetched from RPC at the same time.
+const CONCURRENT_BLOCKS: usize = 10;
+const WEI_PER_GWEI: u128 = 1_000_000_000;
+/// Vertex type of transactions counted into `Relation_Unique_TX`, keyed by hash.
+const TRANSACTIONS: &str = "Transactions";
+
+/// A transaction sending `value` from an address to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub hash: String,
+    pub block_number: u64,
+    /// Lowercase address.
+    pub from: String,
+    /// Lowercase address. `None` for contract creations.
+    pub to: Option<String>,
+    /// In Wei.
+    pub value: u128,
+    /// `false` if reverted. Always `true` for blocks from RPC, which carry no receipts.
+    pub success: bool,
+}
+
+impl TryFrom<&RpcTransaction> for Transaction {
+    type Error = Error;
+
+    fn try_from(tx: &RpcTransaction) -> Result<Self, Self::Error> {
+        Ok(Transaction {
+            hash: tx.hash.to_lowercase(),
+            block_number: parse_number(&tx.block_number)?,
+            from: parse_address(&tx.from)?,
+            to: tx.to.as_deref().map(parse_address).transpose()?,
+            value: parse_value(&tx.value)?,
+            success: true,
+        })
+    }
+}
+
+/// Block ranges already counted, inclusive, sorted and not overlapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessedBlocks {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl ProcessedBlocks {
+    /// Checkpoint in `path`. Nothing is processed if it doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn ranges(&self) -> &[(u64, u64)] {
+        &self.ranges
+    }
+
+    pub fn contains(&self, block: u64) -> bool {
+        self.ranges
+            .iter()
+            .any(|&(start, end)| start <= block && block <= end)
+    }
+
+    /// Mark blocks from `start` to `end` (inclusive) as processed.
+    pub fn insert(&mut self, start: u64, end: u64) {
+        if start > end {
+            return;
+        }
+        self.ranges.push((start, end));
+        self.merge();
+    }
+
+    /// Mark each of `blocks` as processed, but not the blocks in between.
+    pub fn insert_blocks(&mut self, blocks: &BTreeSet<u64>) {
+        self.ranges
+            .extend(blocks.iter().map(|&block| (block, block)));
+        self.merge();
+    }
+
+    /// Sort ranges, merging the adjacent and overlapping ones.
+    fn merge(&mut self) {
+        self.ranges.sort();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(self.ranges.len());
+        for &(start, end) in &self.ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        self.ranges = merged;
+    }
+
+    /// Ranges from `start` to `end` (inclusive) which are not processed yet.
+    pub fn unprocessed(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        let mut missing = vec![];
+        let mut next = start;
+        for &(range_start, range_end) in &self.ranges {
+            if next > end {
+                break;
+            }
+            if range_end < next {
+                continue;
+            }
+            if range_start > next {
+                missing.push((next, (range_start - 1).min(end)));
+            }
+            next = range_end.saturating_add(1);
+        }
+        if next <= end {
+            missing.push((next, end));
+        }
+        missing
+    }
+}
+
+/// Transactions to count: not reverted, not creating contracts, not sending to oneself,
+/// and each hash only once.
+fn countable<'a>(transactions: impl IntoIterator<Item = &'a Transaction>) -> Vec<&'a Transaction> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    transactions
+        .into_iter()
+        .filter(|tx| tx.success && tx.to.as_ref().is_some_and(|to| to != &tx.from))
+        .filter(|tx| seen.insert(tx.hash.as_str()))
+        .collect()
+}
+
+/// Statistics of `transactions` per `(from, to)` address pair, values in Gwei.
+/// Transactions reverted, creating contracts, sending to oneself,
+/// or seen before by hash are not counted.
+pub fn aggregate<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+) -> BTreeMap<(String, String), RelationUniqueTX> {
+    let mut relations: BTreeMap<(String, String), RelationUniqueTX> = BTreeMap::new();
+    for tx in countable(transactions) {
+        let Some(to) = tx.to.as_ref() else {
+            continue;
+        };
+        let value = i64::try_from(tx.value / WEI_PER_GWEI).unwrap_or(i64::MAX);
+        relations
+            .entry((tx.from.clone(), to.clone()))
+            .and_modify(|relation| {
+                relation.count += 1;
+                relation.sum = relation.sum.saturating_add(value);
+                relation.max = relation.max.max(value);
+                relation.min = relation.min.min(value);
+            })
+            .or_insert_with(|| RelationUniqueTX {
+                count: 1,
+                sum: value,
+                max: value,
+                min: value,
+                updated_at: naive_now(),
+            });
+    }
+    relations
+}
+
+/// Format of an export file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// By extension: `.csv`, or `.json` / `.jsonl` / `.ndjson` for one JSON object per line.
+    fn of(path: &Path) -> Result<Self, Error> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Ok(ExportFormat::Csv),
+            Some("json") | Some("jsonl") | Some("ndjson") => Ok(ExportFormat::Ndjson),
+            _ => Err(Error::ParamError(format!(
+                "Unknown export format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Ndjson => "NDJSON",
+        }
+    }
+}
+
+/// Reads an export line by line, so that an export never has to fit in memory.
+struct ExportParser {
+    format: ExportFormat,
+    /// Column names in the CSV header, `None` until it is read.
+    columns: Option<Vec<String>>,
+    line: usize,
+}
+
+impl ExportParser {
+    fn new(format: ExportFormat) -> Self {
+        ExportParser {
+            format,
+            columns: None,
+            line: 0,
+        }
+    }
+
+    /// Transaction in the next line. `None` for blank lines and the CSV header.
+    fn parse_line(&mut self, line: &str) -> Result<Option<Transaction>, Error> {
+        self.line += 1;
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        let row: Map<String, Value> = match (self.format, &self.columns) {
+            (ExportFormat::Csv, None) => {
+                self.columns = Some(split_csv_line(line));
+                return Ok(None);
+            }
+            (ExportFormat::Csv, Some(columns)) => columns
+                .iter()
+                .cloned()
+                .zip(split_csv_line(line).into_iter().map(Value::String))
+                .collect(),
+            (ExportFormat::Ndjson, _) => serde_json::from_str(line)?,
+        };
+        parse_row(&row).map(Some).map_err(|err| {
+            Error::ParamError(format!(
+                "{} line {}: {}",
+                self.format.name(),
+                self.line,
+                err
+            ))
+        })
+    }
+
+    /// All transactions in `content`.
+    fn parse(mut self, content: &str) -> Result<Vec<Transaction>, Error> {
+        content
+            .lines()
+            .filter_map(|line| self.parse_line(line).transpose())
+            .collect()
+    }
+}
+
+/// Transactions in CSV with a header. Columns are named as in ethereum-etl:
+/// `hash`, `block_number`, `from_address`, `to_address`, `value` and optional `receipt_status`.
+/// `transaction_hash`, `blockNumber`, `from`, `to` and `status` are accepted too.
+pub fn parse_csv(content: &str) -> Result<Vec<Transaction>, Error> {
+    ExportParser::new(ExportFormat::Csv).parse(content)
+}
+
+/// Transactions in NDJSON, with the same fields as `parse_csv`.
+/// Numbers may be JSON numbers, decimal strings or hex strings.
+pub fn parse_ndjson(content: &str) -> Result<Vec<Transaction>, Error> {
+    ExportParser::new(ExportFormat::Ndjson).parse(content)
+}
+
+/// Count transactions in export file `path`, read and upserted `[unique_tx] batch_size`
+/// transactions at a time. Transactions counted already are skipped, so an export may overlap
+/// others, or be ingested again after a failure. Blocks found in the export are then marked as
+/// processed, and not fetched again by `ingest_block_range`.
+/// Returns the amount of address pairs upserted.
+pub async fn ingest_export(path: &Path) -> Result<usize, Error> {
+    let mut parser = ExportParser::new(ExportFormat::of(path)?);
+    let client = make_http_client();
+    let batch_size = batch_size();
+    let checkpoint = checkpoint_path();
+    let mut processed = ProcessedBlocks::load(&checkpoint)?;
+    let mut batch: Vec<Transaction> = Vec::with_capacity(batch_size);
+    let mut total = 0;
+    for line in BufReader::new(File::open(path)?).lines() {
+        batch.extend(parser.parse_line(&line?)?);
+        if batch.len() >= batch_size {
+            total += flush_export_batch(&client, &mut batch, &mut processed, &checkpoint).await?;
+        }
+    }
+    total += flush_export_batch(&client, &mut batch, &mut processed, &checkpoint).await?;
+    info!(path = %path.display(), pairs = total, "UniqueTX: Ingested");
+    Ok(total)
+}
+
+/// Upsert transactions read from an export so far, then mark their blocks as processed.
+async fn flush_export_batch(
+    client: &Client<HttpConnector>,
+    batch: &mut Vec<Transaction>,
+    processed: &mut ProcessedBlocks,
+    checkpoint: &Path,
+) -> Result<usize, Error> {
+    if batch.is_empty() {
+        return Ok(0);
+    }
+    let pairs = upsert_transactions(client, batch).await?;
+    processed.insert_blocks(&batch.iter().map(|tx| tx.block_number).collect());
+    processed.save(checkpoint)?;
+    batch.clear();
+    Ok(pairs)
+}
+
+/// Count transactions in blocks from `start` to `end` (inclusive) through
+/// `[upstream.ethereum_rpc]`, skipping blocks counted already. `end` is capped at the latest
+/// block. Progress is saved into the checkpoint after every `[unique_tx] blocks_per_batch`,
+/// and it stops at the first block the node does not return.
+/// Returns the amount of address pairs upserted.
+pub async fn ingest_block_range(start: u64, end: u64) -> Result<usize, Error> {
+    let rpc = EthereumRpc::from_config()
+        .ok_or_else(|| Error::ParamMissing("upstream.ethereum_rpc.rpc_url".to_string()))?;
+    let end = end.min(rpc.block_number().await?);
+    let blocks_per_batch = C
+        .unique_tx
+        .as_ref()
+        .and_then(|config| config.blocks_per_batch)
+        .unwrap_or(DEFAULT_BLOCKS_PER_BATCH)
+        .max(1);
+    let client = make_http_client();
+    let checkpoint = checkpoint_path();
+    let mut processed = ProcessedBlocks::load(&checkpoint)?;
+    let mut total = 0;
+    for (range_start, range_end) in processed.unprocessed(start, end) {
+        let mut batch_start = range_start;
+        while batch_start <= range_end {
+            let batch_end = range_end.min(batch_start.saturating_add(blocks_per_batch - 1));
+            let (transactions, fetched) = fetch_transactions(&rpc, batch_start, batch_end).await?;
+            total += upsert_transactions(&client, &transactions).await?;
+            if fetched == 0 {
+                warn!(block = batch_start, "UniqueTX: Block not available, stop");
+                return Ok(total);
+            }
+            // Only blocks actually fetched are processed.
+            let fetched_end = batch_start + fetched - 1;
+            processed.insert(batch_start, fetched_end);
+            processed.save(&checkpoint)?;
+            info!(batch_start, batch_end = fetched_end, "UniqueTX: Ingested");
+            if fetched_end < batch_end {
+                warn!(
+                    block = fetched_end + 1,
+                    "UniqueTX: Block not available, stop"
+                );
+                return Ok(total);
+            }
+            batch_start = batch_end.saturating_add(1);
+        }
+    }
+    Ok(total)
+}
+
+/// Transactions in blocks from `start` to `end` (inclusive), up to the first block the node
+/// does not return. Returns the transactions and the amount of blocks fetched.
+async fn fetch_transactions(
+    rpc: &EthereumRpc,
+    start: u64,
+    end: u64,
+) -> Result<(Vec<Transaction>, u64), Error> {
+    let blocks: Vec<_> = futures::stream::iter(start..=end)
+        .map(|number| rpc.block_with_transactions(number))
+        .buffered(CONCURRENT_BLOCKS)
+        .try_collect()
+        .await?;
+    let blocks: Vec<_> = blocks.into_iter().map_while(|block| block).collect();
+    let transactions = blocks
+        .iter()
+        .flat_map(|block| block.transactions.iter())
+        .map(Transaction::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok((transactions, blocks.len() as u64))
+}
+
+/// Merge statistics of `transactions` into `Relation_Unique_TX` edges, creating the addresses
+/// if not exist, `[unique_tx] batch_size` transactions per request. Transactions are saved along
+/// with the statistics in the same request, and skipped if counted already.
+/// Returns the amount of address pairs upserted.
+async fn upsert_transactions(
+    client: &Client<HttpConnector>,
+    transactions: &[Transaction],
+) -> Result<usize, Error> {
+    let mut total = 0;
+    for chunk in transactions.chunks(batch_size()) {
+        let candidates = countable(chunk);
+        if candidates.is_empty() {
+            continue;
+        }
+        let counted = counted_transactions(client, &candidates).await?;
+        let fresh: Vec<&Transaction> = candidates
+            .into_iter()
+            .filter(|tx| !counted.contains(&tx.hash))
+            .collect();
+        let relations = aggregate(fresh.iter().copied());
+        if relations.is_empty() {
+            continue;
+        }
+        let wrappers: Vec<_> = relations
+            .iter()
+            .map(|((from, to), relation)| {
+                relation.wrapper(
+                    &Identity::new_address(Platform::Ethereum, from),
+                    &Identity::new_address(Platform::Ethereum, to),
+                    RELATION_UNIQUE_TX,
+                )
+            })
+            .collect();
+        let ids: Vec<String> = wrappers
+            .iter()
+            .flat_map(|wrapper| [wrapper.source.primary_key(), wrapper.target.primary_key()])
+            .collect();
+        let mut payload: UpsertGraph = Edges(wrappers).into();
+        payload.vertices.insert(
+            TRANSACTIONS.to_string(),
+            fresh
+                .iter()
+                .map(|tx| (tx.hash.clone(), transaction_attributes(tx)))
+                .collect(),
+        );
+        upsert_graph(client, &payload, Graph::SocialGraph).await?;
+        cache::invalidate(&ids);
+        total += relations.len();
+    }
+    Ok(total)
+}
+
+/// Attributes of the `Transactions` vertex of `tx`.
+fn transaction_attributes(tx: &Transaction) -> HashMap<String, Attribute> {
+    let mut attributes_map = HashMap::new();
+    attributes_map.insert(
+        "id".to_string(),
+        Attribute {
+            value: json!(tx.hash),
+            op: Some(OpCode::IgnoreIfExists),
+        },
+    );
+    attributes_map.insert(
+        "block_number".to_string(),
+        Attribute {
+            value: json!(tx.block_number),
+            op: Some(OpCode::IgnoreIfExists),
+        },
+    );
+    attributes_map.insert(
+        "updated_at".to_string(),
+        Attribute {
+            value: json!(naive_now()),
+            op: Some(OpCode::IgnoreIfExists),
+        },
+    );
+    attributes_map
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CountedTransactionsResponse {
+    #[serde(flatten)]
+    base: BaseResponse,
+    results: Option<Vec<CountedTransactions>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CountedTransactions {
+    counted: Vec<String>,
+}
+
+/// Hashes of `transactions` counted into `Relation_Unique_TX` already.
+async fn counted_transactions(
+    client: &Client<HttpConnector>,
+    transactions: &[&Transaction],
+) -> Result<HashSet<String>, Error> {
+    let hashes: Vec<&str> = transactions.iter().map(|tx| tx.hash.as_str()).collect();
+    let uri: http::Uri = format!(
+        "{}/query/{}/counted_transactions",
+        C.tdb.host,
+        Graph::SocialGraph
+    )
+    .parse()
+    .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+    let json_params = json!({ "hashes_str": serde_json::to_string(&hashes)? }).to_string();
+    let req = hyper::Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("Authorization", Graph::SocialGraph.token())
+        .body(Body::from(json_params))
+        .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
+            "counted_transactions | Fail to request: {:?}",
+            err.to_string()
+        ))
+    })?;
+
+    let r = parse_body::<CountedTransactionsResponse>(&mut resp).await?;
+    if r.base.error {
+        let err_message = format!(
+            "counted_transactions error | Code: {:?}, Message: {:?}",
+            r.base.code, r.base.message
+        );
+        error!(err_message);
+        return Err(Error::General(err_message, resp.status()));
+    }
+    Ok(r.results
+        .and_then(|results| results.into_iter().next())
+        .map(|result| result.counted.into_iter().collect())
+        .unwrap_or_default())
+}
+
+fn batch_size() -> usize {
+    C.unique_tx
+        .as_ref()
+        .and_then(|config| config.batch_size)
+        .unwrap_or(DEFAULT_BATCH_SIZE)
+        .max(1)
+}
+
+fn checkpoint_path() -> PathBuf {
+    C.unique_tx
+        .as_ref()
+        .and_then(|config| config.checkpoint.clone())
+        .unwrap_or_else(|| DEFAULT_CHECKPOINT.to_string())
+        .into()
+}
+
+/// Transaction from a row of an export, keyed by column names.
+fn parse_row(row: &Map<String, Value>) -> Result<Transaction, Error> {
+    let field = |names: &[&str]| -> Option<String> {
+        names
+            .iter()
+            .filter_map(|name| row.get(*name))
+            .find_map(|value| match value {
+                Value::String(value) if !value.is_empty() => Some(value.clone()),
+                Value::Number(value) => Some(value.to_string()),
+                _ => None,
+            })
+    };
+    let required =
+        |names: &[&str]| field(names).ok_or_else(|| Error::ParamMissing(names[0].to_string()));
+    let success = match field(&["receipt_status", "status"]) {
+        Some(status) => parse_number(&status)? != 0,
+        None => true,
+    };
+    Ok(Transaction {
+        hash: required(&["hash", "transaction_hash"])?.to_lowercase(),
+        block_number: parse_number(&required(&["block_number", "blockNumber"])?)?,
+        from: parse_address(&required(&["from_address", "from"])?)?,
+        to: field(&["to_address", "to"])
+            .map(|to| parse_address(&to))
+            .transpose()?,
+        value: parse_value(&required(&["value"])?)?,
+        success,
+    })
+}
+
+/// Fields of a CSV line. Fields may be quoted to contain commas.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Decimal or `0x`-prefixed hex.
+fn parse_number(number: &str) -> Result<u64, Error> {
+    match number.strip_prefix("0x") {
+        Some(hex) => Ok(u64::from_str_radix(hex, 16)?),
+        None => Ok(number.parse()?),
+    }
+}
+
+/// Value in Wei, decimal or `0x`-prefixed hex. Too large for `u64` in general.
+fn parse_value(value: &str) -> Result<u128, Error> {
+    let parsed = match value.strip_prefix("0x") {
+        Some("") => Ok(0),
+        Some(hex) => u128::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|err| Error::ParamError(format!("Invalid value {}: {}", value, err)))
+}
+
+fn parse_address(address: &str) -> Result<String, Error> {
+    let address = address.to_lowercase();
+    let valid = address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit());
+    match valid {
+        true => Ok(address),
+        false => Err(Error::ParamError(format!("Invalid address: {}", address))),
+    }
+}
@@ -0,0 +1,214 @@
+use super::*;
+use crate::tigergraph::{OpCode, Transfer};
+use serde_json::json;
+use warp::Filter;
+
+const ALICE: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+const BOB: &str = "0x225f137127d9067788314bc7fcc1f36746a3c3b5";
+
+fn transaction(hash: &str, block_number: u64, from: &str, to: &str, value: u128) -> Transaction {
+    Transaction {
+        hash: hash.to_string(),
+        block_number,
+        from: from.to_string(),
+        to: Some(to.to_string()),
+        value,
+        success: true,
+    }
+}
+
+#[test]
+fn test_parse_csv() -> Result<(), Error> {
+    let content = format!(
+        "hash,nonce,block_number,from_address,to_address,value,receipt_status\n\
+         0x01,0,100,{ALICE},{BOB},1000000000000000000,1\n\
+         0x02,1,101,{},,0,1\n\
+         \n\
+         \"0x03\",2,102,{},\"{}\",0x3b9aca00,0\n",
+        BOB.to_uppercase().replace("0X", "0x"),
+        ALICE,
+        BOB,
+    );
+    let transactions = parse_csv(&content)?;
+    assert_eq!(
+        transactions,
+        vec![
+            transaction("0x01", 100, ALICE, BOB, 1_000_000_000_000_000_000),
+            Transaction {
+                to: None,
+                ..transaction("0x02", 101, BOB, BOB, 0)
+            },
+            Transaction {
+                success: false,
+                ..transaction("0x03", 102, ALICE, BOB, 1_000_000_000)
+            },
+        ]
+    );
+
+    let invalid = format!("hash,block_number,from_address,to_address,value\n0x01,100,0x1,{BOB},1");
+    assert!(parse_csv(&invalid).is_err());
+    let missing = format!("hash,block_number,to_address,value\n0x01,100,{BOB},1");
+    assert!(parse_csv(&missing).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_ndjson() -> Result<(), Error> {
+    let content = format!(
+        "{{\"hash\":\"0x01\",\"block_number\":100,\"from_address\":\"{ALICE}\",\
+         \"to_address\":\"{BOB}\",\"value\":\"1000000000000000000\"}}\n\
+         \n\
+         {{\"transaction_hash\":\"0x02\",\"blockNumber\":\"0x65\",\"from\":\"{BOB}\",\
+         \"to\":null,\"value\":0}}\n"
+    );
+    let transactions = parse_ndjson(&content)?;
+    assert_eq!(
+        transactions,
+        vec![
+            transaction("0x01", 100, ALICE, BOB, 1_000_000_000_000_000_000),
+            Transaction {
+                to: None,
+                ..transaction("0x02", 101, BOB, BOB, 0)
+            },
+        ]
+    );
+    assert!(parse_ndjson("{\"hash\":").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_processed_blocks() {
+    let mut processed = ProcessedBlocks::default();
+    processed.insert(10, 20);
+    processed.insert(30, 40);
+    assert_eq!(processed.ranges(), &[(10, 20), (30, 40)]);
+    assert!(processed.contains(10) && processed.contains(40));
+    assert!(!processed.contains(25));
+
+    assert_eq!(
+        processed.unprocessed(0, 50),
+        vec![(0, 9), (21, 29), (41, 50)]
+    );
+    assert_eq!(processed.unprocessed(15, 35), vec![(21, 29)]);
+    assert_eq!(processed.unprocessed(12, 18), vec![]);
+
+    // Adjacent and overlapping ranges are merged.
+    processed.insert(21, 29);
+    assert_eq!(processed.ranges(), &[(10, 40)]);
+    processed.insert(5, 12);
+    processed.insert(41, 41);
+    assert_eq!(processed.ranges(), &[(5, 41)]);
+
+    // Only the blocks found in an export.
+    processed.insert_blocks(&BTreeSet::from([42, 43, 50, 60, 61]));
+    assert_eq!(processed.ranges(), &[(5, 43), (50, 50), (60, 61)]);
+}
+
+#[test]
+fn test_aggregate() {
+    const GWEI: u128 = 1_000_000_000;
+    let transactions = vec![
+        transaction("0x01", 10, ALICE, BOB, 3 * GWEI),
+        transaction("0x02", 11, ALICE, BOB, GWEI + 1),
+        // Same transaction in an overlapping export.
+        transaction("0x02", 11, ALICE, BOB, GWEI + 1),
+        transaction("0x03", 12, BOB, ALICE, 0),
+        // To oneself.
+        transaction("0x05", 12, ALICE, ALICE, GWEI),
+        Transaction {
+            success: false,
+            ..transaction("0x06", 12, ALICE, BOB, 100 * GWEI)
+        },
+        Transaction {
+            to: None,
+            ..transaction("0x07", 12, ALICE, BOB, 100 * GWEI)
+        },
+    ];
+    let relations = aggregate(&transactions);
+    assert_eq!(relations.len(), 2);
+
+    let sent = &relations[&(ALICE.to_string(), BOB.to_string())];
+    assert_eq!((sent.count, sent.sum, sent.max, sent.min), (2, 4, 3, 1));
+    let received = &relations[&(BOB.to_string(), ALICE.to_string())];
+    assert_eq!(
+        (received.count, received.sum, received.max, received.min),
+        (1, 0, 0, 0)
+    );
+}
+
+#[test]
+fn test_export_format() {
+    assert_eq!(
+        ExportFormat::of(Path::new("transactions.csv")).unwrap(),
+        ExportFormat::Csv
+    );
+    assert_eq!(
+        ExportFormat::of(Path::new("transactions.jsonl")).unwrap(),
+        ExportFormat::Ndjson
+    );
+    assert!(ExportFormat::of(Path::new("transactions.parquet")).is_err());
+}
+
+#[tokio::test]
+async fn test_fetch_transactions() -> Result<(), Error> {
+    let route = warp::post()
+        .and(warp::body::json())
+        .map(|req: serde_json::Value| {
+            let result = match req["params"][0].as_str() {
+                Some(number @ ("0xe" | "0xf")) => json!({
+                    "transactions": [{
+                        "hash": format!("0x{}", number),
+                        "blockNumber": number,
+                        "from": ALICE,
+                        "to": BOB,
+                        "value": "0x3b9aca00",
+                    }],
+                }),
+                _ => json!(null),
+            };
+            warp::reply::json(&json!({"jsonrpc": "2.0", "id": req["id"], "result": result}))
+        });
+    let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    let rpc = EthereumRpc::new(&format!("http://{}", addr));
+
+    let (transactions, fetched) = fetch_transactions(&rpc, 14, 15).await?;
+    assert_eq!((transactions.len(), fetched), (2, 2));
+    assert_eq!(
+        transactions[1],
+        transaction("0x0xf", 15, ALICE, BOB, 1_000_000_000)
+    );
+    // Block 16 is not mined yet, so blocks after it are not fetched either.
+    let (transactions, fetched) = fetch_transactions(&rpc, 15, 17).await?;
+    assert_eq!((transactions.len(), fetched), (1, 1));
+    Ok(())
+}
+
+#[test]
+fn test_relation_wrapper() {
+    let relation = RelationUniqueTX {
+        count: 2,
+        sum: 4,
+        max: 3,
+        min: 1,
+        updated_at: naive_now(),
+    };
+    let wrapper = relation.wrapper(
+        &Identity::new_address(Platform::Ethereum, ALICE),
+        &Identity::new_address(Platform::Ethereum, BOB),
+        RELATION_UNIQUE_TX,
+    );
+    assert_eq!(wrapper.edge.from_id, format!("ethereum,{}", ALICE));
+    assert_eq!(wrapper.edge.to_id, format!("ethereum,{}", BOB));
+
+    let attributes = wrapper.edge.to_attributes_map();
+    assert_eq!(attributes["tx_count"].value, 2);
+    assert_eq!(attributes["tx_count"].op, Some(OpCode::Add));
+    assert_eq!(attributes["tx_sum"].op, Some(OpCode::Add));
+    assert_eq!(attributes["tx_max"].op, Some(OpCode::Max));
+    assert_eq!(attributes["tx_min"].value, 1);
+    assert_eq!(attributes["tx_min"].op, Some(OpCode::Min));
+
+    let payload: UpsertGraph = Edges(vec![wrapper]).into();
+    assert_eq!(payload.vertices["Identities"].len(), 2);
+}
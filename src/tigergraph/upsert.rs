@@ -1,4 +1,5 @@
 use crate::{
+    cache,
     config::C,
     error::Error,
     tigergraph::{
@@ -7,6 +8,7 @@ use crate::{
             HOLD_CONTRACT, HOLD_IDENTITY, PROOF_EDGE, PROOF_REVERSE_EDGE, RESOLVE,
             RESOLVE_CONTRACT, REVERSE_RESOLVE, REVERSE_RESOLVE_CONTRACT,
         },
+        timed_request,
         vertex::{Contract, FromWithJsonValue, Identity, Vertex},
         Attribute, BaseResponse, EdgeWrapper, Edges, Graph, Transfer,
     },
@@ -46,8 +48,8 @@ pub async fn delete_graph_inner_connection(
         .header("Authorization", Graph::IdentityGraph.token())
         .body(Body::empty())
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "delete_graph_inner_connection | Fail to request: {:?}",
             err.to_string()
         ))
@@ -73,6 +75,7 @@ pub async fn delete_graph_inner_connection(
     // let json_raw = serde_json::to_string(&result).map_err(|err| Error::JSONParseError(err))?;
     // println!("{}", json_raw);
     trace!("TigerGraph delete_graph_inner_connection...");
+    cache::invalidate(&[v_id]);
 
     Ok(())
 }
@@ -141,8 +144,8 @@ pub async fn upsert_vertices(
         .header("Authorization", graph_name.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request upsert vertices: {:?}",
             err.to_string()
         ))
@@ -243,8 +246,8 @@ async fn upsert_edge(
         .header("Authorization", graph_name.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request upsert graph: {:?}",
             err.to_string()
         ))
@@ -378,8 +381,8 @@ async fn upsert_isolated_vertex(
         .header("Authorization", graph.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request upsert_isolated_vertex: {:?}",
             err.to_string()
         ))
@@ -426,8 +429,8 @@ async fn upsert_hyper_vertex(
         .header("Authorization", graph.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request upsert_hyper_vertex: {:?}",
             err.to_string()
         ))
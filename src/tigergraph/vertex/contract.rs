@@ -2,11 +2,11 @@ use crate::{
     config::C,
     error::Error,
     tigergraph::{
-        upsert_graph,
+        timed_request, upsert_graph,
         vertex::{FromWithParams, Vertex, VertexRecord},
         Attribute, BaseResponse, Graph, OpCode, Transfer, UpsertGraph, Vertices,
     },
-    upstream::{Chain, ContractCategory},
+    upstream::{normalize_address, Chain, ContractCategory},
     util::{naive_datetime_from_string, naive_datetime_to_string, naive_now, parse_body},
 };
 
@@ -212,6 +212,11 @@ pub struct VertexResponse {
 }
 
 impl Contract {
+    /// Bring `address` into its canonical form on `chain`.
+    pub fn normalize(&mut self) {
+        self.address = normalize_address(self.chain, &self.address);
+    }
+
     #[allow(dead_code)]
     fn uuid(&self) -> Option<uuid::Uuid> {
         Some(self.uuid)
@@ -259,8 +264,8 @@ impl Contract {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query filter error | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -310,8 +315,8 @@ impl Contract {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query filter error | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -393,8 +398,8 @@ async fn get_contracts_by_ids(
         .header("Authorization", Graph::SocialGraph.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request contracts_by_ids graph: {:?}",
             err.to_string()
         ))
@@ -4,14 +4,15 @@ use crate::{
     tigergraph::{
         edge::{
             resolve::{ResolveRecord, ResolveReverse},
-            EdgeUnion, HoldRecord,
+            EdgeUnion, HoldRecord, ProofRecord,
         },
-        upsert_graph,
-        vertex::{FromWithParams, Vertex, VertexRecord},
+        timed_request, upsert_graph,
+        vertex::{FromWithParams, IdentityConnection, Vertex, VertexRecord},
         Attribute, BaseResponse, Graph, OpCode, Transfer, UpsertGraph, Vertices,
     },
     upstream::{
-        vec_string_to_vec_datasource, ContractCategory, DataSource, DomainNameSystem, Platform,
+        normalize, validate, vec_string_to_vec_datasource, ContractCategory, DataSource,
+        DomainNameSystem, ExpiryStatus, Platform,
     },
     util::{
         naive_datetime_from_string, naive_datetime_to_string, naive_now,
@@ -357,9 +358,13 @@ pub struct NeighborsWithSource {
     results: Option<Vec<VertexWithSource>>,
 }
 
+/// Neighbors, or connections between them, in separate results.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VertexWithSource {
+    #[serde(default)]
     vertices: Vec<IdentityWithSource>,
+    #[serde(default)]
+    edges: Vec<IdentityConnection>,
 }
 
 #[derive(Clone, Serialize, Debug)]
@@ -367,6 +372,8 @@ pub struct IdentityWithSource {
     pub identity: IdentityRecord,
     pub sources: Vec<DataSource>,
     pub reverse: Option<bool>,
+    /// Relative to the queried identity, see `IdentityGraph::confidences`.
+    pub confidence: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -417,6 +424,25 @@ struct Nfts {
     edges: Vec<HoldRecord>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QueryClaimsResponse {
+    #[serde(flatten)]
+    base: BaseResponse,
+    results: Option<Vec<Claims>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Claims {
+    edges: Vec<ProofRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowsResponse {
+    #[serde(flatten)]
+    base: BaseResponse,
+    results: Option<Vec<Identities>>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct IdentityBySourceResponse {
     #[serde(flatten)]
@@ -508,6 +534,7 @@ impl<'de> Deserialize<'de> for IdentityWithSource {
                     }),
                     sources,
                     reverse,
+                    confidence: None,
                 })
             }
         }
@@ -517,10 +544,52 @@ impl<'de> Deserialize<'de> for IdentityWithSource {
 }
 
 impl Identity {
+    /// New address `address` on `platform` (e.g. an Ethereum wallet), not a primary name.
+    pub fn new_address(platform: Platform, address: &str) -> Self {
+        Identity {
+            uuid: Some(Uuid::new_v4()),
+            platform,
+            identity: address.to_string(),
+            uid: None,
+            created_at: None,
+            display_name: None,
+            added_at: naive_now(),
+            avatar_url: None,
+            profile_url: None,
+            updated_at: naive_now(),
+            expired_at: None,
+            reverse: Some(false),
+        }
+    }
+
+    /// New domain name `name` on `platform`, displayed by itself.
+    /// `reverse` if it is the primary name of its address.
+    pub fn new_domain(platform: Platform, name: &str, reverse: bool) -> Self {
+        Identity {
+            display_name: Some(name.to_string()),
+            reverse: Some(reverse),
+            ..Self::new_address(platform, name)
+        }
+    }
+
     pub fn uuid(&self) -> Option<Uuid> {
         self.uuid
     }
 
+    /// Bring `identity` into its canonical form on `platform`.
+    pub fn normalize(&mut self) {
+        self.identity = normalize(self.platform, &self.identity);
+    }
+
+    /// Check if this is a well-formed ENS name, if on ENS or Basenames.
+    /// `normalize` keeps names it can't normalize as given, so they are caught here.
+    pub fn has_valid_name(&self) -> bool {
+        match self.platform {
+            Platform::ENS | Platform::Basenames => validate(self.platform, &self.identity).is_ok(),
+            _ => true,
+        }
+    }
+
     /// Judge if this record is outdated and should be refetched.
     pub fn is_outdated(&self) -> bool {
         let outdated_in = Duration::try_hours(1).unwrap();
@@ -530,6 +599,16 @@ impl Identity {
             .lt(&naive_now())
     }
 
+    /// Expiry status of a domain name. `None` if not a name, or it never expires.
+    pub fn expiry_status(&self) -> Option<ExpiryStatus> {
+        DomainNameSystem::from(self.platform).expiry_status(self.expired_at, naive_now())
+    }
+
+    /// Judge if this is a domain name past its grace period, i.e. released.
+    pub fn is_expired(&self) -> bool {
+        self.expiry_status() == Some(ExpiryStatus::Expired)
+    }
+
     /// Create or update a vertex.
     pub async fn create_or_update(&self, client: &Client<HttpConnector>) -> Result<(), Error> {
         let vertices = Vertices(vec![self.to_owned()]);
@@ -563,8 +642,8 @@ impl Identity {
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query filter error | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -624,8 +703,8 @@ impl Identity {
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error | {}", _err)))?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query filter=platform=%22{}%22,identity=%22{}%22 error | Fail to request: {:?}",
                 platform.to_string(),
                 encoded_identity,
@@ -672,6 +751,19 @@ impl IdentityRecord {
         depth: u16,
         reverse: Option<bool>,
     ) -> Result<Vec<IdentityWithSource>, Error> {
+        Ok(self
+            .neighbors_with_connections(client, depth, reverse)
+            .await?
+            .0)
+    }
+
+    /// `neighbors`, along with the connections between them (and this identity).
+    async fn neighbors_with_connections(
+        &self,
+        client: &Client<HttpConnector>,
+        depth: u16,
+        reverse: Option<bool>,
+    ) -> Result<(Vec<IdentityWithSource>, Vec<IdentityConnection>), Error> {
         // This reverse flag can be used as a filtering for Identity which type is domain system .
         // flag = 0, If `reverse=None` if omitted, there is no need to filter anything.
         // flag = 1, When `reverse=true`, just return `primary domain` related identities.
@@ -698,8 +790,8 @@ impl IdentityRecord {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query neighbors_with_source | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -716,17 +808,18 @@ impl IdentityRecord {
                     return Err(Error::General(err_message, resp.status()));
                 }
 
-                let result: Vec<IdentityWithSource> = r
-                    .results
-                    .and_then(|vec_with_sources| vec_with_sources.first().cloned())
-                    .map_or(vec![], |result| {
+                let mut neighbors: Vec<IdentityWithSource> = vec![];
+                let mut connections: Vec<IdentityConnection> = vec![];
+                for result in r.results.unwrap_or_default() {
+                    neighbors.extend(
                         result
                             .vertices
                             .into_iter()
-                            .filter(|target| target.identity.v_id != self.v_id)
-                            .collect()
-                    });
-                Ok(result)
+                            .filter(|target| target.identity.v_id != self.v_id),
+                    );
+                    connections.extend(result.edges);
+                }
+                Ok((neighbors, connections))
             }
             Err(err) => {
                 let err_message = format!(
@@ -739,6 +832,35 @@ impl IdentityRecord {
         }
     }
 
+    /// `neighbors` with their confidence relative to this identity, computed over the
+    /// connections between them. Neighbors less confident than `min_confidence` are left out.
+    pub async fn neighbors_with_confidence(
+        &self,
+        client: &Client<HttpConnector>,
+        depth: u16,
+        reverse: Option<bool>,
+        min_confidence: Option<f64>,
+    ) -> Result<Vec<IdentityWithSource>, Error> {
+        let (neighbors, connections) = self
+            .neighbors_with_connections(client, depth, reverse)
+            .await?;
+        let confidences =
+            IdentityConnection::confidences(&connections, &self.v_id, C.confidence.as_ref());
+        Ok(neighbors
+            .into_iter()
+            .map(|mut neighbor| {
+                neighbor.confidence = confidences.get(&neighbor.identity.v_id).copied();
+                neighbor
+            })
+            .filter(|neighbor| match min_confidence {
+                Some(min) => neighbor
+                    .confidence
+                    .is_some_and(|confidence| confidence >= min),
+                None => true,
+            })
+            .collect())
+    }
+
     /// Return all neighbors of this identity with traversal paths.
     pub async fn neighbors_with_traversal(
         &self,
@@ -767,8 +889,8 @@ impl IdentityRecord {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query neighbors_with_traversal | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -822,8 +944,8 @@ impl IdentityRecord {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query identity_by_source | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -878,8 +1000,8 @@ impl IdentityRecord {
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query reverse_domains | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -904,8 +1026,9 @@ impl IdentityRecord {
                             .map(|record| {
                                 let mut resolve_reverse =
                                     ResolveReverse::from(record.attributes.clone());
-                                // set 'reverse' to true.
+                                // `reverse_domains` only returns records resolving back.
                                 resolve_reverse.reverse = true;
+                                resolve_reverse.verified = true;
                                 resolve_reverse
                             })
                             .collect()
@@ -954,8 +1077,8 @@ impl IdentityRecord {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query owned_by | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -1036,8 +1159,8 @@ impl IdentityRecord {
             .header("Authorization", Graph::SocialGraph.token())
             .body(Body::empty())
             .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query nfts | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -1066,6 +1189,125 @@ impl IdentityRecord {
             }
         }
     }
+
+    /// Identities following this identity (`followers: true`), or followed by it.
+    /// If `mutual`, only identities which follow it and are followed back by it.
+    pub async fn follows(
+        &self,
+        client: &Client<HttpConnector>,
+        followers: bool,
+        mutual: bool,
+        limit: u16,
+        offset: u16,
+    ) -> Result<Vec<IdentityRecord>, Error> {
+        if self.attributes.platform != Platform::Farcaster
+            && self.attributes.platform != Platform::Lens
+        {
+            return Ok(vec![]);
+        }
+        // query see in Solution: follows(VERTEX<Identities> p, BOOL followers, BOOL mutual, ...)
+        let encoded_id = urlencoding::encode(self.v_id.as_str());
+        let uri: http::Uri = format!(
+            "{}/query/{}/follows?p={}&followers={}&mutual={}&numPerPage={}&pageNum={}",
+            C.tdb.host,
+            Graph::SocialGraph,
+            encoded_id,
+            followers,
+            mutual,
+            limit,
+            offset
+        )
+        .parse()
+        .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+        let req = hyper::Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header("Authorization", Graph::SocialGraph.token())
+            .body(Body::empty())
+            .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
+                "query follows | Fail to request: {:?}",
+                err.to_string()
+            ))
+        })?;
+        match parse_body::<FollowsResponse>(&mut resp).await {
+            Ok(r) => {
+                if r.base.error {
+                    let err_message = format!(
+                        "TigerGraph query follows error | Code: {:?}, Message: {:?}",
+                        r.base.code, r.base.message
+                    );
+                    error!(err_message);
+                    return Err(Error::General(err_message, resp.status()));
+                }
+
+                let result = r
+                    .results
+                    .and_then(|vec_unions| vec_unions.first().cloned())
+                    .map_or(vec![], |union| union.vertices);
+                Ok(result)
+            }
+            Err(err) => {
+                let err_message = format!("TigerGraph query follows parse_body error: {:?}", err);
+                error!(err_message);
+                Err(err)
+            }
+        }
+    }
+
+    /// Links claimed by this identity or claiming it, e.g. from text records of an ENS name.
+    /// They are saved as `Claim` edges, apart from proofs of the identity graph.
+    pub async fn find_claims(
+        &self,
+        client: &Client<HttpConnector>,
+    ) -> Result<Vec<ProofRecord>, Error> {
+        // query see in Solution: claims(VERTEX<Identities> p)
+        let encoded_id = urlencoding::encode(self.v_id.as_str());
+        let uri: http::Uri = format!(
+            "{}/query/{}/claims?p={}",
+            C.tdb.host,
+            Graph::SocialGraph,
+            encoded_id,
+        )
+        .parse()
+        .map_err(|_err: InvalidUri| Error::ParamError(format!("Uri format Error {}", _err)))?;
+        let req = hyper::Request::builder()
+            .method(Method::GET)
+            .uri(uri)
+            .header("Authorization", Graph::SocialGraph.token())
+            .body(Body::empty())
+            .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
+                "query claims | Fail to request: {:?}",
+                err.to_string()
+            ))
+        })?;
+        match parse_body::<QueryClaimsResponse>(&mut resp).await {
+            Ok(r) => {
+                if r.base.error {
+                    let err_message = format!(
+                        "TigerGraph query claims error | Code: {:?}, Message: {:?}",
+                        r.base.code, r.base.message
+                    );
+                    error!(err_message);
+                    return Err(Error::General(err_message, resp.status()));
+                }
+
+                let result = r
+                    .results
+                    .and_then(|vec_unions| vec_unions.first().cloned())
+                    .map_or(vec![], |union| union.edges);
+                Ok(result)
+            }
+            Err(err) => {
+                let err_message = format!("TigerGraph query claims parse_body error: {:?}", err);
+                error!(err_message);
+                Err(err)
+            }
+        }
+    }
 }
 
 pub struct ExpireTimeLoadFn {
@@ -1211,8 +1453,8 @@ async fn get_expired_time_by_ids(
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
 
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request expired_time_by_ids: {:?}",
             err.to_string()
         ))
@@ -1274,8 +1516,8 @@ async fn get_neighbor_reverse_by_ids(
         .header("Authorization", Graph::SocialGraph.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request neighbor_reverse_by_ids: {:?}",
             err.to_string()
         ))
@@ -1326,8 +1568,8 @@ async fn get_owners_by_ids(
         .header("Authorization", Graph::SocialGraph.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request owners_by_ids: {:?}",
             err.to_string()
         ))
@@ -1378,8 +1620,8 @@ async fn get_identities_by_ids(
         .header("Authorization", Graph::SocialGraph.token())
         .body(Body::from(json_params))
         .map_err(|_err| Error::ParamError(format!("ParamError Error {}", _err)))?;
-    let mut resp = client.request(req).await.map_err(|err| {
-        Error::ManualHttpClientError(format!(
+    let mut resp = timed_request(client, req).await.map_err(|err| {
+        Error::DatabaseError(format!(
             "TigerGraph | Fail to request identities_by_ids: {:?}",
             err.to_string()
         ))
@@ -1,11 +1,13 @@
 use crate::{
-    config::C,
+    cache::{graph_tags, identity_tags, CacheKey, GRAPH_CACHE, IDENTITY_CACHE},
+    config::{ConfigConfidence, C},
     error::Error,
     tigergraph::{
+        timed_request,
         vertex::{FromWithParams, Identity, IdentityRecord, Vertex, VertexRecord},
         Attribute, BaseResponse, Graph, OpCode, Transfer,
     },
-    upstream::{Chain, DataSource, Platform},
+    upstream::{Chain, DataSource, Platform, ProofLevel},
     util::parse_body,
 };
 use async_trait::async_trait;
@@ -16,7 +18,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::value::{Map, Value};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use strum::IntoEnumIterator;
 use tracing::error;
 
 pub const VERTEX_NAME: &str = "IdentitiesGraph";
@@ -151,6 +154,18 @@ pub struct IdentityConnection {
     pub source: String,
     #[serde(rename = "target_v")]
     pub target: String,
+    /// Proof level stored on the connection, `None` if it has none (e.g. holds).
+    #[serde(default, deserialize_with = "option_proof_level")]
+    pub level: Option<ProofLevel>,
+}
+
+/// Proof level from its number, `None` for anything else (e.g. `0` for connections without one).
+fn option_proof_level<'de, D>(deserializer: D) -> Result<Option<ProofLevel>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    let level: Option<i32> = Option::deserialize(deserializer)?;
+    Ok(level.and_then(|level| ProofLevel::iter().find(|proof_level| *proof_level as i32 == level)))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -179,11 +194,201 @@ struct ExpandVerticesList {
     expand_vlist: Vec<ExpandIdentityRecord>,
 }
 
+impl IdentityConnection {
+    /// Base confidence of this connection from 0 to 1: the lower one of its data source and
+    /// its edge type, configured in `[confidence]`. Data sources not configured default to
+    /// the proof level stored on the connection, or the proof level of the data source.
+    pub fn base_confidence(&self, config: Option<&ConfigConfidence>) -> f64 {
+        let source_key = self.data_source.to_string().to_lowercase();
+        let source = config
+            .and_then(|config| config.sources.get(&source_key))
+            .copied()
+            .unwrap_or_else(|| {
+                self.level
+                    .unwrap_or_else(|| self.data_source.proof_level())
+                    .confidence()
+            });
+        let edge_type = config
+            .and_then(|config| config.edge_types.get(&self.edge_type.to_lowercase()))
+            .copied()
+            .unwrap_or(1.0);
+        source.min(edge_type).clamp(0.0, 1.0)
+    }
+
+    /// Confidence of every vertex connected to vertex `v_id` through `connections`, which is 1
+    /// itself: product of base confidences of connections along the most confident path from it,
+    /// regardless of their direction.
+    pub fn confidences(
+        connections: &[IdentityConnection],
+        v_id: &str,
+        config: Option<&ConfigConfidence>,
+    ) -> HashMap<String, f64> {
+        let mut adjacent: HashMap<&str, Vec<(&str, f64)>> = HashMap::new();
+        for edge in connections {
+            let confidence = edge.base_confidence(config);
+            adjacent
+                .entry(edge.source.as_str())
+                .or_default()
+                .push((edge.target.as_str(), confidence));
+            adjacent
+                .entry(edge.target.as_str())
+                .or_default()
+                .push((edge.source.as_str(), confidence));
+        }
+        // Same as Dijkstra's: confidence never rises along a path,
+        // so the most confident vertex in `frontier` can't be reached more confidently.
+        let mut confidences: HashMap<String, f64> = HashMap::new();
+        let mut frontier: HashMap<&str, f64> = HashMap::from([(v_id, 1.0)]);
+        while let Some((vertex, confidence)) = frontier
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(vertex, confidence)| (*vertex, *confidence))
+        {
+            frontier.remove(vertex);
+            confidences.insert(vertex.to_string(), confidence);
+            for &(next, base) in adjacent.get(vertex).into_iter().flatten() {
+                if confidences.contains_key(next) {
+                    continue;
+                }
+                let reached = frontier.entry(next).or_insert(0.0);
+                *reached = reached.max(confidence * base);
+            }
+        }
+        confidences
+    }
+}
+
 impl IdentityGraph {
+    /// Confidence of every vertex relative to vertex `v_id`, see `IdentityConnection::confidences`.
+    pub fn confidences(
+        &self,
+        v_id: &str,
+        config: Option<&ConfigConfidence>,
+    ) -> HashMap<String, f64> {
+        IdentityConnection::confidences(&self.edges, v_id, config)
+    }
+
+    /// Set the confidence of every vertex relative to vertex `v_id`, see `confidences`.
+    /// Vertices less confident than `min_confidence` are dropped, along with their connections.
+    pub fn with_confidence(
+        mut self,
+        v_id: &str,
+        min_confidence: Option<f64>,
+        config: Option<&ConfigConfidence>,
+    ) -> Self {
+        let confidences = self.confidences(v_id, config);
+        for vertex in self.vertices.iter_mut() {
+            vertex.confidence = Some(confidences.get(&vertex.v_id).copied().unwrap_or(0.0));
+        }
+        let Some(min_confidence) = min_confidence else {
+            return self;
+        };
+        let dropped: HashSet<String> = self
+            .vertices
+            .iter()
+            .filter(|vertex| vertex.confidence.unwrap_or(0.0) < min_confidence)
+            .map(|vertex| vertex.v_id.clone())
+            .collect();
+        self.drop_vertices(&dropped);
+        self
+    }
+
+    /// Drop domain names past their grace period, along with their connections,
+    /// and vertices connected to vertex `v_id` only through them.
+    /// Nothing is left if `v_id` itself is expired.
+    pub fn without_expired(mut self, v_id: &str) -> Self {
+        let expired: HashSet<String> = self
+            .vertices
+            .iter()
+            .filter(|vertex| vertex.is_expired())
+            .map(|vertex| vertex.v_id.clone())
+            .collect();
+        if expired.is_empty() {
+            return self;
+        }
+        self.drop_vertices(&expired);
+        let connected = self.connected(v_id);
+        let dangling: HashSet<String> = self
+            .vertices
+            .iter()
+            .filter(|vertex| !connected.contains(vertex.v_id.as_str()))
+            .map(|vertex| vertex.v_id.clone())
+            .collect();
+        self.drop_vertices(&dangling);
+        self
+    }
+
+    /// Vertex ids connected to vertex `v_id` (incl. itself if present), regardless of direction.
+    fn connected(&self, v_id: &str) -> HashSet<String> {
+        let mut connected = HashSet::new();
+        if !self.vertices.iter().any(|vertex| vertex.v_id == v_id) {
+            return connected;
+        }
+        let mut adjacent: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            adjacent.entry(&edge.source).or_default().push(&edge.target);
+            adjacent.entry(&edge.target).or_default().push(&edge.source);
+        }
+        let mut stack = vec![v_id];
+        while let Some(vertex) = stack.pop() {
+            if !connected.insert(vertex.to_string()) {
+                continue;
+            }
+            stack.extend(adjacent.get(vertex).into_iter().flatten());
+        }
+        connected
+    }
+
+    /// Drop `dropped` vertices, along with their connections.
+    fn drop_vertices(&mut self, dropped: &HashSet<String>) {
+        self.vertices
+            .retain(|vertex| !dropped.contains(&vertex.v_id));
+        self.edges
+            .retain(|edge| !dropped.contains(&edge.source) && !dropped.contains(&edge.target));
+    }
+
+    /// Find an identity with its owner / resolve addresses.
+    /// Found records are cached, see `crate::cache`.
     pub async fn find_expand_identity(
         client: &Client<HttpConnector>,
         platform: &Platform,
         identity: &str,
+    ) -> Result<Option<ExpandIdentityRecord>, Error> {
+        let key = CacheKey::new(platform, identity, None);
+        if let Some(cached) = IDENTITY_CACHE.get(&key) {
+            return Ok(Some(cached));
+        }
+        let found = Self::query_expand_identity(client, platform, identity).await?;
+        if let Some(found) = found.as_ref() {
+            IDENTITY_CACHE.insert(key, found.clone(), identity_tags(found));
+        }
+        Ok(found)
+    }
+
+    /// Find the identity graph which `platform,identity` belongs to.
+    /// Found graphs are cached, see `crate::cache`.
+    pub async fn find_graph_by_platform_identity(
+        client: &Client<HttpConnector>,
+        platform: &Platform,
+        identity: &str,
+        reverse: Option<bool>,
+    ) -> Result<Option<IdentityGraph>, Error> {
+        let key = CacheKey::new(platform, identity, reverse);
+        if let Some(cached) = GRAPH_CACHE.get(&key) {
+            return Ok(Some(cached));
+        }
+        let found =
+            Self::query_graph_by_platform_identity(client, platform, identity, reverse).await?;
+        if let Some(found) = found.as_ref() {
+            GRAPH_CACHE.insert(key, found.clone(), graph_tags(found));
+        }
+        Ok(found)
+    }
+
+    async fn query_expand_identity(
+        client: &Client<HttpConnector>,
+        platform: &Platform,
+        identity: &str,
     ) -> Result<Option<ExpandIdentityRecord>, Error> {
         let encoded_identity = urlencoding::encode(identity);
         let uri: http::Uri = format!(
@@ -215,8 +420,8 @@ impl IdentityGraph {
                 ))
             })?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query find_expand_identity | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -250,7 +455,8 @@ impl IdentityGraph {
             }
         }
     }
-    pub async fn find_graph_by_platform_identity(
+
+    async fn query_graph_by_platform_identity(
         client: &Client<HttpConnector>,
         platform: &Platform,
         identity: &str,
@@ -293,8 +499,8 @@ impl IdentityGraph {
                 ))
             })?;
 
-        let mut resp = client.request(req).await.map_err(|err| {
-            Error::ManualHttpClientError(format!(
+        let mut resp = timed_request(client, req).await.map_err(|err| {
+            Error::DatabaseError(format!(
                 "query find_identity_graph | Fail to request: {:?}",
                 err.to_string()
             ))
@@ -354,6 +560,8 @@ pub struct ExpandIdentityRecord {
     pub record: IdentityRecord,
     pub owner_address: Option<Vec<Address>>,
     pub resolve_address: Option<Vec<Address>>,
+    /// Relative to the queried identity, see `IdentityGraph::confidences`.
+    pub confidence: Option<f64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -455,6 +663,7 @@ impl<'de> Deserialize<'de> for ExpandIdentityRecord {
                     }),
                     owner_address,
                     resolve_address,
+                    confidence: None,
                 };
                 Ok(expand_identity)
             }
@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod tests;
+
+use crate::config::C;
+use crate::error::Error;
+use crate::tigergraph::edge::{
+    Hold, HyperEdge, Resolve, Wrapper, HOLD_IDENTITY, HYPER_EDGE, RESOLVE, REVERSE_RESOLVE,
+};
+use crate::tigergraph::vertex::{IdentitiesGraph, Identity};
+use crate::tigergraph::{EdgeList, EdgeWrapperEnum};
+use crate::upstream::{
+    ens::{self, BasenameRecord},
+    EthereumRpc,
+};
+use crate::upstream::{
+    DataFetcher, DataSource, DomainNameSystem, Fetcher, Platform, Target, TargetProcessedList,
+};
+use crate::util::{naive_now, timestamp_to_naive};
+use async_trait::async_trait;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+/// Basenames (`*.base.eth`) read from the Registry, Registrar and resolvers on Base
+/// through `[upstream.base_rpc]`. Disabled if `rpc_url` is not configured.
+#[derive(Clone, Debug)]
+pub struct Basenames {}
+
+#[async_trait]
+impl Fetcher for Basenames {
+    async fn fetch(_target: &Target) -> Result<TargetProcessedList, Error> {
+        Ok(vec![])
+    }
+
+    async fn batch_fetch(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
+        if !Self::can_fetch(target) {
+            return Ok((vec![], vec![]));
+        }
+        let rpc = base_rpc().unwrap();
+        match target.platform()? {
+            Platform::Ethereum => {
+                let address = target.identity()?.to_lowercase();
+                let name = ens::lookup_basename_reverse(&rpc, &address).await?;
+                Ok(reverse_edges(&address, name))
+            }
+            Platform::Basenames => {
+                let name = ens::normalize(&target.identity()?)?;
+                match ens::lookup_basename(&rpc, &name).await? {
+                    Some(record) => Ok(name_edges(record)),
+                    None => {
+                        info!(?target, "Basenames: Name not registered");
+                        Ok((vec![], vec![]))
+                    }
+                }
+            }
+            _ => Ok((vec![], vec![])),
+        }
+    }
+
+    fn can_fetch(target: &Target) -> bool {
+        target.in_platform_supported(vec![Platform::Ethereum, Platform::Basenames])
+            && base_rpc().is_some()
+    }
+}
+
+impl Basenames {
+    /// Address Basename `name` resolves to, without fetching anything else.
+    /// Empty if it is not registered, or resolves nowhere.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let rpc = base_rpc().ok_or_else(|| {
+            Error::ParamError("Basenames: [upstream.base_rpc] not configured".to_string())
+        })?;
+        let name = ens::normalize(name)?;
+        Ok(ens::lookup_basename(&rpc, &name)
+            .await?
+            .and_then(|record| record.resolved_address)
+            .map(|address| (Platform::Ethereum, address))
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Endpoint in `[upstream.base_rpc]`, `None` if not configured.
+fn base_rpc() -> Option<EthereumRpc> {
+    let url = C.upstream.base_rpc.rpc_url.trim();
+    match url.is_empty() {
+        true => None,
+        false => Some(EthereumRpc::new(url)),
+    }
+}
+
+/// Ownership of a name, and its resolve record if it resolves to its owner.
+fn name_edges(record: BasenameRecord) -> (TargetProcessedList, EdgeList) {
+    let next_targets = vec![Target::Identity(Platform::Ethereum, record.owner.clone())];
+    let mut edges = EdgeList::new();
+    let hv = IdentitiesGraph::default();
+
+    let expired_at = record
+        .expires
+        .and_then(|expires| timestamp_to_naive(expires, 0));
+    let owner = Identity::new_address(Platform::Ethereum, &record.owner);
+    let mut basename = Identity::new_domain(Platform::Basenames, &record.name, false);
+    basename.expired_at = expired_at;
+    let ownership = Hold {
+        uuid: Uuid::new_v4(),
+        transaction: None,
+        id: record.name.clone(),
+        source: DataSource::Basenames,
+        created_at: None,
+        updated_at: naive_now(),
+        fetcher: DataFetcher::RelationService,
+        expired_at,
+    };
+
+    edges.push(EdgeWrapperEnum::new_hyper_edge(
+        HyperEdge {}.wrapper(&hv, &owner, HYPER_EDGE),
+    ));
+    let hd = ownership.wrapper(&owner, &basename, HOLD_IDENTITY);
+    edges.push(EdgeWrapperEnum::new_hold_identity(hd));
+
+    // Same as ENS: the name joins the identity graph only when it resolves to its owner.
+    if record.resolved_address.as_ref() == Some(&record.owner) {
+        debug!(domain = record.name, "Basenames: Resolved to owner");
+        edges.push(EdgeWrapperEnum::new_hyper_edge(
+            HyperEdge {}.wrapper(&hv, &basename, HYPER_EDGE),
+        ));
+        let rs = Resolve::new(
+            DataSource::Basenames,
+            DomainNameSystem::Basenames,
+            &record.name,
+        )
+        .wrapper(&basename, &owner, RESOLVE);
+        edges.push(EdgeWrapperEnum::new_resolve(rs));
+    }
+
+    (next_targets, edges)
+}
+
+/// Primary Basename of `address`, if it is set and normalized.
+/// Verified later against forward resolution of the name.
+fn reverse_edges(address: &str, name: Option<String>) -> (TargetProcessedList, EdgeList) {
+    let mut edges = EdgeList::new();
+    let hv = IdentitiesGraph::default();
+    let mut eth_identity = Identity::new_address(Platform::Ethereum, address);
+
+    // A reverse record not in its normalized form is invalid (ENSIP-15).
+    let Some(name) = name.filter(|name| ens::is_normalized(name)) else {
+        // Still save the address into the identity graph, as an isolated vertex.
+        edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+            &hv,
+            &eth_identity,
+            HYPER_EDGE,
+        )));
+        return (vec![], edges);
+    };
+    info!(address, name, "Basenames: Reverse record");
+
+    eth_identity.display_name = Some(name.clone());
+    eth_identity.reverse = Some(true);
+    let basename = Identity::new_domain(Platform::Basenames, &name, true);
+    let rr = Resolve::new(DataSource::Basenames, DomainNameSystem::Basenames, &name).wrapper(
+        &eth_identity,
+        &basename,
+        REVERSE_RESOLVE,
+    );
+    edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+        &hv,
+        &eth_identity,
+        HYPER_EDGE,
+    )));
+    edges.push(EdgeWrapperEnum::new_hyper_edge(
+        HyperEdge {}.wrapper(&hv, &basename, HYPER_EDGE),
+    ));
+    edges.push(EdgeWrapperEnum::new_reverse_resolve(rr));
+
+    (vec![Target::Identity(Platform::Basenames, name)], edges)
+}
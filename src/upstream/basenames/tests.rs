@@ -0,0 +1,62 @@
+use super::*;
+
+const OWNER: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+const OTHER: &str = "0x231b0ee14048e9dccd1d247744d114a4eb5e8e63";
+
+fn basename_record(resolved_address: Option<&str>) -> BasenameRecord {
+    BasenameRecord {
+        name: "jesse.base.eth".into(),
+        owner: OWNER.into(),
+        resolved_address: resolved_address.map(String::from),
+        expires: Some(2_000_000_000),
+    }
+}
+
+#[test]
+fn test_name_edges() {
+    let (next, edges) = name_edges(basename_record(Some(OWNER)));
+    assert_eq!(
+        next,
+        vec![Target::Identity(Platform::Ethereum, OWNER.into())]
+    );
+    // Hyper edges of owner and name, hold and resolve records.
+    assert_eq!(edges.len(), 4);
+    match &edges[1] {
+        EdgeWrapperEnum::HoldIdentity(wrapper) => {
+            assert_eq!(
+                wrapper.edge.expired_at,
+                timestamp_to_naive(2_000_000_000, 0)
+            );
+            assert_eq!(wrapper.target.platform, Platform::Basenames);
+        }
+        _ => panic!("expected Hold_Identity"),
+    }
+
+    // Resolving to someone else, or nothing: ownership only.
+    let (_, edges) = name_edges(basename_record(Some(OTHER)));
+    assert_eq!(edges.len(), 2);
+    let (_, edges) = name_edges(basename_record(None));
+    assert_eq!(edges.len(), 2);
+}
+
+#[test]
+fn test_reverse_edges() {
+    let (next, edges) = reverse_edges(OWNER, Some("jesse.base.eth".into()));
+    assert_eq!(
+        next,
+        vec![Target::Identity(
+            Platform::Basenames,
+            "jesse.base.eth".into()
+        )]
+    );
+    // Hyper edges of address and name, reverse resolve record.
+    assert_eq!(edges.len(), 3);
+
+    // Not set, or not normalized: the address only.
+    let (next, edges) = reverse_edges(OWNER, None);
+    assert!(next.is_empty());
+    assert_eq!(edges.len(), 1);
+    let (next, edges) = reverse_edges(OWNER, Some("Jesse.base.eth".into()));
+    assert!(next.is_empty());
+    assert_eq!(edges.len(), 1);
+}
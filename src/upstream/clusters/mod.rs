@@ -71,6 +71,17 @@ impl Fetcher for Clusters {
     }
 }
 
+impl Clusters {
+    /// Addresses (on any platform) cluster `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let metadatas = get_address_by_clusters(&name.to_lowercase()).await?;
+        Ok(metadatas
+            .into_iter()
+            .filter_map(|d| Some((d.platform.parse().ok()?, d.address)))
+            .collect())
+    }
+}
+
 async fn batch_fetch_by_address(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
     let platform = target.platform()?;
     let mut address = target.identity()?;
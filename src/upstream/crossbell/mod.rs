@@ -134,32 +134,34 @@ impl Fetcher for Crossbell {
     }
 }
 
-async fn query_by_handle(target: &Target) -> Result<Option<QueryResponse>, Error> {
+/// Characters of handle `name` (`*.csb`). Fails if Crossbell does not respond.
+async fn query_by_handle(name: &str) -> Result<Option<QueryResponse>, Error> {
     let query = QUERY_BY_HANDLE.to_string();
-    let target_var = target.identity()?;
-    let handle = target_var.trim_end_matches(".csb");
+    let handle = name.trim_end_matches(".csb");
     let client = GQLClient::new(&C.upstream.crossbell_api.url);
     let vars = QueryVars {
         target: handle.to_string(),
     };
     let resp = client.query_with_vars::<QueryResponse, QueryVars>(&query, vars);
 
-    let data: Option<QueryResponse> =
-        match tokio::time::timeout(std::time::Duration::from_secs(5), resp).await {
-            Ok(resp) => match resp {
-                Ok(resp) => resp,
-                Err(err) => {
-                    warn!(?target, ?err, "Crossbell: Failed to fetch");
-                    None
-                }
-            },
-            Err(_) => {
-                warn!(?target, "Crossbell timeout: no response in 5 seconds.");
-                None
-            }
-        };
+    match tokio::time::timeout(std::time::Duration::from_secs(5), resp).await {
+        Ok(resp) => resp.map_err(|err| Error::GraphQLError(format!("Crossbell: {:?}", err))),
+        Err(_) => Err(Error::GraphQLError(
+            "Crossbell timeout: no response in 5 seconds.".to_string(),
+        )),
+    }
+}
 
-    Ok(data)
+impl Crossbell {
+    /// Owner Crossbell handle `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let data = query_by_handle(name).await?;
+        Ok(data
+            .and_then(|res| res.characters.into_iter().next())
+            .map(|character| (Platform::Ethereum, character.owner.to_lowercase()))
+            .into_iter()
+            .collect())
+    }
 }
 
 async fn query_by_wallet(target: &Target) -> Result<Option<QueryResponse>, Error> {
@@ -190,7 +192,13 @@ async fn query_by_wallet(target: &Target) -> Result<Option<QueryResponse>, Error
 }
 
 async fn batch_fetch_by_handle(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
-    let data = query_by_handle(target).await?;
+    let data = match query_by_handle(&target.identity()?).await {
+        Ok(data) => data,
+        Err(err) => {
+            warn!(?target, %err, "Crossbell: Failed to fetch");
+            None
+        }
+    };
     if data.is_none() {
         info!(?target, "Crossbell: No result");
         return Ok((vec![], vec![]));
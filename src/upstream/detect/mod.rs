@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    error::Error,
+    upstream::{farcaster::warpcast::user_by_fid, normalize, Platform},
+};
+use regex::Regex;
+use tracing::warn;
+
+lazy_static! {
+    static ref EVM_ADDRESS: Regex = Regex::new(r"^0[xX][0-9a-fA-F]{40}$").unwrap();
+    static ref APTOS_ADDRESS: Regex = Regex::new(r"^0[xX][0-9a-fA-F]{64}$").unwrap();
+    static ref BTC_LEGACY_ADDRESS: Regex =
+        Regex::new(r"^[13][1-9A-HJ-NP-Za-km-z]{25,34}$").unwrap();
+    static ref BTC_BECH32_ADDRESS: Regex = Regex::new(r"^(bc1|BC1)[0-9a-zA-Z]{11,71}$").unwrap();
+    static ref TRON_ADDRESS: Regex = Regex::new(r"^T[1-9A-HJ-NP-Za-km-z]{33}$").unwrap();
+    static ref SOLANA_ADDRESS: Regex = Regex::new(r"^[1-9A-HJ-NP-Za-km-z]{32,44}$").unwrap();
+    static ref COSMOS_ADDRESS: Regex = Regex::new(r"^cosmos1[02-9ac-hj-np-z]{38,58}$").unwrap();
+    static ref STACKS_ADDRESS: Regex = Regex::new(r"^S[PMTN][0-9A-Z]{28,41}$").unwrap();
+    static ref NEAR_IMPLICIT_ADDRESS: Regex = Regex::new(r"^[0-9a-f]{64}$").unwrap();
+    static ref HANDLE: Regex = Regex::new(r"^[a-zA-Z0-9_\-]{1,39}$").unwrap();
+    static ref FID: Regex = Regex::new(r"^(?i:fid):\s*([0-9]+)$").unwrap();
+}
+
+/// Suffixes of name systems, mapped into the platform(s) of the name.
+/// Farcaster fnames may also be ENS names, so `.eth` yields both.
+/// Basenames are only read on Base, not as ENS names on L1.
+/// First matched suffix wins.
+const DOMAIN_SUFFIXES: &[(&str, &[Platform])] = &[
+    (".base.eth", &[Platform::Basenames]),
+    (".eth", &[Platform::ENS, Platform::Farcaster]),
+    (".bit", &[Platform::Dotbit]),
+    (".sol", &[Platform::SNS]),
+    (".lens", &[Platform::Lens]),
+    (".csb", &[Platform::Crossbell]),
+    (".gno", &[Platform::Genome]),
+    (".bnb", &[Platform::SpaceId]),
+    (".arb", &[Platform::SpaceId]),
+    (".near", &[Platform::Near]),
+    (".crypto", &[Platform::UnstoppableDomains]),
+    (".nft", &[Platform::UnstoppableDomains]),
+    (".x", &[Platform::UnstoppableDomains]),
+    (".wallet", &[Platform::UnstoppableDomains]),
+    (".blockchain", &[Platform::UnstoppableDomains]),
+    (".bitcoin", &[Platform::UnstoppableDomains]),
+    (".dao", &[Platform::UnstoppableDomains]),
+    (".888", &[Platform::UnstoppableDomains]),
+    (".zil", &[Platform::UnstoppableDomains]),
+    (".polygon", &[Platform::UnstoppableDomains]),
+];
+
+/// An `(platform, identity)` candidate inferred from free-form input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Detected {
+    pub platform: Platform,
+    pub identity: String,
+}
+
+impl Detected {
+    fn new(platform: Platform, identity: &str) -> Self {
+        Detected {
+            platform,
+            identity: normalize(platform, identity),
+        }
+    }
+}
+
+/// Infer candidate `(platform, identity)` pairs from any user input, e.g.
+/// `"0xabc…"`, `"vitalik.eth"`, `"name.bit"`, `"@handle"`, `"bc1…"`, `"twitter:handle"`.
+/// Candidates are ordered from the most to the least likely one.
+/// `fid:123` is not handled here since it needs a lookup, see `resolve_input`.
+pub fn detect(input: &str) -> Vec<Detected> {
+    let input = input.trim();
+    if input.is_empty() {
+        return vec![];
+    }
+
+    // Explicit `platform:identity`.
+    if let Some((prefix, rest)) = input.split_once(':') {
+        if let Ok(platform) = prefix.trim().to_lowercase().parse::<Platform>() {
+            let rest = rest.trim();
+            if !rest.is_empty() && platform != Platform::Unknown {
+                return vec![Detected::new(platform, rest)];
+            }
+        }
+    }
+
+    if EVM_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Ethereum, input)];
+    }
+    if APTOS_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Aptos, input)];
+    }
+    if BTC_BECH32_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Bitcoin, input)];
+    }
+    if COSMOS_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Cosmos, input)];
+    }
+    if STACKS_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Stacks, input)];
+    }
+    if TRON_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Tron, input)];
+    }
+    if BTC_LEGACY_ADDRESS.is_match(input) {
+        // Base58 alphabet overlaps: a legacy BTC address could also be a short Solana pubkey.
+        let mut candidates = vec![Detected::new(Platform::Bitcoin, input)];
+        if SOLANA_ADDRESS.is_match(input) {
+            candidates.push(Detected::new(Platform::Solana, input));
+        }
+        return candidates;
+    }
+    if SOLANA_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Solana, input)];
+    }
+    if NEAR_IMPLICIT_ADDRESS.is_match(input) {
+        return vec![Detected::new(Platform::Near, input)];
+    }
+
+    let lowercased = input.to_lowercase();
+    for (suffix, platforms) in DOMAIN_SUFFIXES {
+        if lowercased.ends_with(suffix) && lowercased.len() > suffix.len() {
+            return platforms
+                .iter()
+                .map(|platform| Detected::new(*platform, &lowercased))
+                .collect();
+        }
+    }
+
+    // Social handles.
+    let handle = input.trim_start_matches('@');
+    if HANDLE.is_match(handle) {
+        let mut candidates = vec![
+            Detected::new(Platform::Twitter, handle),
+            Detected::new(Platform::Farcaster, handle),
+        ];
+        if !input.starts_with('@') {
+            candidates.push(Detected::new(Platform::Github, handle));
+        }
+        return candidates;
+    }
+
+    vec![]
+}
+
+/// Same as `detect`, but also resolves inputs which need a lookup to
+/// know the identity, e.g. `fid:123` to its Farcaster username.
+pub async fn resolve_input(input: &str) -> Result<Vec<Detected>, Error> {
+    if let Some(captures) = FID.captures(input.trim()) {
+        let fid: i64 = captures[1].parse()?;
+        return match user_by_fid(fid).await? {
+            Some(user) => Ok(vec![Detected::new(Platform::Farcaster, &user.username)]),
+            None => {
+                warn!(fid, "No Farcaster user found for fid");
+                Ok(vec![])
+            }
+        };
+    }
+    Ok(detect(input))
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+fn platforms(input: &str) -> Vec<Platform> {
+    detect(input).into_iter().map(|d| d.platform).collect()
+}
+
+#[test]
+fn test_detect_addresses() {
+    assert_eq!(
+        detect("0xD8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+        vec![Detected::new(
+            Platform::Ethereum,
+            "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
+        )]
+    );
+    assert_eq!(
+        platforms("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"),
+        vec![Platform::Bitcoin]
+    );
+    assert_eq!(
+        platforms("TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7"),
+        vec![Platform::Tron]
+    );
+    assert_eq!(
+        platforms("HKKp49qGWXd639QsuH7JiLijfVW5UtCVY4s1n2HANwEA"),
+        vec![Platform::Solana]
+    );
+    assert_eq!(
+        platforms("cosmos1fl48vsnmsdzcv85q5d2q4z5ajdha8yu34mf0eh"),
+        vec![Platform::Cosmos]
+    );
+}
+
+#[test]
+fn test_detect_domains() {
+    assert_eq!(
+        platforms("Vitalik.eth"),
+        vec![Platform::ENS, Platform::Farcaster]
+    );
+    assert_eq!(detect("Vitalik.eth")[0].identity, "vitalik.eth");
+    assert_eq!(platforms("jesse.base.eth"), vec![Platform::Basenames]);
+    assert_eq!(platforms("threebody.bit"), vec![Platform::Dotbit]);
+    assert_eq!(platforms("bonfida.sol"), vec![Platform::SNS]);
+    assert_eq!(platforms("stani.lens"), vec![Platform::Lens]);
+    assert_eq!(platforms("brad.crypto"), vec![Platform::UnstoppableDomains]);
+    assert!(platforms(".eth").is_empty());
+}
+
+#[test]
+fn test_detect_handles() {
+    assert_eq!(
+        platforms("@suji_yan"),
+        vec![Platform::Twitter, Platform::Farcaster]
+    );
+    assert_eq!(
+        platforms("suji_yan"),
+        vec![Platform::Twitter, Platform::Farcaster, Platform::Github]
+    );
+}
+
+#[test]
+fn test_detect_explicit_platform() {
+    assert_eq!(
+        detect("twitter: suji_yan"),
+        vec![Detected::new(Platform::Twitter, "suji_yan")]
+    );
+    assert!(detect("fid:3").is_empty());
+    assert!(detect("   ").is_empty());
+}
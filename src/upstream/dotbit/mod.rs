@@ -185,6 +185,21 @@ pub struct AccountListResponse {
 
 const UNKNOWN_OWNER: &str = "0x0000000000000000000000000000000000000000";
 
+impl DotBit {
+    /// Owner .bit account `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let info = query_by_handle(&Platform::Dotbit, name).await?;
+        Ok(info
+            .account_info
+            .map(|account| {
+                let platform: Platform = account.owner_algorithm_id.into();
+                (platform, account.owner_key.to_lowercase())
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
 async fn query_by_handle(_platform: &Platform, name: &str) -> Result<AccountInfoData, Error> {
     let request_acc = AccInfoRequestParams {
         account: name.to_string(),
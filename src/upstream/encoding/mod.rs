@@ -0,0 +1,112 @@
+//! Address encodings shared by several chains: base58check and bech32(m).
+
+#[cfg(test)]
+mod tests;
+
+use sha2::{Digest, Sha256};
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+/// Checksum constant of bech32 (BIP-173).
+pub const BECH32_CONST: u32 = 1;
+/// Checksum constant of bech32m (BIP-350), used by segwit v1+.
+pub const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// Payload of a base58check string (version byte included) if its checksum is right.
+pub fn base58check(address: &str) -> Option<Vec<u8>> {
+    let decoded = bs58::decode(address).into_vec().ok()?;
+    if decoded.len() < 5 {
+        return None;
+    }
+    let (payload, checksum) = decoded.split_at(decoded.len() - 4);
+    let hash = Sha256::digest(Sha256::digest(payload));
+    (hash[..4] == *checksum).then(|| payload.to_vec())
+}
+
+/// base58check string of `payload` (version byte included).
+pub fn base58check_encode(payload: &[u8]) -> String {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    let mut data = payload.to_vec();
+    data.extend(&hash[..4]);
+    bs58::encode(data).into_string()
+}
+
+/// Human-readable part, 5-bit data (checksum excluded) and checksum residue
+/// of a lowercase bech32(m) string.
+pub fn bech32_decode(address: &str) -> Option<(&str, Vec<u8>, u32)> {
+    let (hrp, data) = address.rsplit_once('1')?;
+    if hrp.is_empty() || data.len() < 6 || address.len() > 90 {
+        return None;
+    }
+    let data: Vec<u8> = data
+        .chars()
+        .map(|c| BECH32_CHARSET.find(c).map(|value| value as u8))
+        .collect::<Option<_>>()?;
+    let checksum = bech32_polymod(hrp_expand(hrp).chain(data.iter().copied()));
+    Some((hrp, data[..data.len() - 6].to_vec(), checksum))
+}
+
+/// bech32 (`constant` = `BECH32_CONST`) or bech32m (`BECH32M_CONST`) string of 5-bit `data`.
+pub fn bech32_encode(hrp: &str, data: &[u8], constant: u32) -> String {
+    let values = hrp_expand(hrp).chain(data.iter().copied()).chain([0; 6]);
+    let checksum = bech32_polymod(values) ^ constant;
+    let checksum = (0..6).map(|i| ((checksum >> (5 * (5 - i))) & 31) as u8);
+    let charset = BECH32_CHARSET.as_bytes();
+    let encoded: String = data
+        .iter()
+        .copied()
+        .chain(checksum)
+        .map(|value| charset[value as usize] as char)
+        .collect();
+    format!("{}1{}", hrp, encoded)
+}
+
+/// Regroup bits of `data` from `from`-bit to `to`-bit values, e.g. bytes into bech32 5-bit data.
+/// `None` if `data` has values too large, or leftover bits when not padding.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut converted = vec![];
+    let max = (1 << to) - 1;
+    let max_acc = (1 << (from + to - 1)) - 1;
+    for value in data {
+        if (*value as u32) >> from != 0 {
+            return None;
+        }
+        acc = ((acc << from) | *value as u32) & max_acc;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            converted.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            converted.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max) != 0 {
+        return None;
+    }
+    Some(converted)
+}
+
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|b| b & 31))
+}
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut checksum: u32 = 1;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
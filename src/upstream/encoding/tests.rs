@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn test_base58check() {
+    let address = "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2";
+    let payload = base58check(address).unwrap();
+    assert_eq!(payload.len(), 21);
+    assert_eq!(base58check_encode(&payload), address);
+    assert_eq!(base58check("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3"), None);
+}
+
+#[test]
+fn test_bech32() {
+    for (address, constant) in [
+        ("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq", BECH32_CONST),
+        (
+            "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297",
+            BECH32M_CONST,
+        ),
+    ] {
+        let (hrp, data, checksum) = bech32_decode(address).unwrap();
+        assert_eq!(checksum, constant);
+        assert_eq!(bech32_encode(hrp, &data, constant), address);
+    }
+}
+
+#[test]
+fn test_convert_bits() {
+    assert_eq!(convert_bits(&[0xff], 8, 5, true), Some(vec![31, 28]));
+    let bytes = vec![0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94];
+    let words = convert_bits(&bytes, 8, 5, true).unwrap();
+    assert_eq!(convert_bits(&words, 5, 8, false), Some(bytes));
+    // Value too large
+    assert_eq!(convert_bits(&[32], 5, 8, false), None);
+}
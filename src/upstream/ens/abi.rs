@@ -0,0 +1,206 @@
+//! Minimal Solidity ABI encoding / decoding, enough for calling ENS contracts.
+
+use sha3::{Digest, Keccak256};
+
+/// Argument of a contract call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// `bytes32` or `uint256`.
+    Word([u8; 32]),
+    /// `bytes`
+    Bytes(Vec<u8>),
+    /// `string`
+    String(String),
+    /// `T[]`
+    Array(Vec<Token>),
+    /// `(T1, T2, ...)`
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Word(_) => false,
+            Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+        }
+    }
+}
+
+/// First 4 bytes of `keccak256(signature)`, e.g. `selector("addr(bytes32)")`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Calldata of calling `signature` with `args`.
+pub fn encode_call(signature: &str, args: &[Token]) -> Vec<u8> {
+    encode_call_selector(selector(signature), args)
+}
+
+/// Calldata of calling the function of `selector` with `args`.
+pub fn encode_call_selector(selector: [u8; 4], args: &[Token]) -> Vec<u8> {
+    let mut data = selector.to_vec();
+    data.extend(encode(args));
+    data
+}
+
+/// ABI-encode `tokens` as a tuple.
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let head_length: usize = tokens
+        .iter()
+        .map(|token| match token.is_dynamic() {
+            true => 32,
+            false => encode_token(token).len(),
+        })
+        .sum();
+    let mut head: Vec<u8> = vec![];
+    let mut tail: Vec<u8> = vec![];
+    for token in tokens {
+        match token.is_dynamic() {
+            true => {
+                head.extend(uint_word((head_length + tail.len()) as u64));
+                tail.extend(encode_token(token));
+            }
+            false => head.extend(encode_token(token)),
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+/// `address` as a word. `None` if not a `0x`-prefixed 20 bytes hex.
+pub fn address_word(address: &str) -> Option<[u8; 32]> {
+    let address: [u8; 20] = hex::decode(address.strip_prefix("0x")?)
+        .ok()?
+        .try_into()
+        .ok()?;
+    let mut word = [0; 32];
+    word[12..].copy_from_slice(&address);
+    Some(word)
+}
+
+/// `bool` as a word.
+pub fn bool_word(value: bool) -> [u8; 32] {
+    uint_word(value as u64)
+}
+
+/// `address` at the `index`-th word of `data`. `None` if out of range or zero address.
+pub fn decode_address(data: &[u8], index: usize) -> Option<String> {
+    let word = word_at(data, index)?;
+    if word[12..].iter().all(|b| *b == 0) {
+        return None;
+    }
+    Some(format!("0x{}", hex::encode(&word[12..])))
+}
+
+/// `bytes` whose offset is at the `index`-th word of `data`.
+pub fn decode_bytes(data: &[u8], index: usize) -> Option<Vec<u8>> {
+    let offset = decode_uint(data, index)?;
+    let length = decode_uint(data.get(offset..)?, 0)?;
+    // Offset and length come from the contract, so they may overflow.
+    let start = offset.checked_add(32)?;
+    let end = start.checked_add(length)?;
+    data.get(start..end).map(|bytes| bytes.to_vec())
+}
+
+/// `string` whose offset is at the `index`-th word of `data`.
+pub fn decode_string(data: &[u8], index: usize) -> Option<String> {
+    String::from_utf8(decode_bytes(data, index)?).ok()
+}
+
+/// `string[]` whose offset is at the `index`-th word of `data`.
+pub fn decode_string_array(data: &[u8], index: usize) -> Option<Vec<String>> {
+    let offset = decode_uint(data, index)?;
+    let array = data.get(offset..)?;
+    let length = decode_uint(array, 0)?;
+    // Offsets of elements are relative to the start of their contents.
+    let elements = array.get(32..)?;
+    (0..length)
+        .map(|index| decode_string(elements, index))
+        .collect()
+}
+
+/// Elements of a `T[]` of dynamic `T` whose offset is at the `index`-th word of `data`.
+/// Each element is the data it is encoded in, to read its fields by their index from.
+pub fn decode_dynamic_array(data: &[u8], index: usize) -> Option<Vec<&[u8]>> {
+    let offset = decode_uint(data, index)?;
+    let array = data.get(offset..)?;
+    let length = decode_uint(array, 0)?;
+    // Offsets of elements are relative to the start of their contents.
+    let elements = array.get(32..)?;
+    (0..length)
+        .map(|index| elements.get(decode_uint(elements, index)?..))
+        .collect()
+}
+
+/// `bytes4` at the `index`-th word of `data`.
+pub fn decode_bytes4(data: &[u8], index: usize) -> Option<[u8; 4]> {
+    word_at(data, index)?[..4].try_into().ok()
+}
+
+/// DNS wire format of `name`, as taken by ENSIP-10 resolvers:
+/// each label prefixed by its length, terminated by a zero.
+pub fn dns_encode(name: &str) -> Vec<u8> {
+    let mut encoded = vec![];
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        // Labels longer than 255 bytes are hashed, as `[labelhash]`.
+        match u8::try_from(label.len()) {
+            Ok(length) => {
+                encoded.push(length);
+                encoded.extend(label.as_bytes());
+            }
+            Err(_) => {
+                let hashed = format!("[{}]", hex::encode(super::labelhash(label)));
+                encoded.push(hashed.len() as u8);
+                encoded.extend(hashed.as_bytes());
+            }
+        }
+    }
+    encoded.push(0);
+    encoded
+}
+
+/// `uint256` at the `index`-th word of `data`, if it fits in `usize`.
+pub fn decode_uint(data: &[u8], index: usize) -> Option<usize> {
+    let word = word_at(data, index)?;
+    if word[..24].iter().any(|b| *b != 0) {
+        return None;
+    }
+    usize::try_from(u64::from_be_bytes(word[24..].try_into().ok()?)).ok()
+}
+
+fn word_at(data: &[u8], index: usize) -> Option<&[u8]> {
+    let start = index.checked_mul(32)?;
+    data.get(start..start.checked_add(32)?)
+}
+
+fn uint_word(value: u64) -> [u8; 32] {
+    let mut word = [0; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Encoding of `token` alone: in place in the head if static, or in the tail if dynamic.
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Word(word) => word.to_vec(),
+        Token::Bytes(bytes) => encode_bytes(bytes),
+        Token::String(string) => encode_bytes(string.as_bytes()),
+        Token::Array(tokens) => {
+            let mut encoded = uint_word(tokens.len() as u64).to_vec();
+            encoded.extend(encode(tokens));
+            encoded
+        }
+        Token::Tuple(tokens) => encode(tokens),
+    }
+}
+
+/// Length, then content padded to 32 bytes.
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = uint_word(bytes.len() as u64).to_vec();
+    encoded.extend(bytes);
+    let padding = (32 - bytes.len() % 32) % 32;
+    encoded.extend(vec![0; padding]);
+    encoded
+}
@@ -0,0 +1,148 @@
+//! Basenames (`*.base.eth`): ENS names issued on Base, read from their contracts there.
+//! Registry and resolvers work the same as ENS ones on Ethereum.
+
+#[cfg(test)]
+mod tests;
+
+use super::{
+    abi::{decode_address, decode_string, decode_uint, encode_call, Token},
+    labelhash, namehash, Hash,
+};
+use crate::{
+    error::Error,
+    upstream::ethereum::{CallOutput, EthereumRpc},
+};
+
+/// Registry of Basenames on Base.
+pub const BASE_REGISTRY: &str = "0xb94704422c2a1e396835a571837aa5ae53285a95";
+/// ERC-721 registrar of `*.base.eth`. Token ID is `uint256(labelhash(label))`.
+pub const BASE_REGISTRAR: &str = "0x03c4738ee98ae44591e1a4a4f3cab6641d95dd9a";
+/// Parent of all Basenames.
+pub const BASE_PARENT: &str = "base.eth";
+/// Reverse namespace of Base ([ENSIP-19](https://docs.ens.domains/ensip/19)):
+/// its coin type `0x80000000 | 8453` in hex.
+const BASE_REVERSE_NAMESPACE: &str = "80002105.reverse";
+
+/// Records of a Basename.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasenameRecord {
+    pub name: String,
+    /// Holder of the token in `BASE_REGISTRAR`, or owner in `BASE_REGISTRY` for subnames.
+    pub owner: String,
+    /// `addr` record set in its resolver.
+    pub resolved_address: Option<String>,
+    /// Expiry in `BASE_REGISTRAR`, as a unix timestamp. `None` for subnames.
+    pub expires: Option<i64>,
+}
+
+/// Owner, `addr` record and expiry of a normalized Basename. `None` if not registered.
+pub async fn lookup_basename(
+    rpc: &EthereumRpc,
+    name: &str,
+) -> Result<Option<BasenameRecord>, Error> {
+    let node = namehash(name);
+    let Some(registry_owner) = registry_address(rpc, "owner(bytes32)", &node).await? else {
+        return Ok(None);
+    };
+    let (owner, expires) = match registrar_label(name) {
+        Some(label) => {
+            let id = [Token::Word(labelhash(label))];
+            let holder = match rpc
+                .try_call(BASE_REGISTRAR, &encode_call("ownerOf(uint256)", &id))
+                .await?
+            {
+                CallOutput::Return(output) => decode_address(&output, 0),
+                // Expired, the registry is not cleaned up until someone registers it again.
+                CallOutput::Revert(_) => None,
+            };
+            let output = rpc
+                .call(BASE_REGISTRAR, &encode_call("nameExpires(uint256)", &id))
+                .await?;
+            let expires = decode_uint(&output, 0).map(|expires| expires as i64);
+            (holder.unwrap_or(registry_owner), expires)
+        }
+        None => (registry_owner, None),
+    };
+    let resolved_address = match registry_address(rpc, "resolver(bytes32)", &node).await? {
+        Some(resolver) => {
+            match rpc
+                .try_call(
+                    &resolver,
+                    &encode_call("addr(bytes32)", &[Token::Word(node)]),
+                )
+                .await?
+            {
+                CallOutput::Return(output) => decode_address(&output, 0),
+                // Resolver without `addr`.
+                CallOutput::Revert(_) => None,
+            }
+        }
+        None => None,
+    };
+    Ok(Some(BasenameRecord {
+        name: name.to_string(),
+        owner,
+        resolved_address,
+        expires,
+    }))
+}
+
+/// Primary Basename of `address` (`0x`-prefixed), set in its reverse record on Base.
+/// `None` if not set. Not checked to resolve back to `address`.
+pub async fn lookup_basename_reverse(
+    rpc: &EthereumRpc,
+    address: &str,
+) -> Result<Option<String>, Error> {
+    let node = namehash(&reverse_name(address));
+    let Some(resolver) = registry_address(rpc, "resolver(bytes32)", &node).await? else {
+        return Ok(None);
+    };
+    match rpc
+        .try_call(
+            &resolver,
+            &encode_call("name(bytes32)", &[Token::Word(node)]),
+        )
+        .await?
+    {
+        CallOutput::Return(output) => Ok(decode_string(&output, 0).filter(|name| !name.is_empty())),
+        // Resolver without `name`.
+        CallOutput::Revert(_) => Ok(None),
+    }
+}
+
+/// Name of the reverse record of `address` on Base, e.g. `d8da...6045.80002105.reverse`.
+pub fn reverse_name(address: &str) -> String {
+    format!(
+        "{}.{}",
+        address.trim_start_matches("0x").to_lowercase(),
+        BASE_REVERSE_NAMESPACE
+    )
+}
+
+/// Whether `name` is a Basename (`*.base.eth`). Basenames live on Base, not in ENS on L1.
+pub fn is_basename(name: &str) -> bool {
+    name.strip_suffix(BASE_PARENT)
+        .is_some_and(|label| label.ends_with('.') && label.len() > 1)
+}
+
+/// Label of a second-level Basename (`label.base.eth`), which is a token of `BASE_REGISTRAR`.
+pub fn registrar_label(name: &str) -> Option<&str> {
+    name.strip_suffix(BASE_PARENT)?
+        .strip_suffix('.')
+        .filter(|label| !label.is_empty() && !label.contains('.'))
+}
+
+/// Address stored for `node` in `BASE_REGISTRY` by getter `signature`, e.g. `owner(bytes32)`.
+async fn registry_address(
+    rpc: &EthereumRpc,
+    signature: &str,
+    node: &Hash,
+) -> Result<Option<String>, Error> {
+    let output = rpc
+        .call(
+            BASE_REGISTRY,
+            &encode_call(signature, &[Token::Word(*node)]),
+        )
+        .await?;
+    Ok(decode_address(&output, 0))
+}
@@ -0,0 +1,131 @@
+use super::*;
+use crate::upstream::ens::{
+    abi,
+    tests::{address_word, call, serve_rpc, word, RESOLVER, VITALIK},
+    to_hex,
+};
+
+#[test]
+fn test_basename_helpers() {
+    assert_eq!(
+        reverse_name("0xD8DA6BF26964AF9D7EED9E03E53415D37AA96045"),
+        "d8da6bf26964af9d7eed9e03e53415d37aa96045.80002105.reverse"
+    );
+    assert_eq!(
+        to_hex(&namehash("base.eth")),
+        "0xff1e3c0eb00ec714e34b6114125fbde1dea2f24a72fbf672e7b7fd5690328e10"
+    );
+    assert_eq!(registrar_label("jesse.base.eth"), Some("jesse"));
+    // Subnames, and the parent itself
+    assert_eq!(registrar_label("pay.jesse.base.eth"), None);
+    assert_eq!(registrar_label("base.eth"), None);
+    assert_eq!(registrar_label("jesse.eth"), None);
+
+    assert!(is_basename("jesse.base.eth"));
+    assert!(is_basename("pay.jesse.base.eth"));
+    assert!(!is_basename("base.eth"));
+    assert!(!is_basename("jessebase.eth"));
+}
+
+#[tokio::test]
+async fn test_lookup_basename() -> Result<(), Error> {
+    let node = namehash("jesse.base.eth");
+    let id = labelhash("jesse");
+    let rpc = serve_rpc(vec![
+        (
+            BASE_REGISTRY,
+            call("owner(bytes32)", node),
+            address_word(RESOLVER).to_vec(),
+        ),
+        (
+            BASE_REGISTRAR,
+            call("ownerOf(uint256)", id),
+            address_word(VITALIK).to_vec(),
+        ),
+        (
+            BASE_REGISTRAR,
+            call("nameExpires(uint256)", id),
+            word(2_000_000_000).to_vec(),
+        ),
+        (
+            BASE_REGISTRY,
+            call("resolver(bytes32)", node),
+            address_word(RESOLVER).to_vec(),
+        ),
+        (
+            RESOLVER,
+            call("addr(bytes32)", node),
+            address_word(VITALIK).to_vec(),
+        ),
+    ]);
+    assert_eq!(
+        lookup_basename(&rpc, "jesse.base.eth").await?,
+        Some(BasenameRecord {
+            name: "jesse.base.eth".into(),
+            owner: VITALIK.into(),
+            resolved_address: Some(VITALIK.into()),
+            expires: Some(2_000_000_000),
+        })
+    );
+
+    // Expired: `ownerOf` reverts, falls back to the registry.
+    let node = namehash("expired.base.eth");
+    let id = labelhash("expired");
+    let rpc = serve_rpc(vec![
+        (
+            BASE_REGISTRY,
+            call("owner(bytes32)", node),
+            address_word(VITALIK).to_vec(),
+        ),
+        (
+            BASE_REGISTRAR,
+            call("nameExpires(uint256)", id),
+            word(1_700_000_000).to_vec(),
+        ),
+        (BASE_REGISTRY, call("resolver(bytes32)", node), vec![0; 32]),
+    ]);
+    let record = lookup_basename(&rpc, "expired.base.eth")
+        .await?
+        .expect("registered");
+    assert_eq!(record.owner, VITALIK);
+    assert_eq!(record.resolved_address, None);
+    assert_eq!(record.expires, Some(1_700_000_000));
+
+    // Not registered
+    let rpc = serve_rpc(vec![(
+        BASE_REGISTRY,
+        call("owner(bytes32)", namehash("unregistered.base.eth")),
+        vec![0; 32],
+    )]);
+    assert_eq!(lookup_basename(&rpc, "unregistered.base.eth").await?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lookup_basename_reverse() -> Result<(), Error> {
+    let node = namehash(&reverse_name(VITALIK));
+    let rpc = serve_rpc(vec![
+        (
+            BASE_REGISTRY,
+            call("resolver(bytes32)", node),
+            address_word(RESOLVER).to_vec(),
+        ),
+        (
+            RESOLVER,
+            call("name(bytes32)", node),
+            abi::encode(&[abi::Token::String("jesse.base.eth".into())]),
+        ),
+        (
+            BASE_REGISTRY,
+            call("resolver(bytes32)", namehash(&reverse_name(RESOLVER))),
+            vec![0; 32],
+        ),
+    ]);
+    assert_eq!(
+        lookup_basename_reverse(&rpc, VITALIK).await?,
+        Some("jesse.base.eth".into())
+    );
+    // No reverse resolver: no primary name.
+    assert_eq!(lookup_basename_reverse(&rpc, RESOLVER).await?, None);
+    Ok(())
+}
@@ -0,0 +1,325 @@
+//! CCIP-read ([EIP-3668](https://eips.ethereum.org/EIPS/eip-3668)): a contract reverts with
+//! `OffchainLookup` to have the caller fetch data from its gateways, then call it back with it.
+
+use super::abi::{
+    decode_address, decode_bytes, decode_bytes4, decode_string_array, encode_call_selector,
+    selector, Token,
+};
+use crate::{
+    error::Error,
+    upstream::ethereum::{decode_hex, CallOutput, EthereumRpc},
+    util::{make_client, request_with_timeout},
+};
+use futures::future::BoxFuture;
+use http::StatusCode;
+use hyper::{
+    body::HttpBody as _,
+    client::{
+        connect::{
+            dns::{GaiResolver, Name},
+            Connect,
+        },
+        HttpConnector,
+    },
+    service::Service,
+    Body, Client, Method, Response,
+};
+use hyper_tls::HttpsConnector;
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    task::{Context, Poll},
+};
+use tracing::warn;
+use url::{Host, Url};
+
+/// Max `OffchainLookup`s followed in a single call.
+const MAX_LOOKUPS: usize = 4;
+/// Max size of a gateway response body, in bytes.
+const MAX_RESPONSE_SIZE: usize = 1 << 20;
+
+/// `error OffchainLookup(address sender, string[] urls, bytes callData,
+/// bytes4 callbackFunction, bytes extraData)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffchainLookup {
+    pub sender: String,
+    pub urls: Vec<String>,
+    pub call_data: Vec<u8>,
+    pub callback: [u8; 4],
+    pub extra_data: Vec<u8>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GatewayResponse {
+    data: String,
+}
+
+impl OffchainLookup {
+    /// Decode revert data. `None` if it is not an `OffchainLookup`.
+    pub fn decode(revert: &[u8]) -> Option<Self> {
+        let data = revert.strip_prefix(&selector(
+            "OffchainLookup(address,string[],bytes,bytes4,bytes)",
+        ))?;
+        Some(OffchainLookup {
+            sender: decode_address(data, 0)?,
+            urls: decode_string_array(data, 1)?,
+            call_data: decode_bytes(data, 2)?,
+            callback: decode_bytes4(data, 3)?,
+            extra_data: decode_bytes(data, 4)?,
+        })
+    }
+
+    /// Try gateways in order. A `4xx` from one is final, other errors fall through to the next.
+    /// Gateway URLs come from the contract, so only public HTTPS ones are fetched,
+    /// unless `local_gateways` is set.
+    async fn fetch_gateways<C>(
+        &self,
+        client: &Client<C>,
+        local_gateways: bool,
+    ) -> Result<Vec<u8>, Error>
+    where
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        let sender = self.sender.clone();
+        let data = format!("0x{}", hex::encode(&self.call_data));
+        let mut last_error = Error::General(
+            "CCIP-read: no gateway URL".to_string(),
+            StatusCode::BAD_GATEWAY,
+        );
+        for url in self.urls.iter() {
+            let uri = url.replace("{sender}", &sender).replace("{data}", &data);
+            if !local_gateways {
+                if let Err(err) = check_gateway_url(&uri) {
+                    warn!(url, %err, "CCIP-read: gateway rejected");
+                    last_error = err;
+                    continue;
+                }
+            }
+            // GET if `{data}` is in the URL, POST otherwise.
+            let req = match url.contains("{data}") {
+                true => hyper::Request::builder()
+                    .method(Method::GET)
+                    .uri(uri)
+                    .body(Body::empty()),
+                false => hyper::Request::builder()
+                    .method(Method::POST)
+                    .uri(uri)
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(
+                        json!({"data": data, "sender": sender}).to_string(),
+                    )),
+            }
+            .map_err(|err| Error::ParamError(format!("CCIP-read Build Request Error {}", err)))?;
+
+            match request_with_timeout(client, req, None).await {
+                Ok(mut resp) if resp.status().is_success() => {
+                    let body = read_body(&mut resp, MAX_RESPONSE_SIZE).await?;
+                    let resp: GatewayResponse = serde_json::from_slice(&body)?;
+                    return decode_hex(&resp.data);
+                }
+                Ok(resp) if resp.status().is_client_error() => {
+                    return Err(Error::General(
+                        format!("CCIP-read gateway {} error: {}", url, resp.status()),
+                        StatusCode::BAD_GATEWAY,
+                    ));
+                }
+                Ok(resp) => {
+                    warn!(url, status = %resp.status(), "CCIP-read: gateway failed");
+                    last_error = Error::General(
+                        format!("CCIP-read gateway {} error: {}", url, resp.status()),
+                        StatusCode::BAD_GATEWAY,
+                    );
+                }
+                Err(err) => {
+                    warn!(url, %err, "CCIP-read: gateway failed");
+                    last_error = err;
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Client of gateways, over HTTPS only, to hosts resolved to public addresses only.
+fn gateway_client() -> Client<HttpsConnector<HttpConnector<PublicResolver>>> {
+    let mut http = HttpConnector::new_with_resolver(PublicResolver(GaiResolver::new()));
+    http.enforce_http(false);
+    let mut https = HttpsConnector::new_with_connector(http);
+    https.https_only(true);
+    Client::builder().build::<_, Body>(https)
+}
+
+/// Check a gateway `url` before fetching it: HTTPS, to a host which is not an address
+/// in a private network. Hosts by name are checked once resolved, by `PublicResolver`.
+pub fn check_gateway_url(url: &str) -> Result<(), Error> {
+    let rejected = |reason: &str| {
+        Error::General(
+            format!("CCIP-read: gateway {} rejected: {}", url, reason),
+            StatusCode::BAD_GATEWAY,
+        )
+    };
+    let parsed = Url::parse(url).map_err(|err| rejected(&err.to_string()))?;
+    if parsed.scheme() != "https" {
+        return Err(rejected("not HTTPS"));
+    }
+    match parsed.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_lowercase();
+            match domain == "localhost" || domain.ends_with(".localhost") {
+                true => Err(rejected("local host")),
+                false => Ok(()),
+            }
+        }
+        Some(Host::Ipv4(ip)) if is_public_ip(IpAddr::V4(ip)) => Ok(()),
+        Some(Host::Ipv6(ip)) if is_public_ip(IpAddr::V6(ip)) => Ok(()),
+        Some(_) => Err(rejected("not a public address")),
+        None => Err(rejected("no host")),
+    }
+}
+
+/// Check if `ip` is routable on the Internet. Loopback, private, link-local (cloud metadata
+/// `169.254.169.254` included), shared (`100.64.0.0/10`), multicast and reserved ones are not.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ipv4) => is_public_ipv4(ipv4),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // `0.0.0.0/8`
+        || a == 0
+        // Shared address space `100.64.0.0/10`
+        || (a == 100 && (b & 0xc0) == 64)
+        // Benchmarking `198.18.0.0/15`
+        || (a == 198 && (b & 0xfe) == 18)
+        // Reserved `240.0.0.0/4`
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local `fc00::/7`, e.g. `fd00:ec2::254` of AWS metadata
+        || (first & 0xfe00) == 0xfc00
+        // Link-local `fe80::/10`
+        || (first & 0xffc0) == 0xfe80
+        // Documentation `2001:db8::/32`
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// DNS resolver failing on hosts which resolve to any non-public address,
+/// so that a gateway can't point into our network through its DNS records.
+#[derive(Clone)]
+pub struct PublicResolver(pub GaiResolver);
+
+impl Service<Name> for PublicResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let host = name.as_str().to_string();
+        let resolving = self.0.call(name);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = resolving.await?.collect();
+            match addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+                Some(addr) => Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{} resolves to non-public address {}", host, addr.ip()),
+                )),
+                None => Ok(addrs.into_iter()),
+            }
+        })
+    }
+}
+
+/// Body of `resp`, failing once larger than `limit` bytes.
+pub async fn read_body(resp: &mut Response<Body>, limit: usize) -> Result<Vec<u8>, Error> {
+    let too_large = || {
+        Error::General(
+            format!("CCIP-read: gateway response larger than {} bytes", limit),
+            StatusCode::BAD_GATEWAY,
+        )
+    };
+    if resp
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size > limit as u64)
+    {
+        return Err(too_large());
+    }
+    let mut body = vec![];
+    while let Some(chunk) = resp.body_mut().data().await {
+        let chunk = chunk.map_err(|err| {
+            Error::General(
+                format!("CCIP-read: gateway response error: {}", err),
+                StatusCode::BAD_GATEWAY,
+            )
+        })?;
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// `eth_call`, following `OffchainLookup` reverts through gateways.
+pub async fn call(rpc: &EthereumRpc, to: &str, data: &[u8]) -> Result<CallOutput, Error> {
+    let mut data = data.to_vec();
+    for _ in 0..=MAX_LOOKUPS {
+        let revert = match rpc.try_call(to, &data).await? {
+            CallOutput::Revert(revert) => revert,
+            output => return Ok(output),
+        };
+        let Some(lookup) = OffchainLookup::decode(&revert) else {
+            return Ok(CallOutput::Revert(revert));
+        };
+        // Only the contract called may ask for a lookup.
+        if lookup.sender != to.to_lowercase() {
+            return Err(Error::General(
+                format!(
+                    "CCIP-read: OffchainLookup sender {} is not {}",
+                    lookup.sender, to
+                ),
+                StatusCode::BAD_GATEWAY,
+            ));
+        }
+        let response = match rpc.local_gateways {
+            true => lookup.fetch_gateways(&make_client(), true).await?,
+            false => lookup.fetch_gateways(&gateway_client(), false).await?,
+        };
+        data = encode_call_selector(
+            lookup.callback,
+            &[Token::Bytes(response), Token::Bytes(lookup.extra_data)],
+        );
+    }
+    Err(Error::General(
+        format!(
+            "CCIP-read: more than {} OffchainLookup from {}",
+            MAX_LOOKUPS, to
+        ),
+        StatusCode::BAD_GATEWAY,
+    ))
+}
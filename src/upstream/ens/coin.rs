@@ -0,0 +1,72 @@
+//! Text form of addresses in multi-coin address records,
+//! which are stored in each chain's binary form ([ENSIP-9](https://docs.ens.domains/ensip/9)).
+
+use crate::upstream::{
+    encoding::{base58check_encode, bech32_encode, convert_bits, BECH32M_CONST, BECH32_CONST},
+    Chain,
+};
+
+/// Text form of binary `address` on `chain`.
+/// `None` if malformed, or the chain's binary form is not supported yet (Stacks, CKB, TON...).
+pub fn format_address(chain: Chain, address: &[u8]) -> Option<String> {
+    match chain {
+        Chain::Bitcoin => format_bitcoin_script(address, 0x00, 0x05, Some("bc")),
+        Chain::Doge => format_bitcoin_script(address, 0x1e, 0x16, None),
+        Chain::Solana => (address.len() == 32).then(|| bs58::encode(address).into_string()),
+        Chain::Tron => {
+            (address.len() == 21 && address[0] == 0x41).then(|| base58check_encode(address))
+        }
+        Chain::Cosmos => match address.len() {
+            20 => Some(bech32_encode(
+                "cosmos",
+                &convert_bits(address, 8, 5, true)?,
+                BECH32_CONST,
+            )),
+            _ => None,
+        },
+        // Account ID in UTF-8.
+        Chain::Near => String::from_utf8(address.to_vec()).ok(),
+        Chain::Aptos => (address.len() == 32).then(|| format!("0x{}", hex::encode(address))),
+        Chain::Stacks
+        | Chain::CKB
+        | Chain::Ton
+        | Chain::Arweave
+        | Chain::Conflux
+        | Chain::Unknown => None,
+        // EVM-compatible
+        _ => (address.len() == 20).then(|| format!("0x{}", hex::encode(address))),
+    }
+}
+
+/// Address of a Bitcoin (or fork) output script: P2PKH, P2SH, or segwit if `hrp` is given.
+fn format_bitcoin_script(script: &[u8], p2pkh: u8, p2sh: u8, hrp: Option<&str>) -> Option<String> {
+    match script {
+        // OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG
+        [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => {
+            Some(base58check_encode(&[&[p2pkh][..], hash].concat()))
+        }
+        // OP_HASH160 <20 bytes> OP_EQUAL
+        [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => {
+            Some(base58check_encode(&[&[p2sh][..], hash].concat()))
+        }
+        // OP_0 / OP_1..OP_16 <witness program>
+        [op, length, program @ ..]
+            if (*op == 0 || (0x51..=0x60).contains(op))
+                && *length as usize == program.len()
+                && (2..=40).contains(&program.len()) =>
+        {
+            let version = match *op {
+                0 => 0,
+                op => op - 0x50,
+            };
+            let constant = match version {
+                0 => BECH32_CONST,
+                _ => BECH32M_CONST,
+            };
+            let mut data = vec![version];
+            data.extend(convert_bits(program, 8, 5, true)?);
+            Some(bech32_encode(hrp?, &data, constant))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,250 @@
+//! ENS records read from contracts through `eth_call`.
+
+use super::{
+    abi::{decode_address, decode_bytes, decode_string, dns_encode, encode_call, Token},
+    ccip, labelhash,
+    multicall::aggregate3,
+    namehash, Hash,
+};
+use crate::{
+    config::C,
+    error::Error,
+    upstream::ethereum::{CallOutput, EthereumRpc},
+};
+
+/// ENS Registry, the same address on all networks.
+pub const REGISTRY: &str = "0x00000000000c2e074ec69a0dfb2997ba6c7d2e1e";
+/// Owner in `REGISTRY` of wrapped names, which are ERC-1155 tokens of it.
+pub const NAME_WRAPPER: &str = "0xd4416b13d2b3a9abae7acd5d6c2bbdbe25686401";
+/// `.eth` registrar, whose ERC-721 tokens are `.eth` second-level names.
+pub const ETH_REGISTRAR: &str = "0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85";
+/// Default of `[upstream.ethereum_rpc] universal_resolver`.
+pub const UNIVERSAL_RESOLVER: &str = "0xce01f8eee7e479c928f8919abd53e553a36cef67";
+
+/// Records of an ENS name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameRecord {
+    pub name: String,
+    /// Registrant of `.eth` second-level names, holder of wrapped names,
+    /// or owner in `REGISTRY` of other names.
+    pub owner: String,
+    pub resolver: Option<String>,
+    /// `addr` record (ETH address) set in its resolver.
+    pub resolved_address: Option<String>,
+}
+
+/// Primary name of an address, set in its reverse record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseRecord {
+    pub name: String,
+    /// `addr` record of `name`. Not necessarily the address being reversed.
+    pub resolved_address: Option<String>,
+}
+
+fn universal_resolver() -> String {
+    C.upstream
+        .ethereum_rpc
+        .universal_resolver
+        .clone()
+        .unwrap_or(UNIVERSAL_RESOLVER.to_string())
+}
+
+/// Owner, resolver and `addr` record of a normalized `name`. `None` if not registered.
+pub async fn lookup_name(rpc: &EthereumRpc, name: &str) -> Result<Option<NameRecord>, Error> {
+    let node = namehash(name);
+    let Some(owner) = owner(rpc, name, &node).await? else {
+        return Ok(None);
+    };
+    let resolver = resolver(rpc, &node).await?;
+    let resolved_address = match &resolver {
+        Some(resolver) => {
+            match rpc
+                .try_call(
+                    resolver,
+                    &encode_call("addr(bytes32)", &[Token::Word(node)]),
+                )
+                .await?
+            {
+                CallOutput::Return(output) => decode_address(&output, 0),
+                // Resolver without `addr`.
+                CallOutput::Revert(_) => None,
+            }
+        }
+        None => None,
+    };
+    Ok(Some(NameRecord {
+        name: name.to_string(),
+        owner,
+        resolver,
+        resolved_address,
+    }))
+}
+
+/// `addr` record of `name` through Universal Resolver, which supports wildcard resolution
+/// ([ENSIP-10](https://docs.ens.domains/ensip/10)) and offchain resolvers (CCIP-read).
+/// Works for subnames not in `REGISTRY`. `None` if no resolver or record is found.
+pub async fn resolve_address(rpc: &EthereumRpc, name: &str) -> Result<Option<String>, Error> {
+    let addr_call = encode_call("addr(bytes32)", &[Token::Word(namehash(name))]);
+    let call = encode_call(
+        "resolve(bytes,bytes)",
+        &[Token::Bytes(dns_encode(name)), Token::Bytes(addr_call)],
+    );
+    // (bytes result, address resolver)
+    match ccip::call(rpc, &universal_resolver(), &call).await? {
+        CallOutput::Return(output) => {
+            Ok(decode_bytes(&output, 0).and_then(|result| decode_address(&result, 0)))
+        }
+        // `ResolverNotFound`, `ResolverWildcardNotSupported`...
+        CallOutput::Revert(_) => Ok(None),
+    }
+}
+
+/// Address records of `coin_types` ([ENSIP-9](https://docs.ens.domains/ensip/9)) of `name`
+/// set in `resolver`, in each chain's binary form, read in a single call through Multicall3.
+/// `None` for each not set, or failed to be read (e.g. resolver without multi-coin support).
+pub async fn lookup_coin_addresses(
+    rpc: &EthereumRpc,
+    resolver: &str,
+    name: &str,
+    coin_types: &[u32],
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    let node = namehash(name);
+    let calls: Vec<(&str, Vec<u8>)> = coin_types
+        .iter()
+        .map(|coin_type| {
+            let mut coin = [0; 32];
+            coin[28..].copy_from_slice(&coin_type.to_be_bytes());
+            let call = encode_call(
+                "addr(bytes32,uint256)",
+                &[Token::Word(node), Token::Word(coin)],
+            );
+            (resolver, call)
+        })
+        .collect();
+    Ok(aggregate3(rpc, &calls)
+        .await?
+        .into_iter()
+        .map(|output| {
+            output
+                .and_then(|output| decode_bytes(&output, 0))
+                .filter(|addr| !addr.is_empty())
+        })
+        .collect())
+}
+
+/// Text records ([ENSIP-5](https://docs.ens.domains/ensip/5)) `keys` of `name` set in
+/// `resolver`, read in a single call through Multicall3.
+/// `None` for each not set, or failed to be read (e.g. resolver without `text`).
+pub async fn lookup_texts(
+    rpc: &EthereumRpc,
+    resolver: &str,
+    name: &str,
+    keys: &[&str],
+) -> Result<Vec<Option<String>>, Error> {
+    let node = namehash(name);
+    let calls: Vec<(&str, Vec<u8>)> = keys
+        .iter()
+        .map(|key| {
+            let call = encode_call(
+                "text(bytes32,string)",
+                &[Token::Word(node), Token::String(key.to_string())],
+            );
+            (resolver, call)
+        })
+        .collect();
+    Ok(aggregate3(rpc, &calls)
+        .await?
+        .into_iter()
+        .map(|output| {
+            output
+                .and_then(|output| decode_string(&output, 0))
+                .filter(|text| !text.is_empty())
+        })
+        .collect())
+}
+
+/// Primary name of `address` (`0x`-prefixed), through Universal Resolver. `None` if not set.
+pub async fn lookup_reverse(
+    rpc: &EthereumRpc,
+    address: &str,
+) -> Result<Option<ReverseRecord>, Error> {
+    let reverse_name = format!(
+        "{}.addr.reverse",
+        address.trim_start_matches("0x").to_lowercase()
+    );
+    let call = encode_call("reverse(bytes)", &[Token::Bytes(dns_encode(&reverse_name))]);
+    // (string name, address resolvedAddress, address reverseResolver, address resolver)
+    let output = match rpc.try_call(&universal_resolver(), &call).await? {
+        CallOutput::Return(output) => output,
+        // No reverse resolver or name is set.
+        CallOutput::Revert(_) => return Ok(None),
+    };
+    match decode_string(&output, 0) {
+        Some(name) if !name.is_empty() => Ok(Some(ReverseRecord {
+            name,
+            resolved_address: decode_address(&output, 1),
+        })),
+        _ => Ok(None),
+    }
+}
+
+/// Owner of `name`. The owner of a `.eth` second-level name in `REGISTRY` is only its
+/// manager, which may be set to anyone by its registrant in `ETH_REGISTRAR`.
+async fn owner(rpc: &EthereumRpc, name: &str, node: &Hash) -> Result<Option<String>, Error> {
+    let output = rpc
+        .call(
+            REGISTRY,
+            &encode_call("owner(bytes32)", &[Token::Word(*node)]),
+        )
+        .await?;
+    let Some(manager) = decode_address(&output, 0) else {
+        return Ok(None);
+    };
+    if manager == NAME_WRAPPER {
+        return wrapped_owner(rpc, node).await;
+    }
+    let Some(label) = eth_second_level_label(name) else {
+        return Ok(Some(manager));
+    };
+    // Token ID is `uint256(labelhash)`.
+    let call = encode_call("ownerOf(uint256)", &[Token::Word(labelhash(label))]);
+    match rpc.try_call(ETH_REGISTRAR, &call).await? {
+        CallOutput::Return(output) => match decode_address(&output, 0) {
+            // Wrapped, but its manager is not set to `NAME_WRAPPER` (yet).
+            Some(registrant) if registrant == NAME_WRAPPER => wrapped_owner(rpc, node).await,
+            registrant => Ok(registrant.or(Some(manager))),
+        },
+        // `ownerOf` reverts once expired, grace period included.
+        // Its registrant is unknown then, so the manager is kept.
+        CallOutput::Revert(_) => Ok(Some(manager)),
+    }
+}
+
+/// Holder of the wrapped `node` in `NAME_WRAPPER`.
+async fn wrapped_owner(rpc: &EthereumRpc, node: &Hash) -> Result<Option<String>, Error> {
+    // Token ID is `uint256(node)`.
+    let output = rpc
+        .call(
+            NAME_WRAPPER,
+            &encode_call("ownerOf(uint256)", &[Token::Word(*node)]),
+        )
+        .await?;
+    Ok(decode_address(&output, 0))
+}
+
+/// `label` of a `label.eth` name.
+fn eth_second_level_label(name: &str) -> Option<&str> {
+    name.strip_suffix(".eth")
+        .filter(|label| !label.is_empty() && !label.contains('.'))
+}
+
+/// Resolver of `node` set in `REGISTRY`.
+async fn resolver(rpc: &EthereumRpc, node: &Hash) -> Result<Option<String>, Error> {
+    let output = rpc
+        .call(
+            REGISTRY,
+            &encode_call("resolver(bytes32)", &[Token::Word(*node)]),
+        )
+        .await?;
+    Ok(decode_address(&output, 0))
+}
@@ -0,0 +1,108 @@
+//! ENS name normalization (UTS-46, plus a part of [ENSIP-15](https://docs.ens.domains/ensip/15))
+//! and hashing, done locally so that names in TigerGraph and in upstream queries always agree.
+//! Also reading ENS contracts through any Ethereum JSON-RPC endpoint, and Basenames on Base.
+
+mod abi;
+mod basenames;
+mod ccip;
+mod coin;
+mod lookup;
+mod multicall;
+#[cfg(test)]
+mod tests;
+
+pub use basenames::{is_basename, lookup_basename, lookup_basename_reverse, BasenameRecord};
+pub use coin::format_address;
+pub use lookup::{
+    lookup_coin_addresses, lookup_name, lookup_reverse, lookup_texts, resolve_address, NameRecord,
+    ReverseRecord,
+};
+
+use crate::error::Error;
+use sha3::{Digest, Keccak256};
+
+/// `keccak256` of a label or a node.
+pub type Hash = [u8; 32];
+
+/// Normalize an ENS name: UTS-46 mapping (case folding, width mapping,
+/// `FE0F` removal, punycode decoding...) plus the ENSIP-15 rules on ASCII labels.
+/// This is not full ENSIP-15: emoji sequences, confusables and mixed scripts are not
+/// checked, so a few names accepted here are still rejected by the ENS app.
+pub fn normalize(name: &str) -> Result<String, Error> {
+    let invalid =
+        |reason: &str| Error::ParamError(format!("Invalid ENS name {}: {}", name, reason));
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(invalid("empty name"));
+    }
+    // Checked before UTS-46, which decodes `xn--` labels.
+    if name
+        .split('.')
+        .any(|label| is_punycode_like(&label.to_lowercase()))
+    {
+        return Err(invalid("label like `xn--`"));
+    }
+
+    let (normalized, result) = idna::Config::default()
+        .use_std3_ascii_rules(false)
+        .transitional_processing(false)
+        .check_hyphens(false)
+        .verify_dns_length(false)
+        .to_unicode(name);
+    result.map_err(|errors| invalid(&format!("{:?}", errors)))?;
+
+    for label in normalized.split('.') {
+        if label.is_empty() {
+            return Err(invalid("empty label"));
+        }
+        if let Some(c) = label.chars().find(|c| c.is_ascii() && !is_valid_ascii(*c)) {
+            return Err(invalid(&format!("disallowed character {:?}", c)));
+        }
+        if label.trim_start_matches('_').contains('_') {
+            return Err(invalid("underscore allowed only at the start of a label"));
+        }
+    }
+    Ok(normalized)
+}
+
+/// Check if `name` is already normalized.
+/// A reverse record is only valid if so.
+pub fn is_normalized(name: &str) -> bool {
+    matches!(normalize(name), Ok(normalized) if normalized == name)
+}
+
+/// `keccak256` of a single label.
+pub fn labelhash(label: &str) -> Hash {
+    Keccak256::digest(label.as_bytes()).into()
+}
+
+/// [EIP-137](https://eips.ethereum.org/EIPS/eip-137) `namehash` of a (normalized) name.
+pub fn namehash(name: &str) -> Hash {
+    let mut node: Hash = [0; 32];
+    if name.is_empty() {
+        return node;
+    }
+    for label in name.rsplit('.') {
+        let mut hasher = Keccak256::new();
+        hasher.update(node);
+        hasher.update(labelhash(label));
+        node = hasher.finalize().into();
+    }
+    node
+}
+
+/// `0x`-prefixed hex of a hash, as used in subgraphs and JSON-RPC.
+pub fn to_hex(hash: &Hash) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+/// ENSIP-15 only allows `a-z`, `0-9`, `-`, `_` and `$` in ASCII.
+fn is_valid_ascii(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_' || c == '$'
+}
+
+/// ASCII labels with `--` at 3rd and 4th characters, e.g. `xn--`.
+fn is_punycode_like(label: &str) -> bool {
+    label.is_ascii() && label.get(2..4) == Some("--")
+}
@@ -0,0 +1,59 @@
+//! Many `eth_call`s in a single one, through [Multicall3](https://www.multicall3.com).
+
+use super::abi::{
+    address_word, bool_word, decode_bytes, decode_dynamic_array, decode_uint, encode_call, Token,
+};
+use crate::{error::Error, upstream::ethereum::EthereumRpc};
+use http::StatusCode;
+
+/// Multicall3, the same address on all networks.
+pub const MULTICALL3: &str = "0xca11bde05977b3631167028862be2a173976ca11";
+
+/// Calldata of `aggregate3` calling each of `calls` (`(to, calldata)`), failures allowed.
+pub fn encode_aggregate3(calls: &[(&str, Vec<u8>)]) -> Result<Vec<u8>, Error> {
+    let calls = calls
+        .iter()
+        .map(|(to, data)| {
+            let to = address_word(to)
+                .ok_or_else(|| Error::ParamError(format!("Multicall3: invalid address {}", to)))?;
+            // (address target, bool allowFailure, bytes callData)
+            Ok(Token::Tuple(vec![
+                Token::Word(to),
+                Token::Word(bool_word(true)),
+                Token::Bytes(data.clone()),
+            ]))
+        })
+        .collect::<Result<Vec<Token>, Error>>()?;
+    Ok(encode_call(
+        "aggregate3((address,bool,bytes)[])",
+        &[Token::Array(calls)],
+    ))
+}
+
+/// `eth_call` each of `calls` (`(to, calldata)`) in a single `eth_call` to `MULTICALL3`.
+/// Output of each call in order, `None` if it reverted: one revert does not fail others.
+pub async fn aggregate3(
+    rpc: &EthereumRpc,
+    calls: &[(&str, Vec<u8>)],
+) -> Result<Vec<Option<Vec<u8>>>, Error> {
+    if calls.is_empty() {
+        return Ok(vec![]);
+    }
+    let output = rpc.call(MULTICALL3, &encode_aggregate3(calls)?).await?;
+    // (bool success, bytes returnData)[]
+    let results = decode_dynamic_array(&output, 0)
+        .filter(|results| results.len() == calls.len())
+        .ok_or_else(|| {
+            Error::General(
+                "Multicall3: malformed aggregate3 output".to_string(),
+                StatusCode::BAD_GATEWAY,
+            )
+        })?;
+    Ok(results
+        .into_iter()
+        .map(|result| match decode_uint(result, 0) {
+            Some(1) => decode_bytes(result, 1),
+            _ => None,
+        })
+        .collect())
+}
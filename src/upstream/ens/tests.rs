@@ -0,0 +1,647 @@
+use super::*;
+use crate::upstream::{ethereum::EthereumRpc, Chain};
+use hyper::Body;
+use serde_json::json;
+use std::collections::HashMap;
+use warp::Filter;
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("Vitalik.ETH").unwrap(), "vitalik.eth");
+    assert_eq!(normalize(" nick.eth ").unwrap(), "nick.eth");
+    // Fullwidth characters are mapped.
+    assert_eq!(normalize("ｎｉｃｋ.eth").unwrap(), "nick.eth");
+    assert_eq!(normalize("_test.eth").unwrap(), "_test.eth");
+    assert_eq!(normalize("$money.eth").unwrap(), "$money.eth");
+    assert_eq!(normalize("🔥🔥🔥.eth").unwrap(), "🔥🔥🔥.eth");
+
+    assert!(normalize("").is_err());
+    assert!(normalize("vitalik..eth").is_err());
+    assert!(normalize("te_st.eth").is_err());
+    assert!(normalize("xn--ls8h.eth").is_err());
+    assert!(normalize("a b.eth").is_err());
+    assert!(normalize("a/b.eth").is_err());
+}
+
+#[test]
+fn test_is_normalized() {
+    assert!(is_normalized("vitalik.eth"));
+    assert!(!is_normalized("Vitalik.eth"));
+    assert!(!is_normalized("vitalik..eth"));
+}
+
+#[test]
+fn test_hash() {
+    assert_eq!(namehash(""), [0; 32]);
+    assert_eq!(
+        to_hex(&labelhash("eth")),
+        "0x4f5b812789fc606be1b3b16908db13fc7a9adf7ca72641f84d75b47069d3d7f0"
+    );
+    assert_eq!(
+        to_hex(&namehash("eth")),
+        "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+    );
+    assert_eq!(
+        to_hex(&namehash("foo.eth")),
+        "0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+    );
+}
+
+#[test]
+fn test_abi_encode() {
+    assert_eq!(abi::selector("addr(bytes32)"), [0x3b, 0x3b, 0x57, 0xde]);
+    assert_eq!(abi::selector("reverse(bytes)"), [0xec, 0x11, 0xc8, 0x23]);
+
+    let data = abi::encode_call("reverse(bytes)", &[abi::Token::Bytes(vec![1, 2, 3])]);
+    assert_eq!(data.len(), 4 + 32 * 3);
+    assert_eq!(data[4 + 31], 0x20); // offset
+    assert_eq!(data[4 + 63], 3); // length
+    assert_eq!(&data[4 + 64..4 + 67], &[1, 2, 3]);
+    assert!(data[4 + 67..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn test_abi_decode() {
+    let output = abi::encode(&[
+        abi::Token::Bytes(b"vitalik.eth".to_vec()),
+        abi::Token::Word(address_word(VITALIK)),
+        abi::Token::Word([0; 32]),
+    ]);
+    assert_eq!(abi::decode_string(&output, 0), Some("vitalik.eth".into()));
+    assert_eq!(abi::decode_address(&output, 1), Some(VITALIK.into()));
+    // Zero address
+    assert_eq!(abi::decode_address(&output, 2), None);
+    // Out of range
+    assert_eq!(abi::decode_address(&output, 10), None);
+    assert_eq!(abi::decode_string(&output, 1), None);
+    // Length overflowing the offset
+    let output = abi::encode(&[abi::Token::Word(word(32)), abi::Token::Word([0xff; 32])]);
+    assert_eq!(abi::decode_bytes(&output, 0), None);
+    let mut length = [0; 32];
+    length[24..].copy_from_slice(&u64::MAX.to_be_bytes());
+    let output = abi::encode(&[abi::Token::Word(word(32)), abi::Token::Word(length)]);
+    assert_eq!(abi::decode_bytes(&output, 0), None);
+}
+
+#[test]
+fn test_dns_encode() {
+    assert_eq!(
+        abi::dns_encode("vitalik.eth"),
+        b"\x07vitalik\x03eth\x00".to_vec()
+    );
+    assert_eq!(abi::dns_encode(""), vec![0]);
+}
+
+pub(super) const VITALIK: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+pub(super) const RESOLVER: &str = "0x231b0ee14048e9dccd1d247744d114a4eb5e8e63";
+
+pub(super) fn address_word(address: &str) -> Hash {
+    let mut word = [0; 32];
+    word[12..].copy_from_slice(&hex::decode(address.trim_start_matches("0x")).unwrap());
+    word
+}
+
+/// Local stand-in of an Ethereum JSON-RPC endpoint, answering `eth_call`s
+/// by `(to, calldata)` with canned outputs. Other calls revert.
+pub(super) fn serve_rpc(calls: Vec<(&str, Vec<u8>, Vec<u8>)>) -> EthereumRpc {
+    serve_rpc_with_reverts(calls, vec![])
+}
+
+/// `serve_rpc`, also reverting `reverts` with canned revert data.
+fn serve_rpc_with_reverts(
+    calls: Vec<(&str, Vec<u8>, Vec<u8>)>,
+    reverts: Vec<(&str, Vec<u8>, Vec<u8>)>,
+) -> EthereumRpc {
+    let canned = |calls: Vec<(&str, Vec<u8>, Vec<u8>)>| -> HashMap<(String, String), String> {
+        calls
+            .into_iter()
+            .map(|(to, data, output)| {
+                let key = (to.to_string(), format!("0x{}", hex::encode(data)));
+                (key, format!("0x{}", hex::encode(output)))
+            })
+            .collect()
+    };
+    let (calls, reverts) = (canned(calls), canned(reverts));
+    let route = warp::post()
+        .and(warp::body::json())
+        .map(move |req: serde_json::Value| {
+            let call = &req["params"][0];
+            let key = (
+                call["to"].as_str().unwrap_or_default().to_string(),
+                call["data"].as_str().unwrap_or_default().to_string(),
+            );
+            let resp = match calls.get(&key) {
+                Some(output) => json!({"jsonrpc": "2.0", "id": req["id"], "result": output}),
+                None => json!({
+                    "jsonrpc": "2.0",
+                    "id": req["id"],
+                    "error": {
+                        "code": 3,
+                        "message": "execution reverted",
+                        "data": reverts.get(&key).cloned().unwrap_or_else(|| "0x".into()),
+                    },
+                }),
+            };
+            warp::reply::json(&resp)
+        });
+    let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    EthereumRpc::new(&format!("http://{}", addr))
+}
+
+/// Local stand-in of a CCIP-read gateway, answering `data` to any lookup.
+/// Returns URL templates of a working and a failing (`500`) gateway.
+fn serve_gateway(data: Vec<u8>) -> (String, String) {
+    let data = format!("0x{}", hex::encode(data));
+    let failing = warp::path("failing")
+        .map(|| warp::reply::with_status("", warp::http::StatusCode::INTERNAL_SERVER_ERROR));
+    let working = warp::path::tail().map(move |_| warp::reply::json(&json!({ "data": data })));
+    let (addr, server) = warp::serve(failing.or(working)).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    (
+        format!("http://{}/{{sender}}/{{data}}.json", addr),
+        format!("http://{}/failing/{{sender}}/{{data}}.json", addr),
+    )
+}
+
+/// Revert data of `OffchainLookup(sender, urls, call_data, callback, extra_data)`.
+fn offchain_lookup(lookup: &ccip::OffchainLookup) -> Vec<u8> {
+    // Contents of `bytes`, without offset.
+    let contents = |bytes: &[u8]| abi::encode(&[abi::Token::Bytes(bytes.to_vec())])[32..].to_vec();
+    let mut urls = abi::encode(&[abi::Token::Word(word(lookup.urls.len()))]);
+    urls.extend(abi::encode(
+        &lookup
+            .urls
+            .iter()
+            .map(|url| abi::Token::String(url.clone()))
+            .collect::<Vec<_>>(),
+    ));
+    let call_data = contents(&lookup.call_data);
+    let mut callback = [0; 32];
+    callback[..4].copy_from_slice(&lookup.callback);
+
+    let mut data = abi::selector("OffchainLookup(address,string[],bytes,bytes4,bytes)").to_vec();
+    data.extend(abi::encode(&[
+        abi::Token::Word(address_word(&lookup.sender)),
+        abi::Token::Word(word(5 * 32)),
+        abi::Token::Word(word(5 * 32 + urls.len())),
+        abi::Token::Word(callback),
+        abi::Token::Word(word(5 * 32 + urls.len() + call_data.len())),
+    ]));
+    data.extend(urls);
+    data.extend(call_data);
+    data.extend(contents(&lookup.extra_data));
+    data
+}
+
+pub(super) fn word(value: usize) -> Hash {
+    let mut word = [0; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+pub(super) fn call(signature: &str, node: Hash) -> Vec<u8> {
+    abi::encode_call(signature, &[abi::Token::Word(node)])
+}
+
+#[tokio::test]
+async fn test_lookup_name() -> Result<(), Error> {
+    let node = namehash("vitalik.eth");
+    let manager = "0x000000000000000000000000000000000000beef";
+    let rpc = serve_rpc(vec![
+        // Manager, set by the registrant.
+        (
+            lookup::REGISTRY,
+            call("owner(bytes32)", node),
+            address_word(manager).to_vec(),
+        ),
+        (
+            lookup::ETH_REGISTRAR,
+            call("ownerOf(uint256)", labelhash("vitalik")),
+            address_word(VITALIK).to_vec(),
+        ),
+        (
+            lookup::REGISTRY,
+            call("resolver(bytes32)", node),
+            address_word(RESOLVER).to_vec(),
+        ),
+        (
+            RESOLVER,
+            call("addr(bytes32)", node),
+            address_word(VITALIK).to_vec(),
+        ),
+    ]);
+    let record = lookup_name(&rpc, "vitalik.eth").await?;
+    assert_eq!(
+        record,
+        Some(NameRecord {
+            name: "vitalik.eth".into(),
+            owner: VITALIK.into(),
+            resolver: Some(RESOLVER.into()),
+            resolved_address: Some(VITALIK.into()),
+        })
+    );
+
+    // Not registered
+    let rpc = serve_rpc(vec![(
+        lookup::REGISTRY,
+        call("owner(bytes32)", namehash("unregistered.eth")),
+        vec![0; 32],
+    )]);
+    assert_eq!(lookup_name(&rpc, "unregistered.eth").await?, None);
+
+    // Subnames have no registrant.
+    let node = namehash("sub.vitalik.eth");
+    let rpc = serve_rpc(vec![
+        (
+            lookup::REGISTRY,
+            call("owner(bytes32)", node),
+            address_word(manager).to_vec(),
+        ),
+        (
+            lookup::REGISTRY,
+            call("resolver(bytes32)", node),
+            vec![0; 32],
+        ),
+    ]);
+    let record = lookup_name(&rpc, "sub.vitalik.eth")
+        .await?
+        .expect("registered");
+    assert_eq!(record.owner, manager);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lookup_name_wrapped() -> Result<(), Error> {
+    let node = namehash("wrapped.eth");
+    let rpc = serve_rpc(vec![
+        (
+            lookup::REGISTRY,
+            call("owner(bytes32)", node),
+            address_word(lookup::NAME_WRAPPER).to_vec(),
+        ),
+        (
+            lookup::NAME_WRAPPER,
+            call("ownerOf(uint256)", node),
+            address_word(VITALIK).to_vec(),
+        ),
+        // No resolver
+        (
+            lookup::REGISTRY,
+            call("resolver(bytes32)", node),
+            vec![0; 32],
+        ),
+    ]);
+    let record = lookup_name(&rpc, "wrapped.eth").await?.expect("registered");
+    assert_eq!(record.owner, VITALIK);
+    assert_eq!(record.resolved_address, None);
+    Ok(())
+}
+
+/// Mocked `eth_call`: `(to, calldata, output)`.
+type MockCall<Output = Vec<u8>> = (&'static str, Vec<u8>, Output);
+
+/// Mocked `aggregate3` of `calls` (`(to, calldata, output)`), `None` output as reverted.
+fn multicall(calls: Vec<MockCall<Option<Vec<u8>>>>) -> MockCall {
+    let call_data = multicall::encode_aggregate3(
+        &calls
+            .iter()
+            .map(|(to, data, _)| (*to, data.clone()))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+    let results = calls
+        .into_iter()
+        .map(|(_, _, output)| {
+            abi::Token::Tuple(vec![
+                abi::Token::Word(abi::bool_word(output.is_some())),
+                abi::Token::Bytes(output.unwrap_or_default()),
+            ])
+        })
+        .collect();
+    (
+        multicall::MULTICALL3,
+        call_data,
+        abi::encode(&[abi::Token::Array(results)]),
+    )
+}
+
+#[test]
+fn test_abi_encode_array() {
+    let tuple = |value: usize| {
+        abi::Token::Tuple(vec![
+            abi::Token::Word(word(value)),
+            abi::Token::Bytes(vec![0xab]),
+        ])
+    };
+    let data = abi::encode(&[abi::Token::Array(vec![tuple(1), tuple(2)])]);
+    let elements = abi::decode_dynamic_array(&data, 0).expect("array");
+    assert_eq!(elements.len(), 2);
+    for (element, value) in elements.into_iter().zip([1, 2]) {
+        assert_eq!(abi::decode_uint(element, 0), Some(value));
+        assert_eq!(abi::decode_bytes(element, 1), Some(vec![0xab]));
+    }
+    assert_eq!(abi::decode_dynamic_array(&data[..64], 0), None);
+}
+
+#[tokio::test]
+async fn test_lookup_texts() -> Result<(), Error> {
+    let text = |key: &str| {
+        abi::encode_call(
+            "text(bytes32,string)",
+            &[
+                abi::Token::Word(namehash("vitalik.eth")),
+                abi::Token::String(key.into()),
+            ],
+        )
+    };
+    let rpc = serve_rpc(vec![multicall(vec![
+        (
+            RESOLVER,
+            text("com.twitter"),
+            Some(abi::encode(&[abi::Token::String("VitalikButerin".into())])),
+        ),
+        (
+            RESOLVER,
+            text("email"),
+            Some(abi::encode(&[abi::Token::String("".into())])),
+        ),
+        // Reverted, does not fail others.
+        (RESOLVER, text("url"), None),
+    ])]);
+    assert_eq!(
+        lookup_texts(
+            &rpc,
+            RESOLVER,
+            "vitalik.eth",
+            &["com.twitter", "email", "url"]
+        )
+        .await?,
+        vec![Some("VitalikButerin".into()), None, None]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lookup_coin_addresses() -> Result<(), Error> {
+    let addr = |coin_type: usize| {
+        abi::encode_call(
+            "addr(bytes32,uint256)",
+            &[
+                abi::Token::Word(namehash("vitalik.eth")),
+                abi::Token::Word(word(coin_type)),
+            ],
+        )
+    };
+    let script = hex::decode("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac").unwrap();
+    let rpc = serve_rpc(vec![multicall(vec![
+        (
+            RESOLVER,
+            addr(0),
+            Some(abi::encode(&[abi::Token::Bytes(script.clone())])),
+        ),
+        (
+            RESOLVER,
+            addr(501),
+            Some(abi::encode(&[abi::Token::Bytes(vec![])])),
+        ),
+        // Reverted, does not fail others.
+        (RESOLVER, addr(195), None),
+    ])]);
+    assert_eq!(
+        lookup_coin_addresses(&rpc, RESOLVER, "vitalik.eth", &[0, 501, 195]).await?,
+        vec![Some(script), None, None]
+    );
+    // No call at all
+    assert_eq!(
+        lookup_coin_addresses(&rpc, RESOLVER, "vitalik.eth", &[]).await?,
+        vec![]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lookup_reverse() -> Result<(), Error> {
+    let reverse_name = format!("{}.addr.reverse", VITALIK.trim_start_matches("0x"));
+    let output = abi::encode(&[
+        abi::Token::Bytes(b"vitalik.eth".to_vec()),
+        abi::Token::Word(address_word(VITALIK)),
+        abi::Token::Word(address_word(RESOLVER)),
+        abi::Token::Word(address_word(RESOLVER)),
+    ]);
+    let rpc = serve_rpc(vec![(
+        lookup::UNIVERSAL_RESOLVER,
+        abi::encode_call(
+            "reverse(bytes)",
+            &[abi::Token::Bytes(abi::dns_encode(&reverse_name))],
+        ),
+        output,
+    )]);
+    assert_eq!(
+        lookup_reverse(&rpc, VITALIK).await?,
+        Some(ReverseRecord {
+            name: "vitalik.eth".into(),
+            resolved_address: Some(VITALIK.into()),
+        })
+    );
+    // Reverted: no primary name.
+    assert_eq!(lookup_reverse(&rpc, RESOLVER).await?, None);
+    Ok(())
+}
+
+#[test]
+fn test_decode_offchain_lookup() {
+    let lookup = ccip::OffchainLookup {
+        sender: RESOLVER.into(),
+        urls: vec![
+            "https://a.example/{sender}/{data}.json".into(),
+            "https://b.example".into(),
+        ],
+        call_data: vec![0xaa, 0xbb],
+        callback: abi::selector("resolveCallback(bytes,bytes)"),
+        extra_data: vec![0xcc],
+    };
+    assert_eq!(
+        ccip::OffchainLookup::decode(&offchain_lookup(&lookup)),
+        Some(lookup)
+    );
+    assert_eq!(
+        ccip::OffchainLookup::decode(&[0x08, 0xc3, 0x79, 0xa0]),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_address_offchain() -> Result<(), Error> {
+    let name = "alice.cb.id";
+    let gateway_response = vec![0x12, 0x34];
+    let extra_data = vec![0xcc];
+    let (working, failing) = serve_gateway(gateway_response.clone());
+    let lookup = ccip::OffchainLookup {
+        sender: lookup::UNIVERSAL_RESOLVER.into(),
+        urls: vec![failing, working],
+        call_data: vec![0xaa, 0xbb],
+        callback: abi::selector("resolveCallback(bytes,bytes)"),
+        extra_data: extra_data.clone(),
+    };
+    let resolve = abi::encode_call(
+        "resolve(bytes,bytes)",
+        &[
+            abi::Token::Bytes(abi::dns_encode(name)),
+            abi::Token::Bytes(call("addr(bytes32)", namehash(name))),
+        ],
+    );
+    let callback = abi::encode_call(
+        "resolveCallback(bytes,bytes)",
+        &[
+            abi::Token::Bytes(gateway_response),
+            abi::Token::Bytes(extra_data),
+        ],
+    );
+    // (bytes result, address resolver)
+    let output = abi::encode(&[
+        abi::Token::Bytes(address_word(VITALIK).to_vec()),
+        abi::Token::Word(address_word(RESOLVER)),
+    ]);
+    let rpc = serve_rpc_with_reverts(
+        vec![(lookup::UNIVERSAL_RESOLVER, callback, output)],
+        vec![(
+            lookup::UNIVERSAL_RESOLVER,
+            resolve,
+            offchain_lookup(&lookup),
+        )],
+    );
+    // Local gateways over plain HTTP are rejected.
+    assert!(resolve_address(&rpc, name).await.is_err());
+
+    let rpc = rpc.with_local_gateways();
+    assert_eq!(resolve_address(&rpc, name).await?, Some(VITALIK.into()));
+    // Reverted without `OffchainLookup`: no resolver.
+    assert_eq!(resolve_address(&rpc, "nobody.cb.id").await?, None);
+    Ok(())
+}
+
+#[test]
+fn test_check_gateway_url() {
+    assert!(ccip::check_gateway_url("https://ccip.ens.xyz/0xabcd/0x1234.json").is_ok());
+    assert!(ccip::check_gateway_url("https://8.8.8.8/lookup").is_ok());
+
+    for url in [
+        "http://ccip.ens.xyz/lookup",
+        "file:///etc/passwd",
+        "https://localhost/lookup",
+        "https://api.localhost./lookup",
+        "https://127.0.0.1/lookup",
+        "https://10.0.0.1/lookup",
+        "https://172.16.0.1/lookup",
+        "https://192.168.1.1/lookup",
+        // Cloud metadata
+        "https://169.254.169.254/latest/meta-data/",
+        "https://100.100.100.200/latest/meta-data/",
+        "https://[fd00:ec2::254]/latest/meta-data/",
+        "https://0.0.0.0/lookup",
+        "https://[::1]/lookup",
+        "https://[::ffff:127.0.0.1]/lookup",
+        "https://[fe80::1]/lookup",
+        // Decimal form of 127.0.0.1
+        "https://2130706433/lookup",
+        "not a url",
+    ] {
+        assert!(
+            ccip::check_gateway_url(url).is_err(),
+            "{} not rejected",
+            url
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_public_resolver() {
+    use hyper::{
+        client::connect::dns::{GaiResolver, Name},
+        service::Service,
+    };
+    use std::str::FromStr;
+
+    let mut resolver = ccip::PublicResolver(GaiResolver::new());
+    let resolved = resolver.call(Name::from_str("localhost").unwrap()).await;
+    assert_eq!(
+        resolved.err().map(|err| err.kind()),
+        Some(std::io::ErrorKind::PermissionDenied)
+    );
+}
+
+#[tokio::test]
+async fn test_read_body_limit() {
+    let response = |body: Body| hyper::Response::new(body);
+    let mut resp = response(Body::from(vec![0u8; 16]));
+    assert_eq!(ccip::read_body(&mut resp, 16).await.unwrap().len(), 16);
+    let mut resp = response(Body::from(vec![0u8; 17]));
+    assert!(ccip::read_body(&mut resp, 16).await.is_err());
+
+    // Streamed, without length known.
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> = vec![Ok(vec![0; 10]), Ok(vec![0; 10])];
+    let mut resp = response(Body::wrap_stream(futures::stream::iter(chunks)));
+    assert!(ccip::read_body(&mut resp, 16).await.is_err());
+}
+
+#[test]
+fn test_is_public_ip() {
+    let public = |ip: &str| ccip::is_public_ip(ip.parse().unwrap());
+    assert!(public("1.1.1.1"));
+    assert!(public("2606:4700:4700::1111"));
+    assert!(!public("127.0.0.53"));
+    assert!(!public("169.254.169.254"));
+    assert!(!public("100.64.0.1"));
+    assert!(!public("::ffff:10.0.0.1"));
+    assert!(!public("fc00::1"));
+}
+
+#[test]
+fn test_format_address() {
+    let format =
+        |chain: Chain, address: &str| format_address(chain, &hex::decode(address).unwrap());
+    assert_eq!(
+        format(
+            Chain::Bitcoin,
+            "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac"
+        ),
+        Some("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".into())
+    );
+    assert_eq!(
+        format(
+            Chain::Bitcoin,
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        ),
+        Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into())
+    );
+    assert_eq!(
+        format(Chain::Cosmos, "6e436a571cec916167ba105160474b9c9cd132bd"),
+        Some("cosmos1depk54cuajgkzea6zpgkq36tnjwdzv4afc3d27".into())
+    );
+    assert_eq!(
+        format(Chain::Tron, "41a614f803b6fd780986a42c78ec9c7f77e6ded13c"),
+        Some("TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".into())
+    );
+    assert_eq!(
+        format(Chain::Base, "d8da6bf26964af9d7eed9e03e53415d37aa96045"),
+        Some(VITALIK.into())
+    );
+    assert_eq!(
+        format(Chain::Near, "616c6963652e6e656172"),
+        Some("alice.near".into())
+    );
+    // Malformed, or not supported yet
+    assert_eq!(
+        format(Chain::Solana, "d8da6bf26964af9d7eed9e03e53415d37aa96045"),
+        None
+    );
+    assert_eq!(
+        format(Chain::Bitcoin, "d8da6bf26964af9d7eed9e03e53415d37aa96045"),
+        None
+    );
+    assert_eq!(
+        format(Chain::Stacks, "d8da6bf26964af9d7eed9e03e53415d37aa96045"),
+        None
+    );
+}
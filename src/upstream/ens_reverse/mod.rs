@@ -11,7 +11,7 @@ use crate::tigergraph::upsert::create_identity_to_contract_reverse_resolve_recor
 use crate::tigergraph::upsert::create_isolated_vertex;
 use crate::tigergraph::vertex::{Contract, IdentitiesGraph, Identity};
 use crate::tigergraph::{EdgeList, EdgeWrapperEnum};
-use crate::upstream::{Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem};
+use crate::upstream::{ens, Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem};
 use crate::util::{make_client, make_http_client, naive_now, parse_body, request_with_timeout};
 use async_trait::async_trait;
 use hyper::{Body, Method};
@@ -19,7 +19,7 @@ use serde::Deserialize;
 use tracing::info;
 use uuid::Uuid;
 
-use super::{Fetcher, Platform, Target, TargetProcessedList};
+use super::{ens_rpc::ENSRpc, Fetcher, Platform, Target, TargetProcessedList};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Response {
@@ -43,7 +43,12 @@ impl Fetcher for ENSReverseLookup {
         // If reverse lookup record is reset to empty by user,
         // our cache should also be cleared.
         // Reach this by setting `display_name` into `Some("")`.
-        let reverse_ens = record.reverse_record.clone().unwrap_or("".into());
+        // A reverse record not in its normalized form is invalid (ENSIP-15).
+        let reverse_ens = record
+            .reverse_record
+            .clone()
+            .filter(|name| ens::is_normalized(name))
+            .unwrap_or("".into());
         let mut eth_identity = Identity::default();
         eth_identity.uuid = Some(Uuid::new_v4());
         eth_identity.platform = Platform::Ethereum;
@@ -111,7 +116,12 @@ impl Fetcher for ENSReverseLookup {
         // If reverse lookup record is reset to empty by user,
         // our cache should also be cleared.
         // Reach this by setting `display_name` into `Some("")`.
-        let reverse_ens = record.reverse_record.clone().unwrap_or("".into());
+        // A reverse record not in its normalized form is invalid (ENSIP-15).
+        let reverse_ens = record
+            .reverse_record
+            .clone()
+            .filter(|name| ens::is_normalized(name))
+            .unwrap_or("".into());
         let mut eth_identity = Identity::default();
         eth_identity.uuid = Some(Uuid::new_v4());
         eth_identity.platform = Platform::Ethereum;
@@ -185,7 +195,8 @@ impl Fetcher for ENSReverseLookup {
     }
 
     fn can_fetch(target: &Target) -> bool {
-        target.in_platform_supported(vec![Platform::Ethereum])
+        // Reverse records are read from contracts by `ENSRpc` if enabled.
+        target.in_platform_supported(vec![Platform::Ethereum]) && !ENSRpc::enabled()
     }
 }
 
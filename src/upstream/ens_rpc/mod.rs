@@ -0,0 +1,440 @@
+#[cfg(test)]
+mod tests;
+
+use crate::error::Error;
+use crate::tigergraph::edge::{
+    Hold, HyperEdge, Proof, Resolve, Wrapper, CLAIM, HOLD_CONTRACT, HOLD_IDENTITY, HYPER_EDGE,
+    RESOLVE, RESOLVE_CONTRACT, REVERSE_RESOLVE, REVERSE_RESOLVE_CONTRACT,
+};
+use crate::tigergraph::vertex::{Contract, IdentitiesGraph, Identity};
+use crate::tigergraph::{EdgeList, EdgeWrapperEnum};
+use crate::upstream::{
+    ens::{self, NameRecord, ReverseRecord},
+    EthereumRpc,
+};
+use crate::upstream::{
+    normalize, validate, Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem,
+    Fetcher, Platform, ProofLevel, Target, TargetProcessedList,
+};
+use crate::util::naive_now;
+use async_trait::async_trait;
+use futures::future::join;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Standard text record keys (ENSIP-5) read for each name.
+const TEXT_RECORD_KEYS: [&str; 6] = [
+    "com.twitter",
+    "com.github",
+    "org.telegram",
+    "email",
+    "url",
+    "avatar",
+];
+
+/// Text records which link to an identity on another platform.
+const TEXT_RECORD_LINKS: [(&str, Platform); 4] = [
+    ("com.twitter", Platform::Twitter),
+    ("com.github", Platform::Github),
+    ("org.telegram", Platform::Telegram),
+    ("email", Platform::Email),
+];
+
+/// `(key, value)` of text records set on a name.
+type TextRecords = Vec<(&'static str, String)>;
+
+/// `(platform, address)` of multi-coin address records set on a name.
+type CoinAddresses = Vec<(Platform, String)>;
+
+/// ENS records read from the Registry, resolvers and Universal Resolver contracts
+/// through `[upstream.ethereum_rpc]`, instead of subgraphs or third-party services.
+/// Disabled if `rpc_url` is not configured.
+///
+/// Once enabled, it is the only source of reverse records (instead of `ENSReverseLookup`)
+/// and of records of a name (instead of `TheGraph`), so that no edge is fetched twice.
+/// Names held by an address can't be listed from contracts, so they still come from `TheGraph`.
+#[derive(Clone, Debug)]
+pub struct ENSRpc {}
+
+impl ENSRpc {
+    /// Check if `[upstream.ethereum_rpc]` is configured.
+    pub fn enabled() -> bool {
+        EthereumRpc::from_config().is_some()
+    }
+
+    /// Address `name` resolves to through Universal Resolver, without reading other records.
+    /// Empty if it resolves nowhere.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let rpc = EthereumRpc::from_config().ok_or_else(|| {
+            Error::ParamError("ENSRpc: [upstream.ethereum_rpc] not configured".to_string())
+        })?;
+        let name = ens::normalize(name)?;
+        let address = ens::resolve_address(&rpc, &name).await?;
+        Ok(address
+            .map(|address| (Platform::Ethereum, address))
+            .into_iter()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Fetcher for ENSRpc {
+    async fn fetch(_target: &Target) -> Result<TargetProcessedList, Error> {
+        Ok(vec![])
+    }
+
+    async fn batch_fetch(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
+        if !Self::can_fetch(target) {
+            return Ok((vec![], vec![]));
+        }
+        let rpc = EthereumRpc::from_config().unwrap();
+        match target {
+            Target::Identity(_, identity) => {
+                let address = identity.to_lowercase();
+                let record = ens::lookup_reverse(&rpc, &address).await?;
+                Ok(reverse_edges(&address, record))
+            }
+            Target::NFT(_, _, _, name) => {
+                let name = ens::normalize(name)?;
+                match ens::lookup_name(&rpc, &name).await? {
+                    Some(record) => {
+                        let (texts, coins) = match &record.resolver {
+                            Some(resolver) => {
+                                join(
+                                    lookup_texts(&rpc, resolver, &record.name),
+                                    lookup_coin_addresses(&rpc, resolver, &record.name),
+                                )
+                                .await
+                            }
+                            None => (vec![], vec![]),
+                        };
+                        Ok(name_edges(record, texts, coins))
+                    }
+                    // Not in Registry: may still be a subname resolved by a wildcard
+                    // (ENSIP-10) or offchain resolver of its parent, e.g. `*.cb.id`.
+                    None => match ens::resolve_address(&rpc, &name).await? {
+                        Some(address) => Ok(wildcard_edges(&name, &address)),
+                        None => {
+                            info!(?target, "ENSRpc: Name not registered");
+                            Ok((vec![], vec![]))
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    fn can_fetch(target: &Target) -> bool {
+        // Basenames are read on Base by `Basenames` only.
+        let is_basename = matches!(target, Target::NFT(_, _, _, name) if ens::is_basename(name));
+        (target.in_platform_supported(vec![Platform::Ethereum])
+            || target.in_nft_supported(vec![ContractCategory::ENS], vec![Chain::Ethereum]))
+            && !is_basename
+            && Self::enabled()
+    }
+}
+
+/// Text records of `TEXT_RECORD_KEYS` set on `name`.
+/// Records failed to be read are skipped, so that they don't fail the name.
+async fn lookup_texts(rpc: &EthereumRpc, resolver: &str, name: &str) -> TextRecords {
+    let values = match ens::lookup_texts(rpc, resolver, name, &TEXT_RECORD_KEYS).await {
+        Ok(values) => values,
+        Err(err) => {
+            warn!(name, %err, "ENSRpc: Fail to read text records");
+            return vec![];
+        }
+    };
+    TEXT_RECORD_KEYS
+        .into_iter()
+        .zip(values)
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .collect()
+}
+
+/// Addresses on other chains (ENSIP-9, ENSIP-11) set on `name`, as identities.
+/// Records failed to be read, or of chains whose binary form is not supported yet,
+/// are skipped.
+async fn lookup_coin_addresses(rpc: &EthereumRpc, resolver: &str, name: &str) -> CoinAddresses {
+    // Ethereum address is the `addr(bytes32)` record, already in `NameRecord`.
+    let coin_types: Vec<_> = Chain::coin_types()
+        .filter(|(_, chain, _)| *chain != Chain::Ethereum)
+        .collect();
+    let types: Vec<u32> = coin_types
+        .iter()
+        .map(|(coin_type, _, _)| *coin_type)
+        .collect();
+    let values = match ens::lookup_coin_addresses(rpc, resolver, name, &types).await {
+        Ok(values) => values,
+        Err(err) => {
+            warn!(name, %err, "ENSRpc: Fail to read address records");
+            return vec![];
+        }
+    };
+    let mut addresses = CoinAddresses::new();
+    for ((_, chain, platform), value) in coin_types.into_iter().zip(values) {
+        let Some(bytes) = value else {
+            continue;
+        };
+        let Some(address) = ens::format_address(chain, &bytes) else {
+            debug!(name, %chain, "ENSRpc: Address record not supported");
+            continue;
+        };
+        let address = normalize(platform, &address);
+        if validate(platform, &address).is_ok() && !addresses.contains(&(platform, address.clone()))
+        {
+            addresses.push((platform, address));
+        }
+    }
+    addresses
+}
+
+/// Ownership of a name, and its resolve record if it resolves to its owner.
+/// Identities in its text records (as claims), and addresses in its multi-coin address
+/// records, are linked to it only in the latter case.
+fn name_edges(
+    record: NameRecord,
+    texts: TextRecords,
+    coins: CoinAddresses,
+) -> (TargetProcessedList, EdgeList) {
+    let mut next_targets = vec![Target::Identity(Platform::Ethereum, record.owner.clone())];
+    let mut edges = EdgeList::new();
+    let hv = IdentitiesGraph::default();
+
+    let owner = Identity::new_address(Platform::Ethereum, &record.owner);
+    let mut ens_domain = Identity::new_domain(Platform::ENS, &record.name, false);
+    ens_domain.avatar_url = text_url(&texts, "avatar");
+    ens_domain.profile_url = text_url(&texts, "url");
+    let contract = ens_contract();
+    let ownership = Hold {
+        uuid: Uuid::new_v4(),
+        transaction: None,
+        id: record.name.clone(),
+        source: DataSource::RPCServer,
+        created_at: None,
+        updated_at: naive_now(),
+        fetcher: DataFetcher::RelationService,
+        expired_at: None,
+    };
+
+    edges.push(EdgeWrapperEnum::new_hyper_edge(
+        HyperEdge {}.wrapper(&hv, &owner, HYPER_EDGE),
+    ));
+    let hd = ownership.wrapper(&owner, &ens_domain, HOLD_IDENTITY);
+    let hdc = ownership.wrapper(&owner, &contract, HOLD_CONTRACT);
+    edges.push(EdgeWrapperEnum::new_hold_identity(hd));
+    edges.push(EdgeWrapperEnum::new_hold_contract(hdc));
+
+    // Same as TheGraph: ens_domain joins the identity graph
+    // only when it resolves to its owner.
+    if record.resolved_address.as_ref() == Some(&record.owner) {
+        debug!(domain = record.name, "ENSRpc: Resolved to owner");
+        let resolve = Resolve::new(DataSource::RPCServer, DomainNameSystem::ENS, &record.name);
+        edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+            &hv,
+            &ens_domain,
+            HYPER_EDGE,
+        )));
+        let rs = resolve.wrapper(&ens_domain, &owner, RESOLVE);
+        let rsc = resolve.wrapper(&contract, &owner, RESOLVE_CONTRACT);
+        edges.push(EdgeWrapperEnum::new_resolve(rs));
+        edges.push(EdgeWrapperEnum::new_resolve_contract(rsc));
+
+        for (key, platform, identity) in text_links(&texts) {
+            debug!(domain = record.name, %platform, identity, "ENSRpc: Text record claim");
+            let linked = Identity {
+                uuid: Some(Uuid::new_v4()),
+                platform,
+                identity,
+                uid: None,
+                created_at: None,
+                display_name: None,
+                added_at: naive_now(),
+                avatar_url: None,
+                profile_url: None,
+                updated_at: naive_now(),
+                expired_at: None,
+                reverse: Some(false),
+            };
+            // Self-asserted by the name's manager, so it is only a claim: the linked identity
+            // neither joins the graph of the name, nor is fetched from it.
+            let claim = Proof {
+                uuid: Uuid::new_v4(),
+                source: DataSource::ENSTextRecord,
+                level: ProofLevel::Insecure,
+                record_id: Some(key.to_string()),
+                created_at: None,
+                updated_at: naive_now(),
+                fetcher: DataFetcher::RelationService,
+            };
+            let cl = claim.wrapper(&ens_domain, &linked, CLAIM);
+            edges.push(EdgeWrapperEnum::new_claim(cl));
+        }
+
+        // Set by the name's manager like the ETH address, so resolved the same way.
+        for (platform, address) in coins {
+            if platform == Platform::Ethereum && address == record.owner {
+                continue;
+            }
+            debug!(domain = record.name, %platform, address, "ENSRpc: Address record");
+            let resolved = Identity::new_address(platform, &address);
+            edges.push(EdgeWrapperEnum::new_hyper_edge(
+                HyperEdge {}.wrapper(&hv, &resolved, HYPER_EDGE),
+            ));
+            let rs = resolve.wrapper(&ens_domain, &resolved, RESOLVE);
+            let rsc = resolve.wrapper(&contract, &resolved, RESOLVE_CONTRACT);
+            edges.push(EdgeWrapperEnum::new_resolve(rs));
+            edges.push(EdgeWrapperEnum::new_resolve_contract(rsc));
+            next_targets.push(Target::Identity(platform, address));
+        }
+    }
+
+    (next_targets, edges)
+}
+
+/// Resolve record of a name without owner in Registry.
+/// Its resolver is managed by the parent's, so the name goes with the address it resolves to.
+fn wildcard_edges(name: &str, address: &str) -> (TargetProcessedList, EdgeList) {
+    let mut edges = EdgeList::new();
+    let hv = IdentitiesGraph::default();
+
+    let resolved = Identity::new_address(Platform::Ethereum, address);
+    let ens_domain = Identity::new_domain(Platform::ENS, name, false);
+    let contract = ens_contract();
+    let resolve = Resolve::new(DataSource::RPCServer, DomainNameSystem::ENS, name);
+
+    edges.push(EdgeWrapperEnum::new_hyper_edge(
+        HyperEdge {}.wrapper(&hv, &resolved, HYPER_EDGE),
+    ));
+    edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+        &hv,
+        &ens_domain,
+        HYPER_EDGE,
+    )));
+    let rs = resolve.wrapper(&ens_domain, &resolved, RESOLVE);
+    let rsc = resolve.wrapper(&contract, &resolved, RESOLVE_CONTRACT);
+    edges.push(EdgeWrapperEnum::new_resolve(rs));
+    edges.push(EdgeWrapperEnum::new_resolve_contract(rsc));
+
+    (
+        vec![Target::Identity(Platform::Ethereum, address.to_string())],
+        edges,
+    )
+}
+
+/// `(key, platform, identity)` of identities on other platforms in text records.
+fn text_links(texts: &TextRecords) -> Vec<(&'static str, Platform, String)> {
+    TEXT_RECORD_LINKS
+        .iter()
+        .filter_map(|(key, platform)| {
+            let (_, value) = texts.iter().find(|(text_key, _)| text_key == key)?;
+            text_identity(*platform, value).map(|identity| (*key, *platform, identity))
+        })
+        .collect()
+}
+
+/// Identity in a text record value, which is also often given as a profile URL
+/// (e.g. `https://x.com/handle`), `@handle` or `mailto:` link.
+fn text_identity(platform: Platform, value: &str) -> Option<String> {
+    let value = value.trim().trim_end_matches('/');
+    let value = match platform {
+        Platform::Email => value.trim_start_matches("mailto:"),
+        _ => value.rsplit('/').next().unwrap_or(value),
+    };
+    let identity = normalize(platform, value);
+    validate(platform, &identity).is_ok().then_some(identity)
+}
+
+/// HTTP(S) URL in text record `key`. Others (e.g. NFT avatars `eip155:1/erc721:...`) are ignored.
+fn text_url(texts: &TextRecords, key: &str) -> Option<String> {
+    texts
+        .iter()
+        .find(|(text_key, _)| *text_key == key)
+        .map(|(_, value)| value.trim())
+        .filter(|value| value.starts_with("https://") || value.starts_with("http://"))
+        .map(|value| value.to_string())
+}
+
+/// Primary name of `address`, if it is set and normalized.
+fn reverse_edges(address: &str, record: Option<ReverseRecord>) -> (TargetProcessedList, EdgeList) {
+    let mut edges = EdgeList::new();
+    let hv = IdentitiesGraph::default();
+    let mut eth_identity = Identity::new_address(Platform::Ethereum, address);
+
+    // A reverse record not in its normalized form is invalid (ENSIP-15).
+    // Primary Basenames set on L1 are read on Base by `Basenames` only.
+    if let Some(record) = record
+        .as_ref()
+        .filter(|record| ens::is_basename(&record.name))
+    {
+        debug!(
+            address,
+            name = record.name,
+            "ENSRpc: Reverse record is a Basename"
+        );
+        return (vec![], vec![]);
+    }
+    let Some(record) = record.filter(|record| ens::is_normalized(&record.name)) else {
+        // Still save the address into the identity graph, as an isolated vertex.
+        eth_identity.display_name = Some("".into());
+        edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+            &hv,
+            &eth_identity,
+            HYPER_EDGE,
+        )));
+        return (vec![], edges);
+    };
+    info!(
+        address,
+        name = record.name,
+        resolved_address = ?record.resolved_address,
+        "ENSRpc: Reverse record"
+    );
+
+    eth_identity.display_name = Some(record.name.clone());
+    eth_identity.reverse = Some(true);
+    let ens_domain = Identity::new_domain(Platform::ENS, &record.name, true);
+    let contract = ens_contract();
+    let reverse = Resolve::new(DataSource::RPCServer, DomainNameSystem::ENS, &record.name);
+
+    let rr = reverse.wrapper(&eth_identity, &ens_domain, REVERSE_RESOLVE);
+    let rrc = reverse.wrapper(&eth_identity, &contract, REVERSE_RESOLVE_CONTRACT);
+    edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+        &hv,
+        &eth_identity,
+        HYPER_EDGE,
+    )));
+    edges.push(EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+        &hv,
+        &ens_domain,
+        HYPER_EDGE,
+    )));
+    edges.push(EdgeWrapperEnum::new_reverse_resolve(rr));
+    edges.push(EdgeWrapperEnum::new_reverse_resolve_contract(rrc));
+    // Forward resolution comes along with the reverse one from the Universal Resolver,
+    // no need to look it up again to verify the primary name.
+    if record.resolved_address.as_deref() == Some(address) {
+        let rs = reverse.wrapper(&ens_domain, &eth_identity, RESOLVE);
+        let rsc = reverse.wrapper(&contract, &eth_identity, RESOLVE_CONTRACT);
+        edges.push(EdgeWrapperEnum::new_resolve(rs));
+        edges.push(EdgeWrapperEnum::new_resolve_contract(rsc));
+    }
+
+    let next = Target::NFT(
+        Chain::Ethereum,
+        ContractCategory::ENS,
+        ContractCategory::ENS.default_contract_address().unwrap(),
+        record.name,
+    );
+    (vec![next], edges)
+}
+
+fn ens_contract() -> Contract {
+    Contract {
+        uuid: Uuid::new_v4(),
+        category: ContractCategory::ENS,
+        address: ContractCategory::ENS.default_contract_address().unwrap(),
+        chain: Chain::Ethereum,
+        symbol: None,
+        updated_at: naive_now(),
+    }
+}
@@ -0,0 +1,247 @@
+use super::*;
+use crate::tigergraph::{edge::HYPER_EDGE_REVERSE, vertex::Vertex};
+
+const OWNER: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+const OTHER: &str = "0x231b0ee14048e9dccd1d247744d114a4eb5e8e63";
+
+fn name_record(resolved_address: &str) -> NameRecord {
+    NameRecord {
+        name: "vitalik.eth".into(),
+        owner: OWNER.into(),
+        resolver: Some(OTHER.into()),
+        resolved_address: Some(resolved_address.into()),
+    }
+}
+
+#[test]
+fn test_name_edges() {
+    let (next, edges) = name_edges(name_record(OWNER), vec![], vec![]);
+    assert_eq!(
+        next,
+        vec![Target::Identity(Platform::Ethereum, OWNER.into())]
+    );
+    // Hyper edges of owner and domain, hold and resolve records.
+    assert_eq!(edges.len(), 6);
+
+    // Resolving to someone else: ownership only.
+    let texts = vec![("com.twitter", "VitalikButerin".to_string())];
+    let (next, edges) = name_edges(name_record(OTHER), texts, vec![]);
+    assert_eq!(
+        next,
+        vec![Target::Identity(Platform::Ethereum, OWNER.into())]
+    );
+    assert_eq!(edges.len(), 3);
+}
+
+#[test]
+fn test_name_edges_text_records() {
+    let texts = vec![
+        ("com.twitter", "https://x.com/VitalikButerin".to_string()),
+        ("com.github", "@vbuterin".to_string()),
+        ("email", "not an email".to_string()),
+        ("avatar", "https://euc.li/vitalik.eth".to_string()),
+    ];
+    let (next, edges) = name_edges(name_record(OWNER), texts, vec![]);
+    // Claims are not fetched.
+    assert_eq!(
+        next,
+        vec![Target::Identity(Platform::Ethereum, OWNER.into())]
+    );
+    // A claim for each link, without hyper edge.
+    assert_eq!(edges.len(), 6 + 2);
+    let claims: Vec<(String, ProofLevel, Option<String>)> = edges
+        .iter()
+        .filter_map(|edge| match edge {
+            EdgeWrapperEnum::Claim(wrapper) => Some((
+                wrapper.target.primary_key(),
+                wrapper.edge.level,
+                wrapper.edge.record_id.clone(),
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        claims,
+        vec![
+            (
+                "twitter,vitalikbuterin".into(),
+                ProofLevel::Insecure,
+                Some("com.twitter".into())
+            ),
+            (
+                "github,vbuterin".into(),
+                ProofLevel::Insecure,
+                Some("com.github".into())
+            ),
+        ]
+    );
+    assert_eq!(
+        edges
+            .iter()
+            .filter(|edge| edge.e_type() == HYPER_EDGE_REVERSE)
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_name_edges_coin_addresses() {
+    let coins = vec![
+        (
+            Platform::Bitcoin,
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_string(),
+        ),
+        // Same address on an L2: already resolved.
+        (Platform::Ethereum, OWNER.to_string()),
+    ];
+    let (next, edges) = name_edges(name_record(OWNER), vec![], coins.clone());
+    assert_eq!(
+        next,
+        vec![
+            Target::Identity(Platform::Ethereum, OWNER.into()),
+            Target::Identity(
+                Platform::Bitcoin,
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".into()
+            ),
+        ]
+    );
+    // Hyper edge and resolve records for each address.
+    assert_eq!(edges.len(), 6 + 3);
+
+    // Resolving to someone else: ownership only.
+    let (next, edges) = name_edges(name_record(OTHER), vec![], coins);
+    assert_eq!(
+        next,
+        vec![Target::Identity(Platform::Ethereum, OWNER.into())]
+    );
+    assert_eq!(edges.len(), 3);
+}
+
+#[test]
+fn test_wildcard_edges() {
+    let (next, edges) = wildcard_edges("alice.cb.id", OWNER);
+    assert_eq!(
+        next,
+        vec![Target::Identity(Platform::Ethereum, OWNER.into())]
+    );
+    // Hyper edges of address and domain, resolve records.
+    assert_eq!(edges.len(), 4);
+}
+
+#[test]
+fn test_text_identity() {
+    assert_eq!(
+        text_identity(Platform::Twitter, "https://twitter.com/VitalikButerin/"),
+        Some("vitalikbuterin".into())
+    );
+    assert_eq!(
+        text_identity(Platform::Telegram, "https://t.me/durov"),
+        Some("durov".into())
+    );
+    assert_eq!(
+        text_identity(Platform::Email, "mailto:Nick@ens.domains"),
+        Some("nick@ens.domains".into())
+    );
+    assert_eq!(text_identity(Platform::Github, "-"), None);
+}
+
+#[test]
+fn test_text_url() {
+    let texts = vec![
+        ("url", "https://vitalik.ca".to_string()),
+        (
+            "avatar",
+            "eip155:1/erc721:0xb7f7f6c52f2e2fdb1963eab30438024864c313f6/2430".to_string(),
+        ),
+    ];
+    assert_eq!(text_url(&texts, "url"), Some("https://vitalik.ca".into()));
+    assert_eq!(text_url(&texts, "avatar"), None);
+}
+
+#[test]
+fn test_reverse_edges() {
+    let (next, edges) = reverse_edges(
+        OWNER,
+        Some(ReverseRecord {
+            name: "vitalik.eth".into(),
+            resolved_address: Some(OWNER.into()),
+        }),
+    );
+    assert_eq!(
+        next,
+        vec![Target::NFT(
+            Chain::Ethereum,
+            ContractCategory::ENS,
+            ContractCategory::ENS.default_contract_address().unwrap(),
+            "vitalik.eth".into(),
+        )]
+    );
+    // Hyper edges, reverse and resolve records.
+    assert_eq!(edges.len(), 6);
+
+    // Resolving to someone else: not verified here.
+    let (_, edges) = reverse_edges(
+        OWNER,
+        Some(ReverseRecord {
+            name: "vitalik.eth".into(),
+            resolved_address: Some(OTHER.into()),
+        }),
+    );
+    assert_eq!(edges.len(), 4);
+
+    // Not set, or not normalized: only the address itself.
+    let (next, edges) = reverse_edges(OWNER, None);
+    assert!(next.is_empty());
+    assert_eq!(edges.len(), 1);
+    let (next, edges) = reverse_edges(
+        OWNER,
+        Some(ReverseRecord {
+            name: "Vitalik.eth".into(),
+            resolved_address: Some(OWNER.into()),
+        }),
+    );
+    assert!(next.is_empty());
+    assert_eq!(edges.len(), 1);
+
+    // A Basename: left to `Basenames`.
+    let (next, edges) = reverse_edges(
+        OWNER,
+        Some(ReverseRecord {
+            name: "jesse.base.eth".into(),
+            resolved_address: Some(OWNER.into()),
+        }),
+    );
+    assert!(next.is_empty());
+    assert!(edges.is_empty());
+}
+
+#[test]
+fn test_one_source_per_edge() {
+    use crate::upstream::{ens_reverse::ENSReverseLookup, the_graph::TheGraph};
+
+    let wallet = Target::Identity(Platform::Ethereum, OWNER.into());
+    let name = Target::NFT(
+        Chain::Ethereum,
+        ContractCategory::ENS,
+        ContractCategory::ENS.default_contract_address().unwrap(),
+        "vitalik.eth".into(),
+    );
+    // Reverse records of a wallet
+    assert_ne!(
+        ENSRpc::can_fetch(&wallet),
+        ENSReverseLookup::can_fetch(&wallet)
+    );
+    // Records of a name
+    assert_ne!(ENSRpc::can_fetch(&name), TheGraph::can_fetch(&name));
+    // Names held by a wallet can only be listed by TheGraph.
+    assert!(TheGraph::can_fetch(&wallet));
+    // Basenames are only read on Base.
+    let basename = Target::NFT(
+        Chain::Ethereum,
+        ContractCategory::ENS,
+        ContractCategory::ENS.default_contract_address().unwrap(),
+        "jesse.base.eth".into(),
+    );
+    assert!(!ENSRpc::can_fetch(&basename));
+    assert!(!TheGraph::can_fetch(&basename));
+}
@@ -0,0 +1,211 @@
+//! Client of Ethereum JSON-RPC endpoints, for contract calls and blocks.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    config::C,
+    error::Error,
+    util::{make_client, parse_body, request_with_timeout},
+};
+use http::StatusCode;
+use hyper::{Body, Method};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+/// Client of an Ethereum JSON-RPC endpoint.
+#[derive(Debug, Clone)]
+pub struct EthereumRpc {
+    url: String,
+    /// Allow CCIP-read gateways over plain HTTP, and in local or private networks.
+    /// Only for gateways stood up by tests.
+    pub(crate) local_gateways: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct RpcResponse<T = String> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+    /// Revert data, hex.
+    data: Option<String>,
+}
+
+impl RpcError {
+    fn is_revert(&self) -> bool {
+        self.code == 3 || self.message.contains("revert")
+    }
+}
+
+/// Block returned by `eth_getBlockByNumber`, with full transaction objects.
+#[derive(Deserialize, Debug, Clone)]
+pub struct RpcBlock {
+    pub transactions: Vec<RpcTransaction>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransaction {
+    pub hash: String,
+    /// Hex.
+    pub block_number: String,
+    pub from: String,
+    /// `None` for contract creations.
+    pub to: Option<String>,
+    /// In Wei, hex.
+    pub value: String,
+}
+
+/// Output of `eth_call`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutput {
+    Return(Vec<u8>),
+    /// Reverted, with revert data (maybe empty, depending on the node).
+    Revert(Vec<u8>),
+}
+
+impl EthereumRpc {
+    pub fn new(url: &str) -> Self {
+        EthereumRpc {
+            url: url.to_string(),
+            local_gateways: false,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_local_gateways(mut self) -> Self {
+        self.local_gateways = true;
+        self
+    }
+
+    /// Endpoint in `[upstream.ethereum_rpc]`, `None` if not configured.
+    pub fn from_config() -> Option<Self> {
+        let url = C.upstream.ethereum_rpc.rpc_url.trim();
+        match url.is_empty() {
+            true => None,
+            false => Some(Self::new(url)),
+        }
+    }
+
+    /// `eth_call` contract `to` with calldata `data` at the latest block, returning its output.
+    /// A revert is an error.
+    pub async fn call(&self, to: &str, data: &[u8]) -> Result<Vec<u8>, Error> {
+        match self.try_call(to, data).await? {
+            CallOutput::Return(output) => Ok(output),
+            CallOutput::Revert(_) => Err(Error::General(
+                format!("EthereumRPC eth_call to {} reverted", to),
+                StatusCode::BAD_GATEWAY,
+            )),
+        }
+    }
+
+    /// `eth_call` contract `to` with calldata `data` at the latest block.
+    pub async fn try_call(&self, to: &str, data: &[u8]) -> Result<CallOutput, Error> {
+        let params = json!([{"to": to, "data": format!("0x{}", hex::encode(data))}, "latest"]);
+        let resp: RpcResponse = self.request("eth_call", params).await?;
+        match resp.error {
+            Some(err) if err.is_revert() => Ok(CallOutput::Revert(
+                decode_hex(&err.data.unwrap_or_default()).unwrap_or_default(),
+            )),
+            Some(err) => Err(Error::General(
+                format!("EthereumRPC eth_call error {}: {}", err.code, err.message),
+                StatusCode::BAD_GATEWAY,
+            )),
+            None => Ok(CallOutput::Return(decode_hex(
+                &resp.result.unwrap_or_default(),
+            )?)),
+        }
+    }
+
+    /// Number of the latest block.
+    pub async fn block_number(&self) -> Result<u64, Error> {
+        let resp: RpcResponse = self.request("eth_blockNumber", json!([])).await?;
+        match result_of("eth_blockNumber", resp)? {
+            Some(number) => decode_quantity(&number),
+            None => Err(Error::General(
+                "EthereumRPC eth_blockNumber returned no result".to_string(),
+                StatusCode::BAD_GATEWAY,
+            )),
+        }
+    }
+
+    /// Block `number` with all its transactions. `None` if it is not mined yet.
+    pub async fn block_with_transactions(&self, number: u64) -> Result<Option<RpcBlock>, Error> {
+        let params = json!([format!("0x{:x}", number), true]);
+        let resp: RpcResponse<RpcBlock> = self.request("eth_getBlockByNumber", params).await?;
+        result_of("eth_getBlockByNumber", resp)
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<RpcResponse<T>, Error> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let req = hyper::Request::builder()
+            .method(Method::POST)
+            .uri(&self.url)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .map_err(|err| Error::ParamError(format!("EthereumRPC Build Request Error {}", err)))?;
+
+        let client = make_client();
+        let mut resp = request_with_timeout(&client, req, None)
+            .await
+            .map_err(|err| {
+                Error::ManualHttpClientError(format!(
+                    "EthereumRPC fetch | {} error: {:?}",
+                    method,
+                    err.to_string()
+                ))
+            })?;
+        if !resp.status().is_success() {
+            return Err(Error::General(
+                format!("EthereumRPC fetch Error: {}", resp.status()),
+                resp.status(),
+            ));
+        }
+
+        parse_body(&mut resp).await
+    }
+}
+
+/// Result of a call to `method`, any error in `resp` is an error.
+fn result_of<T>(method: &str, resp: RpcResponse<T>) -> Result<Option<T>, Error> {
+    match resp.error {
+        Some(err) => Err(Error::General(
+            format!("EthereumRPC {} error {}: {}", method, err.code, err.message),
+            StatusCode::BAD_GATEWAY,
+        )),
+        None => Ok(resp.result),
+    }
+}
+
+/// Quantity encoded in hex, e.g. `0x1b4`.
+pub fn decode_quantity(quantity: &str) -> Result<u64, Error> {
+    u64::from_str_radix(quantity.trim_start_matches("0x"), 16).map_err(|err| {
+        Error::General(
+            format!("EthereumRPC invalid quantity {}: {}", quantity, err),
+            StatusCode::BAD_GATEWAY,
+        )
+    })
+}
+
+pub fn decode_hex(data: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(data.trim_start_matches("0x")).map_err(|err| {
+        Error::General(
+            format!("EthereumRPC invalid hex: {}", err),
+            StatusCode::BAD_GATEWAY,
+        )
+    })
+}
@@ -0,0 +1,38 @@
+use super::*;
+use serde_json::json;
+use warp::Filter;
+
+#[tokio::test]
+async fn test_block_with_transactions() -> Result<(), Error> {
+    let route = warp::post()
+        .and(warp::body::json())
+        .map(|req: serde_json::Value| {
+            let result = match (req["method"].as_str(), req["params"][0].as_str()) {
+                (Some("eth_blockNumber"), _) => json!("0x10"),
+                (Some("eth_getBlockByNumber"), Some("0xf")) => json!({
+                    "hash": "0xabc",
+                    "transactions": [{
+                        "hash": "0x01",
+                        "blockNumber": "0xf",
+                        "from": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                        "to": null,
+                        "value": "0xde0b6b3a7640000",
+                    }],
+                }),
+                _ => json!(null),
+            };
+            warp::reply::json(&json!({"jsonrpc": "2.0", "id": req["id"], "result": result}))
+        });
+    let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    let rpc = EthereumRpc::new(&format!("http://{}", addr));
+
+    assert_eq!(rpc.block_number().await?, 16);
+    let block = rpc.block_with_transactions(15).await?.unwrap();
+    assert_eq!(block.transactions.len(), 1);
+    assert_eq!(block.transactions[0].to, None);
+    assert_eq!(block.transactions[0].value, "0xde0b6b3a7640000");
+    // Not mined yet.
+    assert!(rpc.block_with_transactions(17).await?.is_none());
+    Ok(())
+}
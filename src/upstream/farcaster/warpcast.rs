@@ -3,16 +3,17 @@ use crate::{
     error::Error,
     tigergraph::upsert::{create_identity_to_identity_hold_record, create_isolated_vertex},
     tigergraph::{
-        EdgeList, EdgeWrapperEnum,
+        save_follows, EdgeList, EdgeWrapperEnum,
         {
-            edge::{Hold, HyperEdge, Wrapper, HOLD_IDENTITY, HYPER_EDGE},
+            edge::{Follow, Hold, HyperEdge, Wrapper, FOLLOW, HOLD_IDENTITY, HYPER_EDGE},
             vertex::{IdentitiesGraph, Identity},
         },
     },
     upstream::{DataFetcher, DataSource, Platform, Target, TargetProcessedList},
     util::{
-        make_client, make_http_client, naive_datetime_from_milliseconds,
-        naive_datetime_to_milliseconds, naive_now, parse_body, request_with_timeout,
+        background::spawn_background, make_client, make_http_client,
+        naive_datetime_from_milliseconds, naive_datetime_to_milliseconds, naive_now, parse_body,
+        request_with_timeout,
     },
 };
 use chrono::NaiveDateTime;
@@ -20,9 +21,14 @@ use http::uri::InvalidUri;
 use hyper::{client::HttpConnector, Client};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use tracing::error;
+use tracing::{error, warn};
 use uuid::Uuid;
 
+/// Users per page of `v2/following` and `v2/followers`.
+const FOLLOW_PAGE_LIMIT: u32 = 100;
+/// Pages of follows fetched for a user, each way.
+const MAX_FOLLOW_PAGES: u32 = 5;
+
 pub async fn fetch_connections_by_platform_identity(
     platform: &Platform,
     identity: &str,
@@ -63,6 +69,7 @@ pub async fn batch_fetch_by_username(
         return Ok((vec![], vec![]));
     }
     let verifications = verifications.unwrap();
+    spawn_follows(user.clone());
     // isolated vertex
     if verifications.is_empty() {
         let isolated_farcaster: Identity = Identity {
@@ -142,10 +149,67 @@ pub async fn batch_fetch_by_username(
         edges.push(EdgeWrapperEnum::new_hold_identity(hd));
         targets.push(Target::Identity(protocol, address.clone()))
     }
-
     Ok((targets, edges))
 }
 
+/// Fetch and save follows of `user` in background, so they never hold up fetching its identities.
+fn spawn_follows(user: User) {
+    spawn_background(async move {
+        let follows = follow_edges(&user).await;
+        save_follows(&make_http_client(), follows).await
+    });
+}
+
+/// Follows of `user` both ways, at most `MAX_FOLLOW_PAGES` pages each.
+/// Followed users are not fetched further, and failures only lose the follows.
+async fn follow_edges(user: &User) -> EdgeList {
+    let farcaster = follow_identity(user.fid, &user.username, Some(user.display_name.clone()));
+    let mut edges = EdgeList::new();
+    for followers in [false, true] {
+        let users = match get_follows(user.fid, followers).await {
+            Ok(users) => users,
+            Err(err) => {
+                warn!(fid = user.fid, followers, %err, "Warpcast | Fail to fetch follows");
+                continue;
+            }
+        };
+        for other in users.iter() {
+            // Users without a username can not be an identity.
+            let Some(username) = other.username.as_ref() else {
+                continue;
+            };
+            let other = follow_identity(other.fid, username, other.display_name.clone());
+            let follow = Follow {
+                source: DataSource::Farcaster,
+                ..Default::default()
+            };
+            let wrapper = match followers {
+                true => follow.wrapper(&other, &farcaster, FOLLOW),
+                false => follow.wrapper(&farcaster, &other, FOLLOW),
+            };
+            edges.push(EdgeWrapperEnum::new_follow(wrapper));
+        }
+    }
+    edges
+}
+
+fn follow_identity(fid: i64, username: &str, display_name: Option<String>) -> Identity {
+    Identity {
+        uuid: Some(Uuid::new_v4()),
+        platform: Platform::Farcaster,
+        identity: username.to_string(),
+        uid: Some(fid.to_string()),
+        created_at: None,
+        display_name,
+        added_at: naive_now(),
+        avatar_url: None,
+        profile_url: None,
+        updated_at: naive_now(),
+        expired_at: None,
+        reverse: Some(false),
+    }
+}
+
 pub async fn batch_fetch_by_signer(
     platform: &Platform,
     address: &str,
@@ -421,6 +485,32 @@ pub struct Location {
     pub description: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowResponse {
+    pub errors: Option<Vec<Message>>,
+    pub result: Option<FollowResult>,
+    pub next: Option<Next>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowResult {
+    pub users: Vec<FollowUser>,
+}
+
+/// User in follow lists. Some users have no username or display name.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowUser {
+    pub fid: i64,
+    pub username: Option<String>,
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Next {
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VerificationResponse {
     pub errors: Option<Vec<Message>>,
@@ -443,18 +533,21 @@ pub struct Verification {
 }
 
 async fn user_by_username(username: &str) -> Result<Option<User>, Error> {
+    fetch_user(&format!("v2/user-by-username?username={}", username)).await
+}
+
+pub async fn user_by_fid(fid: i64) -> Result<Option<User>, Error> {
+    fetch_user(&format!("v2/user?fid={}", fid)).await
+}
+
+/// Fetch a user profile from `path` of Warpcast API, e.g. `v2/user?fid=3`.
+async fn fetch_user(path: &str) -> Result<Option<User>, Error> {
     let client = make_client();
-    let uri: http::Uri = format!(
-        "{}/v2/user-by-username?username={}",
-        C.upstream.warpcast_api.url, username
-    )
-    .parse()
-    .map_err(|err: InvalidUri| {
-        Error::ParamError(format!(
-            "v2/user-by-username?username={} Uri format Error: {}",
-            username, err
-        ))
-    })?;
+    let uri: http::Uri = format!("{}/{}", C.upstream.warpcast_api.url, path)
+        .parse()
+        .map_err(|err: InvalidUri| {
+            Error::ParamError(format!("{} Uri format Error: {}", path, err))
+        })?;
 
     let req = hyper::Request::builder()
         .method(http::Method::GET)
@@ -464,19 +557,14 @@ async fn user_by_username(username: &str) -> Result<Option<User>, Error> {
             format!("Bearer {}", C.upstream.warpcast_api.token),
         )
         .body(hyper::Body::empty())
-        .map_err(|err| {
-            Error::ParamError(format!(
-                "v2/user-by-username?username={} Request build error: {}",
-                username, err
-            ))
-        })?;
+        .map_err(|err| Error::ParamError(format!("{} Request build error: {}", path, err)))?;
 
     let mut resp = request_with_timeout(&client, req, None)
         .await
         .map_err(|err| {
             Error::ManualHttpClientError(format!(
-                "Warpcast fetch error | failed to fetch user-by-username?username={} | {:?}",
-                username,
+                "Warpcast fetch error | failed to fetch {} | {:?}",
+                path,
                 err.to_string()
             ))
         })?;
@@ -485,8 +573,8 @@ async fn user_by_username(username: &str) -> Result<Option<User>, Error> {
         Ok(r) => match r.errors {
             Some(errors) => {
                 let err_message = format!(
-                    "Warpcast fetch error| failed to fetch user-by-username?username={}, message: {:?}",
-                    username, errors
+                    "Warpcast fetch error| failed to fetch {}, message: {:?}",
+                    path, errors
                 );
                 error!(err_message);
                 None
@@ -503,7 +591,6 @@ async fn user_by_username(username: &str) -> Result<Option<User>, Error> {
             )));
         }
     };
-
     Ok(result)
 }
 
@@ -646,3 +733,78 @@ async fn get_verifications(fid: i64) -> Result<Option<Vec<Verification>>, Error>
     };
     Ok(result)
 }
+
+/// Users following `fid` (`followers: true`), or followed by it.
+async fn get_follows(fid: i64, followers: bool) -> Result<Vec<FollowUser>, Error> {
+    let client = make_client();
+    let path = match followers {
+        true => "followers",
+        false => "following",
+    };
+    let mut users: Vec<FollowUser> = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_FOLLOW_PAGES {
+        let mut url = format!(
+            "{}/v2/{}?fid={}&limit={}",
+            C.upstream.warpcast_api.url, path, fid, FOLLOW_PAGE_LIMIT
+        );
+        if let Some(cursor) = cursor.as_ref() {
+            url = format!("{}&cursor={}", url, urlencoding::encode(cursor));
+        }
+        let uri: http::Uri = url.parse().map_err(|err: InvalidUri| {
+            Error::ParamError(format!("v2/{}?fid={} Uri format Error: {}", path, fid, err))
+        })?;
+
+        let req = hyper::Request::builder()
+            .method(http::Method::GET)
+            .uri(uri)
+            .header(
+                "authorization",
+                format!("Bearer {}", C.upstream.warpcast_api.token),
+            )
+            .body(hyper::Body::empty())
+            .map_err(|err| {
+                Error::ParamError(format!(
+                    "v2/{}?fid={} Request build error: {}",
+                    path, fid, err
+                ))
+            })?;
+
+        let mut resp = request_with_timeout(&client, req, None)
+            .await
+            .map_err(|err| {
+                Error::ManualHttpClientError(format!(
+                    "Warpcast fetch error | failed to fetch {}?fid={} | {:?}",
+                    path,
+                    fid,
+                    err.to_string()
+                ))
+            })?;
+
+        let result = match parse_body::<FollowResponse>(&mut resp).await {
+            Ok(r) => r,
+            Err(err) => {
+                return Err(Error::ManualHttpClientError(format!(
+                    "Warpcast fetch error | parse_body error: {}",
+                    err
+                )));
+            }
+        };
+        if let Some(errors) = result.errors {
+            let err_message = format!(
+                "Warpcast fetch error| failed to fetch {}?fid={}, message: {:?}",
+                path, fid, errors
+            );
+            error!(err_message);
+            break;
+        }
+        if let Some(page) = result.result {
+            users.extend(page.users);
+        }
+        cursor = result.next.and_then(|next| next.cursor);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(users)
+}
@@ -91,6 +91,18 @@ impl Fetcher for Genome {
     }
 }
 
+impl Genome {
+    /// Owners `.gno` `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let name = name.to_lowercase();
+        let domains = get_address(name.trim_end_matches(".gno")).await?;
+        Ok(domains
+            .into_iter()
+            .map(|d| (Platform::Ethereum, d.owner.to_lowercase()))
+            .collect())
+    }
+}
+
 async fn batch_fetch_by_address(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
     let address = target.identity()?.to_lowercase();
     let domains = get_name(&address).await?;
@@ -8,7 +8,7 @@ use crate::tigergraph::edge::{
 };
 use crate::tigergraph::upsert::create_identity_to_identity_proof_two_way_binding;
 use crate::tigergraph::vertex::{IdentitiesGraph, Identity, IdentityRecord};
-use crate::tigergraph::{BaseResponse, Graph};
+use crate::tigergraph::{timed_request, BaseResponse, Graph};
 use crate::tigergraph::{EdgeList, EdgeWrapperEnum};
 use crate::upstream::{DataSource, Fetcher, Platform, ProofLevel, TargetProcessedList};
 use crate::util::{
@@ -194,7 +194,7 @@ async fn fake_fetch_connections_by_platform_identity(
         .body(Body::empty())
         .map_err(|_err| Error::ParamError(format!("ParamError Error | {}", _err)))?;
 
-    let mut resp = cli.request(req).await.map_err(|err| {
+    let mut resp = timed_request(&cli, req).await.map_err(|err| {
         Error::ManualHttpClientError(format!(
             "query query_keybase_connections?p={} error | Fail to request: {:?}",
             encoded_vid,
@@ -4,23 +4,27 @@ mod tests;
 use crate::config::C;
 use crate::error::Error;
 use crate::tigergraph::edge::{
-    Hold, HyperEdge, Resolve, Wrapper, HOLD_IDENTITY, HYPER_EDGE, RESOLVE, REVERSE_RESOLVE,
+    Follow, Hold, HyperEdge, Resolve, Wrapper, FOLLOW, HOLD_IDENTITY, HYPER_EDGE, RESOLVE,
+    REVERSE_RESOLVE,
 };
 use crate::tigergraph::upsert::create_identity_domain_resolve_record;
 use crate::tigergraph::upsert::create_identity_domain_reverse_resolve_record;
 use crate::tigergraph::upsert::create_identity_to_identity_hold_record;
 use crate::tigergraph::vertex::{IdentitiesGraph, Identity};
-use crate::tigergraph::{EdgeList, EdgeWrapperEnum};
+use crate::tigergraph::{save_follows, EdgeList, EdgeWrapperEnum};
 use crate::upstream::{
     DataFetcher, DataSource, DomainNameSystem, Fetcher, Platform, Target, TargetProcessedList,
 };
-use crate::util::{make_http_client, naive_now, utc_to_naive};
+use crate::util::{background::spawn_background, make_http_client, naive_now, utc_to_naive};
 use async_trait::async_trait;
 use cynic::{http::SurfExt, QueryBuilder};
 use hyper::{client::HttpConnector, Client};
 use tracing::{trace, warn};
 use uuid::Uuid;
 
+/// Pages of follows fetched for a profile, each way.
+const MAX_FOLLOW_PAGES: u32 = 5;
+
 mod schema {
     cynic::use_schema!("src/upstream/lensv2/schema.graphql");
 }
@@ -68,6 +72,63 @@ pub struct PaginatedProfileResult {
     pub items: Vec<Profile>,
 }
 
+// Query followers / following of a profile
+#[derive(cynic::QueryVariables, Debug)]
+pub struct FollowRequestVariables {
+    pub profile_id: ProfileId,
+    pub limit: LimitType,
+    pub cursor: Option<Cursor>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    graphql_type = "Query",
+    schema_path = "src/upstream/lensv2/schema.graphql",
+    variables = "FollowRequestVariables"
+)]
+pub struct FollowersQuery {
+    #[arguments(request: { of: $profile_id, limit: $limit, cursor: $cursor })]
+    pub followers: PaginatedFollowResult,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    graphql_type = "Query",
+    schema_path = "src/upstream/lensv2/schema.graphql",
+    variables = "FollowRequestVariables"
+)]
+pub struct FollowingQuery {
+    #[arguments(request: { for: $profile_id, limit: $limit, cursor: $cursor })]
+    pub following: PaginatedFollowResult,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(
+    graphql_type = "PaginatedProfileResult",
+    schema_path = "src/upstream/lensv2/schema.graphql"
+)]
+pub struct PaginatedFollowResult {
+    pub items: Vec<Profile>,
+    pub page_info: PaginatedResultInfo,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(schema_path = "src/upstream/lensv2/schema.graphql")]
+pub struct PaginatedResultInfo {
+    pub next: Option<Cursor>,
+}
+
+#[derive(cynic::Enum, Clone, Copy, Debug)]
+#[cynic(schema_path = "src/upstream/lensv2/schema.graphql")]
+pub enum LimitType {
+    #[cynic(rename = "Ten")]
+    Ten,
+    #[cynic(rename = "TwentyFive")]
+    TwentyFive,
+    #[cynic(rename = "Fifty")]
+    Fifty,
+}
+
 #[derive(cynic::QueryFragment, Debug, Clone)]
 #[cynic(schema_path = "src/upstream/lensv2/schema.graphql")]
 pub struct Profile {
@@ -105,6 +166,9 @@ pub struct HandleInfo {
 #[derive(cynic::Scalar, Debug, Clone)]
 pub struct ChainId(pub u32);
 
+#[derive(cynic::Scalar, Debug, Clone)]
+pub struct Cursor(pub String);
+
 #[derive(cynic::Scalar, Debug, Clone)]
 pub struct DateTime(pub String);
 
@@ -162,6 +226,7 @@ impl Fetcher for LensV2 {
     }
 }
 
+/// Profiles of full handle `handle_name` (`lens/*`). Fails if Lens API does not respond.
 async fn query_by_handle(handle_name: &str) -> Result<Vec<Profile>, Error> {
     let operation = ProfileQueryByHandles::build(ProfilesRequestVariables {
         handles: Some(vec![Handle(handle_name.to_string())]),
@@ -169,24 +234,34 @@ async fn query_by_handle(handle_name: &str) -> Result<Vec<Profile>, Error> {
     });
     let response = surf::post(C.upstream.lens_api.url.clone())
         .run_graphql(operation)
-        .await;
-    if response.is_err() {
-        warn!(
-            "LensV2 {} | Failed to fetch: {}",
-            handle_name,
-            response.unwrap_err(),
-        );
-        return Ok(vec![]);
-    }
+        .await
+        .map_err(|err| {
+            Error::GraphQLError(format!("LensV2 {} | Failed to fetch: {}", handle_name, err))
+        })?;
 
-    let profiles = response
-        .unwrap()
-        .data
-        .map_or(vec![], |data| data.profiles.items);
+    let profiles = response.data.map_or(vec![], |data| data.profiles.items);
 
     Ok(profiles)
 }
 
+impl LensV2 {
+    /// Owner Lens handle `name` (`*.lens`) resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let handle_name = format!("lens/{}", name.trim_end_matches(".lens"));
+        let profiles = query_by_handle(&handle_name).await?;
+        Ok(profiles
+            .into_iter()
+            .next()
+            .filter(|profile| profile.handle.is_some())
+            .map(|profile| {
+                let owner = profile.owned_by.address.0.to_ascii_lowercase();
+                (Platform::Ethereum, owner)
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
 async fn query_by_wallet(wallet: &str) -> Result<Vec<Profile>, Error> {
     let operation = ProfileQueryByHandles::build(ProfilesRequestVariables {
         handles: None,
@@ -216,7 +291,13 @@ async fn batch_fetch_by_handle(target: &Target) -> Result<(TargetProcessedList,
     let target_var = target.identity()?;
     let handle_name = target_var.trim_end_matches(".lens");
     let full_handle = format!("lens/{}", handle_name);
-    let profiles = query_by_handle(&full_handle).await?;
+    let profiles = match query_by_handle(&full_handle).await {
+        Ok(profiles) => profiles,
+        Err(err) => {
+            warn!(%err, "LensV2 target {} | Failed to fetch", target);
+            vec![]
+        }
+    };
     if profiles.is_empty() {
         warn!("LensV2 target {} | No Result", target,);
         return Ok((vec![], vec![]));
@@ -331,6 +412,7 @@ async fn batch_fetch_by_handle(target: &Target) -> Result<(TargetProcessedList,
     let rs = resolve.wrapper(&lens, &addr, RESOLVE);
     edges.push(EdgeWrapperEnum::new_hold_identity(hd));
     edges.push(EdgeWrapperEnum::new_resolve(rs));
+    spawn_follows(lens.clone(), lens_profile.id.clone());
 
     next_targets.push(Target::Identity(Platform::Ethereum, evm_owner.clone()));
 
@@ -449,11 +531,101 @@ async fn batch_fetch_by_wallet(target: &Target) -> Result<(TargetProcessedList,
         let rs = resolve.wrapper(&lens, &addr, RESOLVE);
         edges.push(EdgeWrapperEnum::new_hold_identity(hd));
         edges.push(EdgeWrapperEnum::new_resolve(rs));
+        spawn_follows(lens.clone(), lens_profile.id.clone());
     }
 
     Ok((vec![], edges))
 }
 
+/// Fetch and save follows of `lens` in background, so they never hold up fetching its identities.
+fn spawn_follows(lens: Identity, profile_id: ProfileId) {
+    spawn_background(async move {
+        let follows = follow_edges(&lens, &profile_id).await;
+        save_follows(&make_http_client(), follows).await
+    });
+}
+
+/// Follows of `lens`, whose profile is `profile_id`, both ways.
+/// At most `MAX_FOLLOW_PAGES` pages each.
+/// Followed profiles are not fetched further, and failures only lose the follows.
+async fn follow_edges(lens: &Identity, profile_id: &ProfileId) -> EdgeList {
+    let mut edges = EdgeList::new();
+    for followers in [false, true] {
+        let profiles = query_follows(profile_id, followers).await;
+        for other in profiles.iter().filter_map(follow_identity) {
+            let follow = Follow {
+                source: DataSource::Lens,
+                ..Default::default()
+            };
+            let wrapper = match followers {
+                true => follow.wrapper(&other, lens, FOLLOW),
+                false => follow.wrapper(lens, &other, FOLLOW),
+            };
+            edges.push(EdgeWrapperEnum::new_follow(wrapper));
+        }
+    }
+    edges
+}
+
+/// Profiles following `profile_id` (`followers: true`), or followed by it.
+async fn query_follows(profile_id: &ProfileId, followers: bool) -> Vec<Profile> {
+    let mut profiles: Vec<Profile> = Vec::new();
+    let mut cursor: Option<Cursor> = None;
+    for _ in 0..MAX_FOLLOW_PAGES {
+        let vars = FollowRequestVariables {
+            profile_id: profile_id.clone(),
+            limit: LimitType::Fifty,
+            cursor: cursor.clone(),
+        };
+        let page = match followers {
+            true => surf::post(C.upstream.lens_api.url.clone())
+                .run_graphql(FollowersQuery::build(vars))
+                .await
+                .map(|response| response.data.map(|data| data.followers)),
+            false => surf::post(C.upstream.lens_api.url.clone())
+                .run_graphql(FollowingQuery::build(vars))
+                .await
+                .map(|response| response.data.map(|data| data.following)),
+        };
+        let page = match page {
+            Ok(Some(page)) => page,
+            Ok(None) => break,
+            Err(err) => {
+                warn!("LensV2 {:?} | Failed to fetch follows: {}", profile_id, err);
+                break;
+            }
+        };
+        profiles.extend(page.items);
+        cursor = page.page_info.next;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    profiles
+}
+
+/// Identity of a followed / following profile. `None` if it has no handle.
+fn follow_identity(profile: &Profile) -> Option<Identity> {
+    let handle_info = profile.handle.as_ref()?;
+    Some(Identity {
+        uuid: Some(Uuid::new_v4()),
+        platform: Platform::Lens,
+        identity: format!("{}.{}", handle_info.local_name, handle_info.namespace),
+        uid: Some(profile.id.0.to_string()),
+        created_at: utc_to_naive(profile.created_at.0.clone()).ok(),
+        display_name: profile
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.display_name.clone()),
+        added_at: naive_now(),
+        avatar_url: None,
+        profile_url: Some("https://hey.xyz/u/".to_owned() + &handle_info.local_name),
+        updated_at: naive_now(),
+        expired_at: None,
+        reverse: Some(false),
+    })
+}
+
 async fn fetch_by_lens_handle(target: &Target) -> Result<TargetProcessedList, Error> {
     let target_var = target.identity()?;
     let handle_name = target_var.trim_end_matches(".lens");
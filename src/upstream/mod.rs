@@ -1,20 +1,29 @@
 // Upstreams
 mod aggregation;
+mod basenames;
 mod clusters;
 mod crossbell;
+mod detect;
 mod dotbit;
+mod encoding;
+mod ens;
 mod ens_reverse;
+mod ens_rpc;
+mod ethereum;
 mod farcaster;
 mod genome;
 mod keybase;
 mod knn3;
 mod lensv2;
+mod normalize;
 mod proof_client;
 mod rss3;
 mod solana;
 mod space_id;
 mod sybil_list;
 mod unstoppable;
+mod validate;
+mod verify;
 // mod firefly;
 // mod opensea;
 
@@ -25,32 +34,84 @@ mod types;
 
 use crate::{
     error::Error,
+    metrics::observe_fetch,
     tigergraph::{batch_upsert, EdgeList},
     upstream::{
-        clusters::Clusters, crossbell::Crossbell, dotbit::DotBit, ens_reverse::ENSReverseLookup,
-        farcaster::Farcaster, genome::Genome, keybase::Keybase, knn3::Knn3, lensv2::LensV2,
-        proof_client::ProofClient, rss3::Rss3, solana::Solana, space_id::SpaceId,
-        sybil_list::SybilList, the_graph::TheGraph, unstoppable::UnstoppableDomains,
+        basenames::Basenames, clusters::Clusters, crossbell::Crossbell, dotbit::DotBit,
+        ens_reverse::ENSReverseLookup, ens_rpc::ENSRpc, farcaster::Farcaster, genome::Genome,
+        keybase::Keybase, knn3::Knn3, lensv2::LensV2, proof_client::ProofClient, rss3::Rss3,
+        solana::Solana, space_id::SpaceId, sybil_list::SybilList, the_graph::TheGraph,
+        unstoppable::UnstoppableDomains,
+    },
+    util::{
+        background::{is_short_lived, spawn_background},
+        hashset_append, make_http_client,
     },
-    util::{hashset_append, make_http_client},
 };
 use async_trait::async_trait;
 use futures::{future::join_all, StreamExt};
-use std::{collections::HashSet, sync::Arc};
+use serde::Serialize;
+use std::{collections::HashSet, future::Future, sync::Arc};
 use tokio::sync::Mutex;
-use tracing::{event, info, warn, Level};
+use tracing::{event, info, info_span, warn, Instrument, Level};
 
+pub(crate) use detect::{detect, resolve_input, Detected};
+pub(crate) use ethereum::{EthereumRpc, RpcTransaction};
+pub(crate) use normalize::{handle_suffix, normalize, normalize_address, normalize_name};
 pub(crate) use types::vec_string_to_vec_datasource;
 pub(crate) use types::{
-    Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem, Platform, ProofLevel,
-    Target, TargetProcessedList,
+    Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem, ExpiryStatus, Platform,
+    ProofLevel, Target, TargetProcessedList,
 };
+pub(crate) use validate::{validate, validate_address};
 
 lazy_static! {
     /// Global processing queue to prevent duplicated query. i.e. multiple same request from frontend.
     pub static ref FETCHING: Arc<Mutex<HashSet<Target>>> = Arc::new(Mutex::new(HashSet::new()));
 }
 
+tokio::task_local! {
+    /// Upstream failures of the request being served, see `track_failures`.
+    static UPSTREAM_FAILURES: Arc<std::sync::Mutex<Vec<UpstreamFailure>>>;
+}
+
+/// An upstream which failed while fetching for a request.
+/// Results of such request may be partial.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamFailure {
+    pub data_source: DataSource,
+    /// `Error::code` of the failure.
+    pub code: &'static str,
+}
+
+/// Run `fut`, collecting upstreams failed in it.
+/// Fetches continued in the background (see `fetch_all`) are not tracked.
+pub async fn track_failures<F: Future>(fut: F) -> (F::Output, Vec<UpstreamFailure>) {
+    let failures = Arc::new(std::sync::Mutex::new(vec![]));
+    let output = UPSTREAM_FAILURES.scope(failures.clone(), fut).await;
+    let failures = failures.lock().unwrap().clone();
+    (output, failures)
+}
+
+fn record_failure(data_source: DataSource, err: &Error) {
+    // Nothing found is an answer, results are complete.
+    if matches!(err, Error::NoResult) {
+        return;
+    }
+    // Not in `track_failures`, e.g. a background fetch.
+    let _ = UPSTREAM_FAILURES.try_with(|failures| {
+        let failure = UpstreamFailure {
+            data_source,
+            code: err.code(),
+        };
+        let mut failures = failures.lock().unwrap();
+        if !failures.contains(&failure) {
+            failures.push(failure);
+        }
+    });
+}
+
 /// Fetcher defines how to fetch data from upstream.
 #[async_trait]
 pub trait Fetcher {
@@ -66,11 +127,12 @@ pub trait Fetcher {
 
 /// Find all available (platform, identity) in all `Upstream`s.
 /// `depth` controls how many fetch layers should `fetch_all` blocks.
-/// The rest `up_next` will be fetched asynchronously.  `None` means
-/// fetch till exhausted.
+/// The rest `up_next` will be fetched asynchronously, unless in a short-lived process.
+/// `None` means fetch till exhausted.
 // #[tracing::instrument(name = "fetch_all", level = "trace")]
 #[async_recursion::async_recursion]
 pub async fn fetch_all(targets: TargetProcessedList, depth: Option<u16>) -> Result<(), Error> {
+    let targets: TargetProcessedList = targets.into_iter().map(Target::normalized).collect();
     let mut round: u16 = 0;
     let mut up_next: HashSet<Target> = HashSet::new();
     let mut all_edges: EdgeList = EdgeList::new();
@@ -107,8 +169,12 @@ pub async fn fetch_all(targets: TargetProcessedList, depth: Option<u16>) -> Resu
         all_edges.extend(edges);
 
         if depth.is_some() && depth.unwrap() <= round {
-            // Fork as background job to continue fetching.
-            tokio::spawn(fetch_all(up_next.into_iter().collect(), None));
+            // Fork as background job to continue fetching. Not in a short-lived process:
+            // frozen there, it would hold its targets in `FETCHING`, so they could not be
+            // fetched when queried. They are left to be fetched then.
+            if !is_short_lived() {
+                spawn_background(fetch_all(up_next.into_iter().collect(), None));
+            }
             break;
         }
     }
@@ -120,6 +186,7 @@ pub async fn fetch_all(targets: TargetProcessedList, depth: Option<u16>) -> Resu
     drop(fetching);
 
     // Upsert all edges after fetching completes
+    let all_edges = verify::verify_reverse(all_edges).await;
     if !all_edges.is_empty() {
         let cli = make_http_client();
         batch_upsert(&cli, all_edges).await?;
@@ -296,6 +363,8 @@ pub async fn fetch_one(target: &Target) -> Result<Vec<Target>, Error> {
         }
     })
     .collect();
+    // Upstreams don't agree on casing, e.g. checksummed or lowercase EVM addresses.
+    up_next = up_next.into_iter().map(Target::normalized).collect();
     up_next.dedup();
     // Filter zero address
     up_next = up_next
@@ -313,6 +382,23 @@ pub async fn fetch_one(target: &Target) -> Result<Vec<Target>, Error> {
     Ok(up_next)
 }
 
+/// `batch_fetch` of one upstream, traced and timed by `source`.
+async fn batch_fetch_from<F>(
+    source: DataSource,
+    target: &Target,
+    fetch: F,
+) -> Result<(TargetProcessedList, EdgeList), Error>
+where
+    F: Future<Output = Result<(TargetProcessedList, EdgeList), Error>>,
+{
+    let span = info_span!("batch_fetch", data_source = %source, %target);
+    let result = observe_fetch(source, fetch).instrument(span).await;
+    if let Err(err) = &result {
+        record_failure(source, err);
+    }
+    result
+}
+
 pub async fn batch_fetch_upstream(
     target: &Target,
 ) -> Result<(TargetProcessedList, EdgeList), Error> {
@@ -320,20 +406,43 @@ pub async fn batch_fetch_upstream(
     let mut all_edges = EdgeList::new();
 
     let _ = join_all(vec![
-        TheGraph::batch_fetch(target),
-        ENSReverseLookup::batch_fetch(target),
-        Farcaster::batch_fetch(target),
-        LensV2::batch_fetch(target),
-        ProofClient::batch_fetch(target),
-        Keybase::batch_fetch(target),
-        Rss3::batch_fetch(target),
-        DotBit::batch_fetch(target),
-        UnstoppableDomains::batch_fetch(target),
-        SpaceId::batch_fetch(target),
-        Genome::batch_fetch(target),
-        Crossbell::batch_fetch(target),
-        Solana::batch_fetch(target),
-        Clusters::batch_fetch(target),
+        batch_fetch_from(DataSource::TheGraph, target, TheGraph::batch_fetch(target)),
+        batch_fetch_from(
+            DataSource::ENS,
+            target,
+            ENSReverseLookup::batch_fetch(target),
+        ),
+        batch_fetch_from(DataSource::RPCServer, target, ENSRpc::batch_fetch(target)),
+        batch_fetch_from(
+            DataSource::Farcaster,
+            target,
+            Farcaster::batch_fetch(target),
+        ),
+        batch_fetch_from(DataSource::Lens, target, LensV2::batch_fetch(target)),
+        batch_fetch_from(DataSource::NextID, target, ProofClient::batch_fetch(target)),
+        batch_fetch_from(DataSource::Keybase, target, Keybase::batch_fetch(target)),
+        batch_fetch_from(DataSource::Rss3, target, Rss3::batch_fetch(target)),
+        batch_fetch_from(DataSource::Dotbit, target, DotBit::batch_fetch(target)),
+        batch_fetch_from(
+            DataSource::UnstoppableDomains,
+            target,
+            UnstoppableDomains::batch_fetch(target),
+        ),
+        batch_fetch_from(DataSource::SpaceId, target, SpaceId::batch_fetch(target)),
+        // Genome is .gno of Space ID, its edges are saved with `space_id` as source.
+        batch_fetch_from(DataSource::Genome, target, Genome::batch_fetch(target)),
+        batch_fetch_from(
+            DataSource::Crossbell,
+            target,
+            Crossbell::batch_fetch(target),
+        ),
+        batch_fetch_from(DataSource::Solana, target, Solana::batch_fetch(target)),
+        batch_fetch_from(DataSource::Clusters, target, Clusters::batch_fetch(target)),
+        batch_fetch_from(
+            DataSource::Basenames,
+            target,
+            Basenames::batch_fetch(target),
+        ),
         // SybilList::batch_fetch(target), // move this logic to `data_process` as a scheduled asynchronous fetch
         // Knn3::batch_fetch(target), // Temporarily cancel
         // Firefly::batch_fetch(target), // Temporarily cancel
@@ -354,6 +463,8 @@ pub async fn batch_fetch_upstream(
         }
     });
 
+    // Upstreams don't agree on casing, e.g. checksummed or lowercase EVM addresses.
+    up_next = up_next.into_iter().map(Target::normalized).collect();
     up_next.dedup();
     // Filter zero address
     up_next = up_next
@@ -0,0 +1,123 @@
+//! Canonical form of identities, so that one identity always lands in the same
+//! vertex, no matter which request or upstream it comes from.
+
+#[cfg(test)]
+mod tests;
+
+use crate::upstream::{ens, Chain, DomainNameSystem, Platform};
+
+/// Suffix of handles which are stored as domains, e.g. `stani` is stored as `stani.lens`.
+const HANDLE_SUFFIXES: &[(Platform, &str)] = &[
+    (Platform::Lens, ".lens"),
+    (Platform::Crossbell, ".csb"),
+    (Platform::SNS, ".sol"),
+    (Platform::Genome, ".gno"),
+];
+
+/// Suffix which handles on `platform` are stored with, if any.
+pub fn handle_suffix(platform: Platform) -> Option<&'static str> {
+    HANDLE_SUFFIXES
+        .iter()
+        .find(|(handle_platform, _)| *handle_platform == platform)
+        .map(|(_, suffix)| *suffix)
+}
+
+/// Canonical form of `raw` identity on `platform`.
+pub fn normalize(platform: Platform, raw: &str) -> String {
+    let raw = raw.trim();
+    match platform {
+        // Hex addresses and public keys, case-insensitive.
+        Platform::Ethereum
+        | Platform::BNBSmartChain
+        | Platform::Polygon
+        | Platform::NextID
+        | Platform::CKB => raw.to_lowercase(),
+        Platform::Aptos => normalize_aptos(raw),
+        // bech32 is case-insensitive, base58 is not.
+        Platform::Bitcoin if is_bech32_btc(raw) => raw.to_lowercase(),
+        Platform::Cosmos => raw.to_lowercase(),
+        // c32check is uppercase.
+        Platform::Stacks => raw.to_uppercase(),
+        // Both named and implicit (hex) accounts are lowercase.
+        Platform::Near => raw.to_lowercase(),
+        // Case-insensitive usernames, sometimes given as `@handle`.
+        Platform::Twitter
+        | Platform::Farcaster
+        | Platform::Github
+        | Platform::Keybase
+        | Platform::Reddit
+        | Platform::Telegram
+        | Platform::MstdnJP => raw.trim_start_matches('@').to_lowercase(),
+        Platform::Email => raw.to_lowercase(),
+        Platform::Lens | Platform::Crossbell | Platform::SNS | Platform::Genome => {
+            let domain = normalize_domain(raw.trim_start_matches('@'));
+            match handle_suffix(platform) {
+                Some(suffix) if !domain.is_empty() && !domain.ends_with(suffix) => {
+                    format!("{}{}", domain, suffix)
+                }
+                _ => domain,
+            }
+        }
+        // Invalid names are kept as given, so that `validate` rejects them.
+        Platform::ENS | Platform::Basenames => {
+            let name = raw.trim_end_matches('.');
+            ens::normalize(name).unwrap_or_else(|_| name.to_string())
+        }
+        Platform::Dotbit
+        | Platform::DNS
+        | Platform::UnstoppableDomains
+        | Platform::SpaceId
+        | Platform::Clusters => normalize_domain(raw),
+        // Base58 / base64 addresses (Solana, Tron, Doge, Ton, legacy Bitcoin)
+        // and case-sensitive usernames.
+        _ => raw.to_string(),
+    }
+}
+
+/// Canonical form of a contract `address` on `chain`.
+pub fn normalize_address(chain: Chain, address: &str) -> String {
+    let address = address.trim();
+    match chain {
+        Chain::Solana | Chain::Tron | Chain::Ton | Chain::Doge | Chain::Arweave => {
+            address.to_string()
+        }
+        Chain::Bitcoin => normalize(Platform::Bitcoin, address),
+        Chain::Stacks => normalize(Platform::Stacks, address),
+        Chain::Aptos => normalize_aptos(address),
+        Chain::Unknown => address.to_string(),
+        // EVM-compatible, Cosmos, Near, CKB...
+        _ => address.to_lowercase(),
+    }
+}
+
+/// Canonical form of a domain `name` in `system`, as stored in `Resolve.name`.
+/// Same as the identity of the domain itself, so both can be found by one form.
+pub fn normalize_name(system: DomainNameSystem, name: &str) -> String {
+    match Platform::from(system) {
+        Platform::Unknown => normalize_domain(name),
+        platform => normalize(platform, name),
+    }
+}
+
+/// Canonical form of a domain name: lowercase, without trailing dot.
+/// Suffixes of handles are added by `normalize`, not here.
+pub fn normalize_domain(raw: &str) -> String {
+    raw.trim().trim_end_matches('.').to_lowercase()
+}
+
+fn is_bech32_btc(address: &str) -> bool {
+    let lowercased = address.to_lowercase();
+    lowercased.starts_with("bc1") || lowercased.starts_with("tb1")
+}
+
+/// Aptos addresses are hex, `0x`-prefixed.
+fn normalize_aptos(raw: &str) -> String {
+    let lowercased = raw.to_lowercase();
+    match lowercased.starts_with("0x") {
+        true => lowercased,
+        false if !lowercased.is_empty() && lowercased.chars().all(|c| c.is_ascii_hexdigit()) => {
+            format!("0x{}", lowercased)
+        }
+        false => lowercased,
+    }
+}
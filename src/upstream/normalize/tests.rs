@@ -0,0 +1,129 @@
+use super::*;
+use crate::upstream::validate;
+
+#[test]
+fn test_normalize_addresses() {
+    assert_eq!(
+        normalize(
+            Platform::Ethereum,
+            " 0xD8dA6BF26964aF9D7eEd9e03E53415D37aA96045 "
+        ),
+        "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
+    );
+    assert_eq!(
+        normalize(
+            Platform::Bitcoin,
+            "BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ"
+        ),
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+    );
+    // Base58 is case-sensitive.
+    assert_eq!(
+        normalize(Platform::Bitcoin, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"),
+        "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"
+    );
+    assert_eq!(
+        normalize(
+            Platform::Solana,
+            "HKKp49qGWXd639QsuH7JiLijfVW5UtCVY4s1n2HANwEA"
+        ),
+        "HKKp49qGWXd639QsuH7JiLijfVW5UtCVY4s1n2HANwEA"
+    );
+    assert_eq!(
+        normalize(Platform::Tron, "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7"),
+        "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7"
+    );
+    assert_eq!(normalize(Platform::Aptos, "ABCDEF01"), "0xabcdef01");
+    assert_eq!(normalize(Platform::Near, "Alice.Near"), "alice.near");
+    assert_eq!(
+        normalize(
+            Platform::Cosmos,
+            "COSMOS1FL48VSNMSDZCV85Q5D2Q4Z5AJDHA8YU34MF0EH"
+        ),
+        "cosmos1fl48vsnmsdzcv85q5d2q4z5ajdha8yu34mf0eh"
+    );
+    assert_eq!(
+        normalize(
+            Platform::Stacks,
+            "sp2j6zy48gv1ez5v2v5rb9mp66sw86pykknrv9ej7"
+        ),
+        "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"
+    );
+}
+
+#[test]
+fn test_normalize_handles() {
+    assert_eq!(normalize(Platform::Twitter, "@Suji_Yan"), "suji_yan");
+    assert_eq!(normalize(Platform::Telegram, "@Durov"), "durov");
+    assert_eq!(normalize(Platform::Farcaster, "Dwr.eth"), "dwr.eth");
+    assert_eq!(normalize(Platform::Crossbell, "Song"), "song.csb");
+    assert_eq!(normalize(Platform::Crossbell, "song.csb"), "song.csb");
+    assert_eq!(normalize(Platform::Lens, "stani"), "stani.lens");
+    assert_eq!(normalize(Platform::SNS, "Bonfida.sol"), "bonfida.sol");
+    assert_eq!(normalize(Platform::Lens, ""), "");
+}
+
+#[test]
+fn test_normalize_domains() {
+    assert_eq!(normalize(Platform::ENS, "Vitalik.ETH."), "vitalik.eth");
+    assert_eq!(normalize(Platform::ENS, "ｎｉｃｋ.eth"), "nick.eth");
+    assert_eq!(
+        normalize(Platform::Basenames, "Jesse.Base.ETH"),
+        "jesse.base.eth"
+    );
+    assert_eq!(
+        normalize(Platform::Dotbit, " Threebody.bit"),
+        "threebody.bit"
+    );
+    assert_eq!(normalize_domain("Brad.Crypto"), "brad.crypto");
+}
+
+#[test]
+fn test_normalize_invalid_ens_names() {
+    // Kept as given, and rejected by `validate` instead of passing as a domain.
+    assert_eq!(normalize(Platform::ENS, "vitalik..eth"), "vitalik..eth");
+    assert_eq!(normalize(Platform::ENS, "Xn--Abc.eth"), "Xn--Abc.eth");
+    assert!(validate(Platform::ENS, &normalize(Platform::ENS, "Xn--Abc.eth")).is_err());
+    assert!(validate(Platform::ENS, &normalize(Platform::ENS, "Vitalik.eth")).is_ok());
+}
+
+#[test]
+fn test_normalize_address() {
+    assert_eq!(
+        normalize_address(
+            Chain::Ethereum,
+            "0x57f1887a8BF19b14fC0dF6Fd9B2acc9Af147eA85"
+        ),
+        "0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85"
+    );
+    assert_eq!(
+        normalize_address(
+            Chain::Solana,
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+        ),
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+    );
+}
+
+#[test]
+fn test_normalize_name_as_identity() {
+    // `find_domain` and stored `Resolve.name` agree with the domain identity.
+    assert_eq!(
+        normalize_name(DomainNameSystem::Lens, "Stani"),
+        "stani.lens"
+    );
+    assert_eq!(
+        normalize_name(DomainNameSystem::Lens, "stani.lens"),
+        normalize(Platform::Lens, "stani")
+    );
+    assert_eq!(
+        normalize_name(DomainNameSystem::SNS, "Bonfida."),
+        "bonfida.sol"
+    );
+    assert_eq!(
+        normalize_name(DomainNameSystem::DotBit, "Jeffx.BIT"),
+        "jeffx.bit"
+    );
+    assert_eq!(handle_suffix(Platform::Crossbell), Some(".csb"));
+    assert_eq!(handle_suffix(Platform::Dotbit), None);
+}
@@ -74,6 +74,18 @@ impl Fetcher for Solana {
     }
 }
 
+impl Solana {
+    /// Owner SNS `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let rpc_client = get_rpc_client(C.upstream.solana_rpc.rpc_url.clone());
+        let owner = fetch_resolve_address(&rpc_client, &trim_domain(name.to_string())).await?;
+        Ok(owner
+            .map(|owner| (Platform::Solana, owner.to_string()))
+            .into_iter()
+            .collect())
+    }
+}
+
 async fn batch_fetch_by_wallet(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
     let owner: String = target.identity()?;
     let rpc_client = get_rpc_client(C.upstream.solana_rpc.rpc_url.clone());
@@ -73,6 +73,14 @@ impl Fetcher for SpaceId {
     }
 }
 
+impl SpaceId {
+    /// Address SpaceID `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let address = get_address(name).await?;
+        Ok(vec![(Platform::Ethereum, address.to_lowercase())])
+    }
+}
+
 async fn batch_fetch_by_wallet(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
     let identity = target.identity()?;
     let name = get_name(&identity).await?;
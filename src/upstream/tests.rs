@@ -1,6 +1,9 @@
 use crate::error::Error;
+use crate::tigergraph::EdgeList;
 use crate::upstream::{
-    batch_fetch_upstream, fetch_all, fetch_one, Chain, ContractCategory, Platform, Target,
+    basenames::Basenames, batch_fetch_from, batch_fetch_upstream, ens_rpc::ENSRpc, fetch_all,
+    fetch_one, track_failures, Chain, ContractCategory, DataSource, DomainNameSystem, Fetcher,
+    Platform, Target, TargetProcessedList, UpstreamFailure,
 };
 
 #[tokio::test]
@@ -123,3 +126,59 @@ async fn test_fetch_all_ens() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_track_failures() {
+    let target = Target::Identity(Platform::Twitter, "suji_yan".into());
+    let fetch = |err: Option<Error>| async move {
+        match err {
+            Some(err) => Err(err),
+            None => Ok((vec![], vec![])),
+        }
+    };
+    let upstream_error = || Some(Error::ManualHttpClientError("Lens is down".into()));
+    let ((), failures) = track_failures(async {
+        let _ = batch_fetch_from(DataSource::Dotbit, &target, fetch(None)).await;
+        let _ = batch_fetch_from(DataSource::Lens, &target, fetch(upstream_error())).await;
+        let _ = batch_fetch_from(DataSource::Lens, &target, fetch(upstream_error())).await;
+    })
+    .await;
+    assert_eq!(
+        failures,
+        vec![UpstreamFailure {
+            data_source: DataSource::Lens,
+            code: "UPSTREAM_ERROR",
+        }]
+    );
+
+    // Untracked failures are ignored.
+    let result = batch_fetch_from(DataSource::Lens, &target, fetch(upstream_error())).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_no_result_is_not_a_failure() {
+    let target = Target::Identity(Platform::Twitter, "suji_yan".into());
+    let ((), failures) = track_failures(async {
+        let result = batch_fetch_from(DataSource::Lens, &target, async {
+            Err::<(TargetProcessedList, EdgeList), _>(Error::NoResult)
+        })
+        .await;
+        assert!(matches!(result, Err(Error::NoResult)));
+    })
+    .await;
+    assert!(failures.is_empty());
+}
+
+#[test]
+fn test_basenames_domain_target() {
+    let target = Target::from_domain(DomainNameSystem::Basenames, "jesse.base.eth").unwrap();
+    assert_eq!(
+        target,
+        Target::Identity(Platform::Basenames, "jesse.base.eth".into())
+    );
+    assert!(Basenames::can_fetch(&target));
+    assert!(!ENSRpc::can_fetch(&target));
+
+    assert!(Target::from_domain(DomainNameSystem::Genome, "alice.gno").is_none());
+}
@@ -14,8 +14,8 @@ use crate::tigergraph::upsert::{create_ens_identity_ownership, create_ens_identi
 use crate::tigergraph::vertex::{Contract, IdentitiesGraph, Identity};
 use crate::tigergraph::{EdgeList, EdgeWrapperEnum};
 use crate::upstream::{
-    Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem, Fetcher, Platform, Target,
-    TargetProcessedList,
+    ens, ens_rpc::ENSRpc, Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem,
+    Fetcher, Platform, Target, TargetProcessedList,
 };
 use crate::util::{make_http_client, naive_now, parse_timestamp};
 use async_trait::async_trait;
@@ -115,9 +115,11 @@ fn choose_endpoint() -> String {
     }
 }
 
+/// `$target` is the namehash of ENS name, which is `id` of `Domain` and `WrappedDomain`.
+/// Matching by `name` misses names not exactly in their normalized form.
 const QUERY_BY_ENS: &str = r#"
-        query OwnerAddressByENS($target: String!){
-            domains(where: { name: $target }) {
+        query OwnerAddressByENS($target: ID!){
+            domains(where: { id: $target }) {
                 name
                 createdAt
                 registration {
@@ -134,7 +136,7 @@ const QUERY_BY_ENS: &str = r#"
                   id
                 }
               }
-            wrappedDomains(where: { name: $target }) {
+            wrappedDomains(where: { id: $target }) {
               name
               domain {
                 name
@@ -226,8 +228,13 @@ impl Fetcher for TheGraph {
     }
 
     fn can_fetch(target: &Target) -> bool {
+        // Records of a name are read from contracts by `ENSRpc` if enabled,
+        // Basenames on Base by `Basenames` only.
+        let is_basename = matches!(target, Target::NFT(_, _, _, name) if ens::is_basename(name));
         target.in_platform_supported(vec![Platform::Ethereum])
-            || target.in_nft_supported(vec![ContractCategory::ENS], vec![Chain::Ethereum])
+            || (target.in_nft_supported(vec![ContractCategory::ENS], vec![Chain::Ethereum])
+                && !is_basename
+                && !ENSRpc::enabled())
     }
 }
 
@@ -245,35 +252,22 @@ async fn fetch_domains(target: &Target) -> Result<Vec<Domain>, Error> {
         }
         Target::NFT(_chain, _category, _contract_addr, ens_name) => {
             query = QUERY_BY_ENS.to_string();
-            target_var = ens_name.clone();
+            let ens_name = ens::normalize(ens_name)?;
+            target_var = ens::to_hex(&ens::namehash(&ens_name));
         }
     }
-    let endpoints = choose_endpoint();
-    let client = GQLClient::new(&endpoints);
-    let vars = QueryVars { target: target_var };
-
-    let resp = client.query_with_vars::<QueryResponse, QueryVars>(&query, vars);
-
-    let data: Option<QueryResponse> =
-        match tokio::time::timeout(std::time::Duration::from_secs(5), resp).await {
-            Ok(resp) => match resp {
-                Ok(resp) => resp,
-                Err(err) => {
-                    warn!(?target, ?err, "TheGraph: Failed to fetch");
-                    None
-                }
-            },
-            Err(_) => {
-                warn!(?target, "TheGraph: Timeout: no response in 5 seconds.");
-                None
-            }
-        };
-
-    if data.is_none() {
+    let res = match query_domains(&query, target_var).await {
+        Ok(res) => res,
+        Err(err) => {
+            warn!(?target, %err, "TheGraph: Failed to fetch");
+            None
+        }
+    };
+    if res.is_none() {
         info!(?target, "TheGraph: No result");
         return Ok(vec![]);
     }
-    let res = data.unwrap();
+    let res = res.unwrap();
     debug!(
         ?target,
         wrapped = res.wrapped_domains.len(),
@@ -303,6 +297,41 @@ async fn fetch_domains(target: &Target) -> Result<Vec<Domain>, Error> {
     Ok(merged_domains)
 }
 
+/// Run `query` with `target` on a subgraph. `None` if there is no result.
+async fn query_domains(query: &str, target: String) -> Result<Option<QueryResponse>, Error> {
+    let endpoints = choose_endpoint();
+    let client = GQLClient::new(&endpoints);
+    let vars = QueryVars { target };
+    let resp = client.query_with_vars::<QueryResponse, QueryVars>(query, vars);
+    match tokio::time::timeout(std::time::Duration::from_secs(5), resp).await {
+        Ok(resp) => resp.map_err(|err| Error::GraphQLError(format!("TheGraph: {:?}", err))),
+        Err(_) => Err(Error::GraphQLError(
+            "TheGraph: Timeout: no response in 5 seconds.".to_string(),
+        )),
+    }
+}
+
+impl TheGraph {
+    /// Address ENS `name` resolves to, without fetching anything else.
+    /// Empty if it is not registered, or resolves nowhere.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let name = ens::normalize(name)?;
+        let target = ens::to_hex(&ens::namehash(&name));
+        let Some(res) = query_domains(QUERY_BY_ENS, target).await? else {
+            return Ok(vec![]);
+        };
+        Ok(res
+            .wrapped_domains
+            .into_iter()
+            .map(|wrapped| wrapped.domain)
+            .chain(res.domains)
+            .find_map(|domain| domain.resolved_address)
+            .map(|account| (Platform::Ethereum, account.id.to_lowercase()))
+            .into_iter()
+            .collect())
+    }
+}
+
 async fn batch_perform_fetch(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
     let merged_domains = fetch_domains(target).await?;
     if merged_domains.is_empty() {
@@ -1,6 +1,42 @@
+use crate::upstream::Platform;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString};
 
+/// [ENSIP-11](https://docs.ens.domains/ensip/11) coin type of an EVM chain is
+/// `EVM_COIN_TYPE | chain_id`.
+const EVM_COIN_TYPE: u32 = 0x80000000;
+
+/// Coin types of addresses on chains, and the platform those addresses are identities on.
+/// Non-EVM chains and Ethereum use SLIP-44 coin types,
+/// as [ENSIP-9](https://docs.ens.domains/ensip/9) does: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+const COIN_TYPES: [(u32, Chain, Platform); 21] = [
+    (0, Chain::Bitcoin, Platform::Bitcoin),
+    (3, Chain::Doge, Platform::Doge),
+    (60, Chain::Ethereum, Platform::Ethereum),
+    (61, Chain::EthereumClassic, Platform::Ethereum),
+    (118, Chain::Cosmos, Platform::Cosmos),
+    (195, Chain::Tron, Platform::Tron),
+    (309, Chain::CKB, Platform::CKB),
+    (397, Chain::Near, Platform::Near),
+    (501, Chain::Solana, Platform::Solana),
+    (607, Chain::Ton, Platform::Ton),
+    (637, Chain::Aptos, Platform::Aptos),
+    (5757, Chain::Stacks, Platform::Stacks),
+    (EVM_COIN_TYPE | 10, Chain::Optimism, Platform::Ethereum),
+    (
+        EVM_COIN_TYPE | 56,
+        Chain::BNBSmartChain,
+        Platform::BNBSmartChain,
+    ),
+    (EVM_COIN_TYPE | 100, Chain::Gnosis, Platform::Ethereum),
+    (EVM_COIN_TYPE | 137, Chain::Polygon, Platform::Polygon),
+    (EVM_COIN_TYPE | 250, Chain::Fantom, Platform::Ethereum),
+    (EVM_COIN_TYPE | 8453, Chain::Base, Platform::Ethereum),
+    (EVM_COIN_TYPE | 42161, Chain::Arbitrum, Platform::Ethereum),
+    (EVM_COIN_TYPE | 42220, Chain::Celo, Platform::Ethereum),
+    (EVM_COIN_TYPE | 43114, Chain::Avalanche, Platform::Ethereum),
+];
+
 /// List of chains supported by RelationService.
 #[derive(
     Default,
@@ -251,7 +287,7 @@ pub enum Chain {
 }
 
 /// Internal chain implementation / framework.
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum ChainType {
     /// EVM (with its chain ID)
     EVM(u128),
@@ -262,10 +298,23 @@ pub enum ChainType {
     Arweave,
     /// Basiclly an EVM, but with different address serializer, transaction packaging and genesis contracts.
     Conflux,
-    /// Bitcoin
+    /// Bitcoin, and its UTXO forks (e.g. Doge)
     BTC,
     /// Cosmos
     Cosmos,
+    /// Nervos CKB (Cell model)
+    Ckb,
+    /// TON
+    Ton,
+    /// TRON: EVM-like (TVM), but with base58check addresses.
+    Tron,
+    /// Aptos (Move)
+    Aptos,
+    /// Near
+    Near,
+    /// Stacks: Bitcoin layer 2 with its own (c32check) addresses.
+    Stacks,
+    Unknown,
 }
 
 impl Default for ChainType {
@@ -290,7 +339,7 @@ impl Chain {
             BNBSmartChain => ChainType::EVM(56),
             Polygon => ChainType::EVM(137),
             Mumbai => ChainType::EVM(80001),
-            Solana => ChainType::EVM(900),
+            Solana => ChainType::Solana,
             Conflux => ChainType::Conflux,
             ConfluxESpace => ChainType::EVM(71),
             EthereumClassic => ChainType::EVM(61),
@@ -304,16 +353,38 @@ impl Chain {
             Avalanche => ChainType::EVM(43114),
             Fantom => ChainType::EVM(250),
             Celo => ChainType::EVM(42220),
-            CKB => ChainType::EVM(71402),
+            CKB => ChainType::Ckb,
             Base => ChainType::EVM(8453),
-            Ton => ChainType::EVM(1100),
-            Tron => ChainType::EVM(1000),
-            Doge => ChainType::EVM(2000),
-            Aptos => ChainType::EVM(1400),
-            Near => ChainType::EVM(1200),
-            Stacks => ChainType::BTC,
+            Ton => ChainType::Ton,
+            Tron => ChainType::Tron,
+            Doge => ChainType::BTC,
+            Aptos => ChainType::Aptos,
+            Near => ChainType::Near,
+            Stacks => ChainType::Stacks,
             Cosmos => ChainType::Cosmos,
-            Unknown => todo!(),
+            Unknown => ChainType::Unknown,
         }
     }
+
+    /// Coin type of addresses on this chain, as used in multi-coin address records of
+    /// name systems. `None` if not registered.
+    pub fn coin_type(&self) -> Option<u32> {
+        COIN_TYPES
+            .iter()
+            .find(|(_, chain, _)| chain == self)
+            .map(|(coin_type, _, _)| *coin_type)
+    }
+
+    /// Chain of `coin_type`, and the platform its addresses are identities on.
+    pub fn from_coin_type(coin_type: u32) -> Option<(Chain, Platform)> {
+        COIN_TYPES
+            .iter()
+            .find(|(registered, _, _)| *registered == coin_type)
+            .map(|(_, chain, platform)| (*chain, *platform))
+    }
+
+    /// All `(coin_type, chain, platform)` registered.
+    pub fn coin_types() -> impl Iterator<Item = (u32, Chain, Platform)> {
+        COIN_TYPES.into_iter()
+    }
 }
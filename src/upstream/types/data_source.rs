@@ -1,4 +1,5 @@
 use crate::error::Error;
+use crate::upstream::ProofLevel;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString};
 
@@ -83,6 +84,14 @@ pub enum DataSource {
     #[graphql(name = "ens")]
     ENS,
 
+    /// ENS <-> Twitter / Github / Telegram / Email
+    /// Text records (`com.twitter`, `com.github`...) set on an ENS name by its manager.
+    /// Self-asserted: nothing proves the other side agrees.
+    #[strum(serialize = "ens_text_record")]
+    #[serde(rename = "ens_text_record")]
+    #[graphql(name = "ens_text_record")]
+    ENSTextRecord,
+
     #[strum(serialize = "the_graph")]
     #[serde(rename = "the_graph")]
     #[graphql(name = "the_graph")]
@@ -123,6 +132,14 @@ pub enum DataSource {
     #[graphql(name = "space_id")]
     SpaceId,
 
+    /// Genome
+    /// `.gno` domains, resolved by the Space ID API.
+    /// Only labels fetches, edges keep `space_id` as their source.
+    #[strum(serialize = "genome")]
+    #[serde(rename = "genome")]
+    #[graphql(name = "genome")]
+    Genome,
+
     #[strum(serialize = "crossbell")]
     #[serde(rename = "crossbell")]
     #[graphql(name = "crossbell")]
@@ -134,6 +151,14 @@ pub enum DataSource {
     #[graphql(name = "clusters")]
     Clusters,
 
+    /// Basenames
+    /// https://www.base.org/names
+    /// Registry and resolvers of `*.base.eth` read on the Base chain.
+    #[strum(serialize = "basenames")]
+    #[serde(rename = "basenames")]
+    #[graphql(name = "basenames")]
+    Basenames,
+
     /// Solana
     #[strum(serialize = "solana")]
     #[serde(rename = "solana")]
@@ -203,6 +228,32 @@ pub enum DataSource {
     Unknown,
 }
 
+impl DataSource {
+    /// Connections claimed by one side only, with nothing proving the other side agrees.
+    /// They should be shown differently from verified proofs.
+    pub fn is_self_asserted(&self) -> bool {
+        matches!(self, DataSource::ENSTextRecord)
+    }
+
+    /// How much connections provided by this data source can be trusted,
+    /// unless configured in `[confidence.sources]`.
+    pub fn proof_level(&self) -> ProofLevel {
+        use DataSource::*;
+        match self {
+            // Crypto-verifiable, or read from chains directly.
+            NextID | Keybase | SybilList | RPCServer | Firefly => ProofLevel::VeryConfident,
+            // Records of name systems and social protocols, through their APIs.
+            ENS | TheGraph | Dotbit | UnstoppableDomains | Lens | Farcaster | SpaceId | Genome
+            | Crossbell | Clusters | Basenames | Solana | SNS => ProofLevel::Confident,
+            // Partially verifiable.
+            Rss3 | Knn3 | CyberConnect => ProofLevel::Neutral,
+            // Only trusting the upstream.
+            EthLeaderboard | OpenSea | TwitterHexagon | ManuallyAdded => ProofLevel::Cautious,
+            ENSTextRecord | PFP | Unknown => ProofLevel::Insecure,
+        }
+    }
+}
+
 pub fn vec_string_to_vec_datasource(vec_string: Vec<String>) -> Result<Vec<DataSource>, Error> {
     let datasource_result: Result<Vec<DataSource>, _> = vec_string
         .into_iter()
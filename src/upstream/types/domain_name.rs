@@ -1,4 +1,5 @@
-use crate::upstream::Platform;
+use crate::{config::C, upstream::Platform};
+use chrono::{Duration, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumString};
 
@@ -25,6 +26,13 @@ pub enum DomainNameSystem {
     #[graphql(name = "ens")]
     ENS,
 
+    /// Basenames: ENS names under `base.eth` on the Base chain.
+    /// https://www.base.org/names
+    #[strum(serialize = "basenames")]
+    #[serde(rename = "basenames")]
+    #[graphql(name = "basenames")]
+    Basenames,
+
     /// https://www.sns.id: Solana Name Service
     #[strum(serialize = "sns")]
     #[serde(rename = "sns")]
@@ -84,6 +92,7 @@ impl From<DomainNameSystem> for Platform {
     fn from(domain: DomainNameSystem) -> Self {
         match domain {
             DomainNameSystem::ENS => Platform::ENS,
+            DomainNameSystem::Basenames => Platform::Basenames,
             DomainNameSystem::SNS => Platform::SNS,
             DomainNameSystem::DotBit => Platform::Dotbit,
             DomainNameSystem::UnstoppableDomains => Platform::UnstoppableDomains,
@@ -96,3 +105,75 @@ impl From<DomainNameSystem> for Platform {
         }
     }
 }
+
+/// `GRACE_PERIOD` of the `.eth` BaseRegistrar.
+const ENS_GRACE_PERIOD_DAYS: i64 = 90;
+/// `GRACE_PERIOD` of the Basenames BaseRegistrar on Base.
+const BASENAMES_GRACE_PERIOD_DAYS: i64 = 90;
+/// `GRACE_PERIOD` of the SpaceID registrars (`.bnb`, `.arb`).
+const SPACE_ID_GRACE_PERIOD_DAYS: i64 = 90;
+/// `.gno` names are registered through SpaceID contracts.
+const GENOME_GRACE_PERIOD_DAYS: i64 = 90;
+/// Accounts on .bit enter a grace period after expiring, before being recycled.
+const DOTBIT_GRACE_PERIOD_DAYS: i64 = 90;
+
+/// Lifecycle of a domain name which expires.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, Display, PartialEq, Eq, async_graphql::Enum,
+)]
+pub enum ExpiryStatus {
+    /// Registered and not expired.
+    Active,
+    /// Expired, but still renewable by its owner only. Records still resolve on most systems.
+    GracePeriod,
+    /// Grace period ended: released, anyone can register it again.
+    Expired,
+}
+
+impl DomainNameSystem {
+    /// How long an expired name stays renewable by its owner only, as set by its registrar.
+    /// `None` if names on this system never expire.
+    pub fn default_grace_period(&self) -> Option<Duration> {
+        use DomainNameSystem::*;
+        let days = match self {
+            ENS => ENS_GRACE_PERIOD_DAYS,
+            Basenames => BASENAMES_GRACE_PERIOD_DAYS,
+            SpaceId => SPACE_ID_GRACE_PERIOD_DAYS,
+            Genome => GENOME_GRACE_PERIOD_DAYS,
+            DotBit => DOTBIT_GRACE_PERIOD_DAYS,
+            SNS | Lens | UnstoppableDomains | Crossbell | Clusters | Unknown => return None,
+        };
+        Duration::try_days(days)
+    }
+
+    /// Grace period configured in `[expiry.grace_periods]` (days), or `default_grace_period`.
+    /// `None` if names on this system never expire.
+    pub fn grace_period(&self) -> Option<Duration> {
+        let configured = C
+            .expiry
+            .as_ref()
+            .and_then(|expiry| expiry.grace_periods.get(&self.to_string()));
+        match configured {
+            Some(days) => Duration::try_days(*days),
+            None => self.default_grace_period(),
+        }
+    }
+
+    /// Status at `now` of a name expiring at `expired_at`.
+    /// `None` if it never expires, or its expiry is unknown.
+    pub fn expiry_status(
+        &self,
+        expired_at: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+    ) -> Option<ExpiryStatus> {
+        let expired_at = expired_at?;
+        let grace_period = self.grace_period()?;
+        if now < expired_at {
+            Some(ExpiryStatus::Active)
+        } else if now < expired_at + grace_period {
+            Some(ExpiryStatus::GracePeriod)
+        } else {
+            Some(ExpiryStatus::Expired)
+        }
+    }
+}
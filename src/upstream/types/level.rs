@@ -30,3 +30,10 @@ pub enum ProofLevel {
     /// High confidence
     VeryConfident = 5,
 }
+
+impl ProofLevel {
+    /// Confidence of this level from 0 to 1, `VeryConfident` being 1.
+    pub fn confidence(self) -> f64 {
+        self as i32 as f64 / ProofLevel::VeryConfident as i32 as f64
+    }
+}
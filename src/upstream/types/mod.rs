@@ -7,6 +7,8 @@ pub(crate) mod level;
 pub(crate) mod platform;
 
 pub(crate) mod target;
+#[cfg(test)]
+mod tests;
 
 use serde::{Deserialize, Serialize};
 
@@ -15,7 +17,7 @@ pub use contract_category::ContractCategory;
 pub use data_fetcher::DataFetcher;
 pub use data_source::vec_string_to_vec_datasource;
 pub use data_source::DataSource;
-pub use domain_name::DomainNameSystem;
+pub use domain_name::{DomainNameSystem, ExpiryStatus};
 pub use level::ProofLevel;
 pub use platform::Platform;
 pub use target::{Target, TargetProcessedList};
@@ -98,6 +98,18 @@ pub enum Platform {
     #[graphql(name = "hackernews")]
     HackerNews,
 
+    /// Telegram
+    #[strum(serialize = "telegram")]
+    #[serde(rename = "telegram")]
+    #[graphql(name = "telegram")]
+    Telegram,
+
+    /// Email address
+    #[strum(serialize = "email")]
+    #[serde(rename = "email")]
+    #[graphql(name = "email")]
+    Email,
+
     /// ENS: ENS domains provide a way for users to map human readable names to blockchain and non-blockchain resources.
     /// https://ens.domains/
     #[strum(serialize = "ens")]
@@ -105,6 +117,13 @@ pub enum Platform {
     #[graphql(name = "ens")]
     ENS,
 
+    /// Basenames: ENS names under `base.eth`, registered on Base by Coinbase.
+    /// https://www.base.org/names
+    #[strum(serialize = "basenames")]
+    #[serde(rename = "basenames")]
+    #[graphql(name = "basenames")]
+    Basenames,
+
     /// Solana Name Service: Create a human-readable identity by replacing decentralized addresses with a domain name.
     /// https://www.sns.id
     #[strum(serialize = "sns")]
@@ -284,6 +303,7 @@ impl From<Platform> for DomainNameSystem {
             Platform::SpaceId => DomainNameSystem::SpaceId,
             Platform::Crossbell => DomainNameSystem::SpaceId,
             Platform::ENS => DomainNameSystem::ENS,
+            Platform::Basenames => DomainNameSystem::Basenames,
             Platform::SNS => DomainNameSystem::SNS,
             Platform::Genome => DomainNameSystem::Genome,
             Platform::Clusters => DomainNameSystem::Clusters,
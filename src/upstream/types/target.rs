@@ -1,9 +1,11 @@
 use http::StatusCode;
 
 use crate::error::Error;
+use crate::upstream::normalize::{normalize, normalize_address};
 
 use super::chain::Chain;
 use super::contract_category::ContractCategory;
+use super::domain_name::DomainNameSystem;
 use super::platform::Platform;
 
 /// List when processing identities.
@@ -24,6 +26,52 @@ impl Default for Target {
     }
 }
 impl Target {
+    /// Build a `Target` from a `(platform, identity)` pair.
+    /// ENS names are fetched as `NFT`s of the ENS contract, everything else as `Identity`.
+    pub fn from_platform_identity(platform: Platform, identity: &str) -> Self {
+        let identity = normalize(platform, identity);
+        match platform {
+            Platform::ENS => Target::NFT(
+                Chain::Ethereum,
+                ContractCategory::ENS,
+                ContractCategory::ENS.default_contract_address().unwrap(),
+                identity,
+            ),
+            _ => Target::Identity(platform, identity),
+        }
+    }
+
+    /// Target to fetch a domain from, `None` if its name system can't be looked up by name.
+    pub fn from_domain(system: DomainNameSystem, name: &str) -> Option<Self> {
+        match system {
+            DomainNameSystem::ENS
+            | DomainNameSystem::DotBit
+            | DomainNameSystem::Lens
+            | DomainNameSystem::UnstoppableDomains
+            | DomainNameSystem::SpaceId
+            | DomainNameSystem::Basenames => {
+                Some(Self::from_platform_identity(system.into(), name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Same target, with identity / contract address in canonical form.
+    pub fn normalized(self) -> Self {
+        match self {
+            Target::Identity(platform, identity) => {
+                Target::Identity(platform, normalize(platform, &identity))
+            }
+            Target::NFT(chain, category, address, id) => {
+                let id = match category {
+                    ContractCategory::ENS => normalize(Platform::ENS, &id),
+                    _ => id,
+                };
+                Target::NFT(chain, category, normalize_address(chain, &address), id)
+            }
+        }
+    }
+
     /// Judge if this target is in supported platforms list given by upstream.
     pub fn in_platform_supported(&self, platforms: Vec<Platform>) -> bool {
         match self {
@@ -0,0 +1,97 @@
+use super::chain::ChainType;
+use super::*;
+use crate::util::naive_now;
+use chrono::Duration;
+
+#[test]
+fn test_coin_type() {
+    assert_eq!(Chain::Bitcoin.coin_type(), Some(0));
+    assert_eq!(Chain::Ethereum.coin_type(), Some(60));
+    assert_eq!(Chain::Base.coin_type(), Some(2147492101));
+    assert_eq!(Chain::Rinkeby.coin_type(), None);
+    assert_eq!(
+        Chain::from_coin_type(501),
+        Some((Chain::Solana, Platform::Solana))
+    );
+    assert_eq!(
+        Chain::from_coin_type(0x80000089),
+        Some((Chain::Polygon, Platform::Polygon))
+    );
+    assert_eq!(Chain::from_coin_type(12345), None);
+}
+
+#[test]
+fn test_coin_type_of_evm_chains() {
+    // ENSIP-11: `0x80000000 | chain_id`, except Ethereum (and Ethereum Classic) in SLIP-44.
+    for (coin_type, chain, _) in Chain::coin_types() {
+        if let ChainType::EVM(chain_id) = chain.chain_type() {
+            if coin_type & 0x80000000 != 0 {
+                assert_eq!((coin_type & 0x7fffffff) as u128, chain_id, "{}", chain);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_chain_type() {
+    assert_eq!(Chain::Solana.chain_type(), ChainType::Solana);
+    assert_eq!(Chain::Tron.chain_type(), ChainType::Tron);
+    assert_eq!(Chain::Near.chain_type(), ChainType::Near);
+    assert_eq!(Chain::Unknown.chain_type(), ChainType::Unknown);
+    assert_eq!(Chain::Base.chain_type(), ChainType::EVM(8453));
+}
+
+#[test]
+fn test_expiry_status() {
+    let now = naive_now();
+    let expiry = |system: DomainNameSystem, days: i64| {
+        system.expiry_status(Some(now + Duration::try_days(days).unwrap()), now)
+    };
+    assert_eq!(expiry(DomainNameSystem::ENS, 1), Some(ExpiryStatus::Active));
+    assert_eq!(
+        expiry(DomainNameSystem::ENS, -1),
+        Some(ExpiryStatus::GracePeriod)
+    );
+    assert_eq!(
+        expiry(DomainNameSystem::ENS, -89),
+        Some(ExpiryStatus::GracePeriod)
+    );
+    assert_eq!(
+        expiry(DomainNameSystem::ENS, -91),
+        Some(ExpiryStatus::Expired)
+    );
+    // Never expires, or expiry unknown
+    assert_eq!(expiry(DomainNameSystem::Lens, -91), None);
+    assert_eq!(DomainNameSystem::ENS.expiry_status(None, now), None);
+}
+
+#[test]
+fn test_default_grace_period() {
+    let days = |system: DomainNameSystem| {
+        system
+            .default_grace_period()
+            .map(|period| period.num_days())
+    };
+    assert_eq!(days(DomainNameSystem::ENS), Some(90));
+    assert_eq!(days(DomainNameSystem::Basenames), Some(90));
+    assert_eq!(days(DomainNameSystem::SpaceId), Some(90));
+    assert_eq!(days(DomainNameSystem::DotBit), Some(90));
+    assert_eq!(days(DomainNameSystem::SNS), None);
+    assert_eq!(days(DomainNameSystem::Lens), None);
+}
+
+#[test]
+fn test_proof_level_confidence() {
+    assert_eq!(ProofLevel::VeryConfident.confidence(), 1.0);
+    assert_eq!(ProofLevel::Insecure.confidence(), 0.2);
+    assert_eq!(DataSource::NextID.proof_level(), ProofLevel::VeryConfident);
+    assert_eq!(DataSource::TheGraph.proof_level(), ProofLevel::Confident);
+    assert_eq!(
+        DataSource::ENSTextRecord.proof_level(),
+        ProofLevel::Insecure
+    );
+    assert!(
+        DataSource::OpenSea.proof_level().confidence()
+            < DataSource::Farcaster.proof_level().confidence()
+    );
+}
@@ -142,6 +142,21 @@ impl Fetcher for UnstoppableDomains {
     }
 }
 
+impl UnstoppableDomains {
+    /// Owner `name` resolves to, without fetching anything else.
+    pub async fn resolve_forward(name: &str) -> Result<Vec<(Platform, String)>, Error> {
+        let result = fetch_owner_by_domain(name).await?;
+        Ok(result
+            .meta
+            .owner
+            .map(|owner| owner.to_lowercase())
+            .filter(|owner| owner != UNKNOWN_OWNER)
+            .map(|owner| (Platform::Ethereum, owner))
+            .into_iter()
+            .collect())
+    }
+}
+
 async fn batch_fetch_by_wallet(target: &Target) -> Result<(TargetProcessedList, EdgeList), Error> {
     let address = target.identity()?.to_lowercase();
 
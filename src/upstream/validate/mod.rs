@@ -0,0 +1,188 @@
+//! Reject malformed identities before they are sent to upstreams or saved into TigerGraph.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    error::Error,
+    upstream::{
+        encoding::{base58check, bech32_decode, BECH32M_CONST, BECH32_CONST},
+        ens, Chain, Platform,
+    },
+};
+use regex::Regex;
+
+lazy_static! {
+    static ref EVM_ADDRESS: Regex = Regex::new(r"^0x[0-9a-f]{40}$").unwrap();
+    static ref APTOS_ADDRESS: Regex = Regex::new(r"^0x[0-9a-f]{1,64}$").unwrap();
+    /// c32check alphabet has no `I`, `L`, `O` or `U`.
+    static ref STACKS_ADDRESS: Regex =
+        Regex::new(r"^S[PMTN][0-9A-HJKMNP-TV-Z]{28,41}$").unwrap();
+    static ref NEAR_IMPLICIT_ACCOUNT: Regex = Regex::new(r"^[0-9a-f]{64}$").unwrap();
+    static ref NEAR_NAMED_ACCOUNT: Regex =
+        Regex::new(r"^(([a-z\d]+[\-_])*[a-z\d]+\.)*([a-z\d]+[\-_])*[a-z\d]+$").unwrap();
+    static ref TWITTER_HANDLE: Regex = Regex::new(r"^[a-z0-9_]{1,15}$").unwrap();
+    static ref GITHUB_HANDLE: Regex = Regex::new(r"^[a-z0-9][a-z0-9\-]{0,38}$").unwrap();
+    static ref TELEGRAM_HANDLE: Regex = Regex::new(r"^[a-z][a-z0-9_]{4,31}$").unwrap();
+    static ref EMAIL_ADDRESS: Regex = Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap();
+    static ref FARCASTER_FNAME: Regex = Regex::new(r"^[a-z0-9][a-z0-9\-]{0,15}$").unwrap();
+}
+
+/// TLDs a name must end with, by platform.
+/// ENS also accepts imported DNS names, so any TLD is fine there.
+const DOMAIN_SUFFIXES: &[(Platform, &[&str])] = &[
+    (Platform::Dotbit, &[".bit"]),
+    (Platform::SNS, &[".sol"]),
+    (Platform::Lens, &[".lens"]),
+    (Platform::Crossbell, &[".csb"]),
+    (Platform::Genome, &[".gno"]),
+    (Platform::SpaceId, &[".bnb", ".arb"]),
+    (Platform::Basenames, &[".base.eth"]),
+];
+
+/// Characters never seen in a domain label.
+const DOMAIN_FORBIDDEN_CHARS: &[char] =
+    &['/', '\\', ':', '@', '?', '#', '%', '&', '=', '"', '<', '>'];
+
+/// Check if `identity` is well-formed on `platform`.
+/// `identity` should be in canonical form already, see `normalize`.
+pub fn validate(platform: Platform, identity: &str) -> Result<(), Error> {
+    if identity.is_empty() {
+        return Err(Error::ParamMissing("identity".to_string()));
+    }
+    let valid = match platform {
+        Platform::Ethereum | Platform::BNBSmartChain | Platform::Polygon => {
+            EVM_ADDRESS.is_match(identity)
+        }
+        Platform::Bitcoin => is_bitcoin_address(identity),
+        Platform::Solana => is_solana_pubkey(identity),
+        Platform::Tron => is_tron_address(identity),
+        Platform::Doge => is_doge_address(identity),
+        Platform::Cosmos => is_cosmos_address(identity),
+        Platform::Aptos => APTOS_ADDRESS.is_match(identity),
+        Platform::Near => is_near_account(identity),
+        Platform::Stacks => STACKS_ADDRESS.is_match(identity),
+        Platform::Twitter => TWITTER_HANDLE.is_match(identity),
+        Platform::Github => GITHUB_HANDLE.is_match(identity),
+        Platform::Telegram => TELEGRAM_HANDLE.is_match(identity),
+        Platform::Email => EMAIL_ADDRESS.is_match(identity),
+        // fname, or an ENS name.
+        Platform::Farcaster => {
+            FARCASTER_FNAME.is_match(identity)
+                || (identity.ends_with(".eth") && is_domain(identity))
+        }
+        Platform::ENS => is_domain(identity) && ens::is_normalized(identity),
+        Platform::Basenames => is_domain_of(platform, identity) && ens::is_normalized(identity),
+        Platform::Dotbit
+        | Platform::SNS
+        | Platform::Lens
+        | Platform::Crossbell
+        | Platform::Genome
+        | Platform::SpaceId
+        | Platform::UnstoppableDomains => is_domain_of(platform, identity),
+        // Usernames and keys we have no strict rule for.
+        _ => true,
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(Error::ParamError(format!(
+            "{} is not a valid {} identity",
+            identity, platform
+        ))),
+    }
+}
+
+/// Check if contract `address` is well-formed on `chain`.
+/// `address` should be in canonical form already, see `normalize_address`.
+pub fn validate_address(chain: Chain, address: &str) -> Result<(), Error> {
+    if address.is_empty() {
+        return Err(Error::ParamMissing("address".to_string()));
+    }
+    let valid = match chain {
+        Chain::Bitcoin => is_bitcoin_address(address),
+        Chain::Solana => is_solana_pubkey(address),
+        Chain::Tron => is_tron_address(address),
+        Chain::Doge => is_doge_address(address),
+        Chain::Cosmos => is_cosmos_address(address),
+        Chain::Aptos => APTOS_ADDRESS.is_match(address),
+        Chain::Near => is_near_account(address),
+        Chain::Stacks => STACKS_ADDRESS.is_match(address),
+        // Not hex addresses, and no strict rule for them yet.
+        Chain::Conflux | Chain::Arweave | Chain::Ton | Chain::CKB | Chain::Unknown => true,
+        // EVM-compatible
+        _ => EVM_ADDRESS.is_match(address),
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(Error::ParamError(format!(
+            "{} is not a valid contract address on {}",
+            address, chain
+        ))),
+    }
+}
+
+fn is_domain(name: &str) -> bool {
+    name.contains('.')
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && !label.chars().any(|c| {
+                    c.is_whitespace() || c.is_control() || DOMAIN_FORBIDDEN_CHARS.contains(&c)
+                })
+        })
+}
+
+fn is_domain_of(platform: Platform, name: &str) -> bool {
+    let suffix_matched = match DOMAIN_SUFFIXES.iter().find(|(p, _)| *p == platform) {
+        Some((_, suffixes)) => suffixes.iter().any(|suffix| name.ends_with(suffix)),
+        None => true,
+    };
+    suffix_matched && is_domain(name)
+}
+
+fn is_bitcoin_address(address: &str) -> bool {
+    if let Some((hrp, data, checksum)) = bech32_decode(address) {
+        // Witness v0 uses bech32, v1+ (taproot) uses bech32m.
+        let expected = match data.first() {
+            Some(0) => BECH32_CONST,
+            Some(version) if *version <= 16 => BECH32M_CONST,
+            _ => return false,
+        };
+        return (hrp == "bc" || hrp == "tb") && checksum == expected;
+    }
+    // P2PKH or P2SH
+    base58check(address)
+        .is_some_and(|payload| payload.len() == 21 && (payload[0] == 0x00 || payload[0] == 0x05))
+}
+
+fn is_tron_address(address: &str) -> bool {
+    base58check(address).is_some_and(|payload| payload.len() == 21 && payload[0] == 0x41)
+}
+
+fn is_doge_address(address: &str) -> bool {
+    base58check(address)
+        .is_some_and(|payload| payload.len() == 21 && (payload[0] == 0x1e || payload[0] == 0x16))
+}
+
+/// Ed25519 public key in base58.
+fn is_solana_pubkey(address: &str) -> bool {
+    bs58::decode(address)
+        .into_vec()
+        .is_ok_and(|bytes| bytes.len() == 32)
+}
+
+/// bech32 with 20 (account) or 32 (module / contract) bytes of data.
+fn is_cosmos_address(address: &str) -> bool {
+    match bech32_decode(address) {
+        Some((hrp, data, checksum)) => {
+            hrp.chars().all(|c| c.is_ascii_lowercase())
+                && checksum == BECH32_CONST
+                && (data.len() == 32 || data.len() == 52)
+        }
+        None => false,
+    }
+}
+
+fn is_near_account(account: &str) -> bool {
+    NEAR_IMPLICIT_ACCOUNT.is_match(account)
+        || ((2..=64).contains(&account.len()) && NEAR_NAMED_ACCOUNT.is_match(account))
+}
@@ -0,0 +1,132 @@
+use super::*;
+
+#[test]
+fn test_validate_addresses() {
+    assert!(validate(
+        Platform::Ethereum,
+        "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
+    )
+    .is_ok());
+    assert!(validate(
+        Platform::Ethereum,
+        "0xd8da6bf26964af9d7eed9e03e53415d37aa9604"
+    )
+    .is_err());
+    assert!(validate(Platform::Ethereum, "vitalik.eth").is_err());
+
+    assert!(validate(Platform::Bitcoin, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2").is_ok());
+    assert!(validate(Platform::Bitcoin, "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy").is_ok());
+    assert!(validate(Platform::Bitcoin, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3").is_err());
+    assert!(validate(
+        Platform::Bitcoin,
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+    )
+    .is_ok());
+    assert!(validate(
+        Platform::Bitcoin,
+        "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297"
+    )
+    .is_ok());
+    assert!(validate(
+        Platform::Bitcoin,
+        "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdd"
+    )
+    .is_err());
+
+    assert!(validate(
+        Platform::Solana,
+        "HKKp49qGWXd639QsuH7JiLijfVW5UtCVY4s1n2HANwEA"
+    )
+    .is_ok());
+    assert!(validate(
+        Platform::Solana,
+        "HKKp49qGWXd639QsuH7JiLijfVW5UtCVY4s1n2HANwE0"
+    )
+    .is_err());
+
+    assert!(validate(Platform::Tron, "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7").is_ok());
+    assert!(validate(Platform::Tron, "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU8").is_err());
+
+    assert!(validate(Platform::Doge, "DH5yaieqoZN36fDVciNyRueRGvGLR3mr7L").is_ok());
+
+    assert!(validate(
+        Platform::Cosmos,
+        "cosmos1fl48vsnmsdzcv85q5d2q4z5ajdha8yu34mf0eh"
+    )
+    .is_ok());
+    assert!(validate(
+        Platform::Cosmos,
+        "cosmos1fl48vsnmsdzcv85q5d2q4z5ajdha8yu34mf0ex"
+    )
+    .is_err());
+
+    assert!(validate(Platform::Aptos, "0xabcdef01").is_ok());
+    assert!(validate(Platform::Aptos, "0xabcdefg1").is_err());
+    assert!(validate(Platform::Near, "alice.near").is_ok());
+    assert!(validate(Platform::Near, "alice..near").is_err());
+    assert!(validate(
+        Platform::Stacks,
+        "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"
+    )
+    .is_ok());
+    assert!(validate(
+        Platform::Stacks,
+        "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJO"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_validate_names() {
+    assert!(validate(Platform::ENS, "vitalik.eth").is_ok());
+    assert!(validate(Platform::ENS, "nick.xyz").is_ok());
+    assert!(validate(Platform::ENS, "vitalik..eth").is_err());
+    assert!(validate(Platform::ENS, "vitalik").is_err());
+    assert!(validate(Platform::ENS, "te_st.eth").is_err());
+    assert!(validate(Platform::Basenames, "jesse.base.eth").is_ok());
+    assert!(validate(Platform::Basenames, "Jesse.base.eth").is_err());
+    assert!(validate(Platform::Basenames, "jesse.eth").is_err());
+    assert!(validate(Platform::Dotbit, "threebody.bit").is_ok());
+    assert!(validate(Platform::Dotbit, "threebody.eth").is_err());
+    assert!(validate(Platform::SpaceId, "bnb.arb").is_ok());
+    assert!(validate(Platform::Lens, "stani.lens").is_ok());
+    assert!(validate(Platform::UnstoppableDomains, "brad crypto.x").is_err());
+
+    assert!(validate(Platform::Twitter, "suji_yan").is_ok());
+    assert!(validate(Platform::Twitter, "a_very_long_twitter_handle").is_err());
+    assert!(validate(Platform::Farcaster, "dwr.eth").is_ok());
+    assert!(validate(Platform::Farcaster, "dwr").is_ok());
+    assert!(validate(Platform::Github, "-leading-dash").is_err());
+    assert!(validate(Platform::Telegram, "durov").is_ok());
+    assert!(validate(Platform::Telegram, "abc").is_err());
+    assert!(validate(Platform::Email, "nick@ens.domains").is_ok());
+    assert!(validate(Platform::Email, "nick.ens.domains").is_err());
+}
+
+#[test]
+fn test_validate_empty() {
+    assert!(matches!(
+        validate(Platform::Twitter, ""),
+        Err(Error::ParamMissing(_))
+    ));
+    assert!(matches!(
+        validate(Platform::Ethereum, "0x"),
+        Err(Error::ParamError(_))
+    ));
+}
+
+#[test]
+fn test_validate_contract_address() {
+    assert!(validate_address(
+        Chain::Ethereum,
+        "0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85"
+    )
+    .is_ok());
+    assert!(validate_address(Chain::Polygon, "0x57f1887a").is_err());
+    assert!(validate_address(
+        Chain::Solana,
+        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+    )
+    .is_ok());
+    assert!(validate_address(Chain::Arweave, "anything").is_ok());
+}
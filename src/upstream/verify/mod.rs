@@ -0,0 +1,193 @@
+//! Forward verification of reverse records (primary names).
+//! Anyone can set any name as the primary name of their address, so a reverse record
+//! only counts if the name also resolves to that address.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    error::Error,
+    tigergraph::{vertex::Identity, EdgeList, EdgeWrapperEnum},
+    upstream::{
+        basenames::Basenames, clusters::Clusters, crossbell::Crossbell, dotbit::DotBit,
+        ens_rpc::ENSRpc, genome::Genome, lensv2::LensV2, normalize, solana::Solana,
+        space_id::SpaceId, the_graph::TheGraph, unstoppable::UnstoppableDomains, Platform,
+    },
+};
+use futures::StreamExt;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn};
+
+/// `(platform, identity)` in canonical form.
+type Key = (Platform, String);
+
+/// `(name, address)` of a resolve or reverse resolve record.
+type Record = (Key, Key);
+
+fn key(identity: &Identity) -> Key {
+    (
+        identity.platform,
+        normalize(identity.platform, &identity.identity),
+    )
+}
+
+/// Name resolving to an address, if `edge` is a `Resolve` record.
+fn forward_record(edge: &EdgeWrapperEnum) -> Option<Record> {
+    match edge {
+        EdgeWrapperEnum::Resolve(wrapper) => Some((key(&wrapper.source), key(&wrapper.target))),
+        _ => None,
+    }
+}
+
+/// Primary name claimed by an address, if `edge` is a reverse resolve record.
+fn reverse_record(edge: &EdgeWrapperEnum) -> Option<Record> {
+    match edge {
+        EdgeWrapperEnum::ReverseResolve(wrapper) => {
+            Some((key(&wrapper.target), key(&wrapper.source)))
+        }
+        EdgeWrapperEnum::ReverseResolveContract(wrapper) => {
+            let platform = Platform::from(wrapper.edge.system);
+            let name = normalize(platform, &wrapper.edge.name);
+            Some(((platform, name), key(&wrapper.source)))
+        }
+        _ => None,
+    }
+}
+
+fn forward_records(edges: &EdgeList) -> HashSet<Record> {
+    edges.iter().filter_map(forward_record).collect()
+}
+
+fn reverse_records(edges: &EdgeList) -> HashSet<Record> {
+    edges.iter().filter_map(reverse_record).collect()
+}
+
+/// Drop reverse records in `edges` whose name does not resolve to the address claiming it,
+/// neither by a `Resolve` edge in `edges` nor by a forward lookup in the upstream of its
+/// name system. Records are only dropped on a successful lookup: if it fails, they are kept
+/// unverified.
+pub async fn verify_reverse(edges: EdgeList) -> EdgeList {
+    const CONCURRENT: usize = 5;
+    let forward = forward_records(&edges);
+    let claims = reverse_records(&edges);
+    let unresolved: HashSet<Record> = claims.difference(&forward).cloned().collect();
+    let names: HashSet<Key> = unresolved.iter().map(|(name, _)| name.clone()).collect();
+    let resolved: HashMap<Key, Vec<Key>> = futures::stream::iter(names)
+        .map(|name| async move {
+            let addresses = resolve_forward(&name).await;
+            addresses.map(|addresses| (name, addresses))
+        })
+        .buffer_unordered(CONCURRENT)
+        .filter_map(|resolved| async move { resolved })
+        .collect()
+        .await;
+
+    let rejected = reject_unresolved(unresolved, &resolved);
+    if !rejected.is_empty() {
+        info!(
+            ?rejected,
+            "Reverse records not resolving back to their address"
+        );
+    }
+    reject_reverse(edges, &claims, &rejected)
+}
+
+/// Records in `unresolved` whose name was looked up (`resolved`), but does not resolve to
+/// the address claiming it.
+fn reject_unresolved(
+    unresolved: HashSet<Record>,
+    resolved: &HashMap<Key, Vec<Key>>,
+) -> HashSet<Record> {
+    unresolved
+        .into_iter()
+        .filter(|(name, address)| {
+            resolved
+                .get(name)
+                .is_some_and(|addresses| !addresses.contains(address))
+        })
+        .collect()
+}
+
+/// Addresses `name` resolves to, looked up in the upstream of its name system
+/// without fetching anything else. Empty if it is not registered.
+/// `None` if the lookup failed, or the system has no reverse records.
+async fn resolve_forward((platform, name): &Key) -> Option<Vec<Key>> {
+    let result = match platform {
+        Platform::ENS if ENSRpc::enabled() => ENSRpc::resolve_forward(name).await,
+        Platform::ENS => TheGraph::resolve_forward(name).await,
+        Platform::Dotbit => DotBit::resolve_forward(name).await,
+        Platform::UnstoppableDomains => UnstoppableDomains::resolve_forward(name).await,
+        Platform::SpaceId => SpaceId::resolve_forward(name).await,
+        Platform::Genome => Genome::resolve_forward(name).await,
+        Platform::SNS => Solana::resolve_forward(name).await,
+        Platform::Crossbell => Crossbell::resolve_forward(name).await,
+        Platform::Lens => LensV2::resolve_forward(name).await,
+        Platform::Clusters => Clusters::resolve_forward(name).await,
+        Platform::Basenames => Basenames::resolve_forward(name).await,
+        _ => return None,
+    };
+    match result {
+        Ok(addresses) => Some(
+            addresses
+                .into_iter()
+                .map(|(platform, address)| (platform, normalize(platform, &address)))
+                .collect(),
+        ),
+        // Not registered
+        Err(Error::NoResult) => Some(vec![]),
+        Err(err) => {
+            warn!(%platform, name, %err, "Fail to resolve name of reverse record, left unverified");
+            None
+        }
+    }
+}
+
+/// Drop `rejected` reverse records (out of all `claims`) from `edges`.
+/// Identities which are primary only by rejected records are no longer marked `reverse`,
+/// and addresses are no longer displayed by the rejected name.
+fn reject_reverse(
+    edges: EdgeList,
+    claims: &HashSet<Record>,
+    rejected: &HashSet<Record>,
+) -> EdgeList {
+    if rejected.is_empty() {
+        return edges;
+    }
+    let verified: HashSet<&Key> = claims
+        .difference(rejected)
+        .flat_map(|(name, address)| [name, address])
+        .collect();
+    let unset: HashSet<&Key> = rejected
+        .iter()
+        .flat_map(|(name, address)| [name, address])
+        .filter(|key| !verified.contains(key))
+        .collect();
+    let spoofed_names: HashSet<(&Key, &String)> = rejected
+        .iter()
+        .map(|((_, name), address)| (address, name))
+        .collect();
+
+    edges
+        .into_iter()
+        .filter(|edge| match reverse_record(edge) {
+            Some(record) => !rejected.contains(&record),
+            None => true,
+        })
+        .map(|mut edge| {
+            for identity in edge.identities_mut() {
+                let identity_key = key(identity);
+                if unset.contains(&identity_key) {
+                    identity.reverse = Some(false);
+                }
+                let spoofed = identity
+                    .display_name
+                    .as_ref()
+                    .is_some_and(|name| spoofed_names.contains(&(&identity_key, name)));
+                if spoofed {
+                    identity.display_name = Some("".into());
+                }
+            }
+            edge
+        })
+        .collect()
+}
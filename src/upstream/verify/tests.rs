@@ -0,0 +1,176 @@
+use super::*;
+use crate::{
+    tigergraph::{
+        edge::{
+            HyperEdge, Resolve, Wrapper, HYPER_EDGE, RESOLVE, REVERSE_RESOLVE,
+            REVERSE_RESOLVE_CONTRACT,
+        },
+        vertex::{Contract, IdentitiesGraph},
+    },
+    upstream::{Chain, ContractCategory, DataFetcher, DataSource, DomainNameSystem},
+    util::naive_now,
+};
+use uuid::Uuid;
+
+const ADDRESS: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+
+fn identity(platform: Platform, identity: &str) -> Identity {
+    Identity {
+        uuid: Some(Uuid::new_v4()),
+        platform,
+        identity: identity.to_string(),
+        display_name: Some(identity.to_string()),
+        reverse: Some(true),
+        ..Default::default()
+    }
+}
+
+fn resolve(system: DomainNameSystem, name: &str) -> Resolve {
+    Resolve {
+        uuid: Uuid::new_v4(),
+        source: DataSource::TheGraph,
+        system,
+        name: name.to_string(),
+        fetcher: DataFetcher::RelationService,
+        updated_at: naive_now(),
+    }
+}
+
+fn hyper_edge(identity: &Identity) -> EdgeWrapperEnum {
+    EdgeWrapperEnum::new_hyper_edge(HyperEdge {}.wrapper(
+        &IdentitiesGraph::default(),
+        identity,
+        HYPER_EDGE,
+    ))
+}
+
+fn reverse_edge(
+    address: &Identity,
+    domain: &Identity,
+    system: DomainNameSystem,
+) -> EdgeWrapperEnum {
+    let record = resolve(system, &domain.identity);
+    EdgeWrapperEnum::new_reverse_resolve(record.wrapper(address, domain, REVERSE_RESOLVE))
+}
+
+fn resolve_edge(
+    domain: &Identity,
+    address: &Identity,
+    system: DomainNameSystem,
+) -> EdgeWrapperEnum {
+    let record = resolve(system, &domain.identity);
+    EdgeWrapperEnum::new_resolve(record.wrapper(domain, address, RESOLVE))
+}
+
+fn ethereum_key(address: &str) -> Key {
+    (Platform::Ethereum, address.to_string())
+}
+
+#[test]
+fn test_reverse_records() {
+    let address = identity(
+        Platform::Ethereum,
+        &ADDRESS.to_uppercase().replace("0X", "0x"),
+    );
+    let domain = identity(Platform::ENS, "vitalik.eth");
+    let contract = Contract {
+        uuid: Uuid::new_v4(),
+        category: ContractCategory::ENS,
+        address: ContractCategory::ENS.default_contract_address().unwrap(),
+        chain: Chain::Ethereum,
+        symbol: None,
+        updated_at: naive_now(),
+    };
+    let edges = vec![
+        hyper_edge(&address),
+        reverse_edge(&address, &domain, DomainNameSystem::ENS),
+        EdgeWrapperEnum::new_reverse_resolve_contract(
+            resolve(DomainNameSystem::ENS, "Vitalik.eth").wrapper(
+                &address,
+                &contract,
+                REVERSE_RESOLVE_CONTRACT,
+            ),
+        ),
+        resolve_edge(&domain, &address, DomainNameSystem::ENS),
+    ];
+
+    // Both reverse records claim the same name, in canonical form.
+    let record = (
+        (Platform::ENS, "vitalik.eth".to_string()),
+        ethereum_key(ADDRESS),
+    );
+    assert_eq!(reverse_records(&edges), HashSet::from([record.clone()]));
+    assert_eq!(forward_records(&edges), HashSet::from([record]));
+}
+
+#[test]
+fn test_reject_reverse() {
+    let mut address = identity(Platform::Ethereum, ADDRESS);
+    address.display_name = Some("spoofed.bit".into());
+    let ens = identity(Platform::ENS, "vitalik.eth");
+    let spoofed = identity(Platform::Dotbit, "spoofed.bit");
+    let edges = vec![
+        hyper_edge(&address),
+        hyper_edge(&spoofed),
+        reverse_edge(&address, &ens, DomainNameSystem::ENS),
+        resolve_edge(&ens, &address, DomainNameSystem::ENS),
+        reverse_edge(&address, &spoofed, DomainNameSystem::DotBit),
+    ];
+    let claims = reverse_records(&edges);
+    let rejected: HashSet<Record> = claims
+        .difference(&forward_records(&edges))
+        .cloned()
+        .collect();
+    let spoofed_record = (
+        (Platform::Dotbit, "spoofed.bit".to_string()),
+        ethereum_key(ADDRESS),
+    );
+    assert_eq!(rejected, HashSet::from([spoofed_record]));
+
+    let mut edges = reject_reverse(edges, &claims, &rejected);
+    assert_eq!(edges.len(), 4);
+    assert_eq!(reverse_records(&edges).len(), 1);
+    for identity in edges.iter_mut().flat_map(|edge| edge.identities_mut()) {
+        match identity.platform {
+            // Still primary by its verified ENS name, but not displayed by the spoofed one.
+            Platform::Ethereum => {
+                assert_eq!(identity.reverse, Some(true));
+                assert_eq!(identity.display_name, Some("".into()));
+            }
+            Platform::Dotbit => assert_eq!(identity.reverse, Some(false)),
+            _ => assert_eq!(identity.reverse, Some(true)),
+        }
+    }
+
+    // Spoofed name only: the address is not primary by any name.
+    let address = identity(Platform::Ethereum, ADDRESS);
+    let edges = vec![
+        hyper_edge(&address),
+        reverse_edge(&address, &spoofed, DomainNameSystem::DotBit),
+    ];
+    let claims = reverse_records(&edges);
+    let mut edges = reject_reverse(edges, &claims, &claims);
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].identities_mut()[0].reverse, Some(false));
+}
+
+#[test]
+fn test_reject_unresolved() {
+    let record = |name: &str| ((Platform::Dotbit, name.to_string()), ethereum_key(ADDRESS));
+    let unresolved = HashSet::from([
+        record("resolved.bit"),
+        record("spoofed.bit"),
+        record("unregistered.bit"),
+        record("failed.bit"),
+    ]);
+    let resolved = HashMap::from([
+        (record("resolved.bit").0, vec![ethereum_key(ADDRESS)]),
+        (record("spoofed.bit").0, vec![ethereum_key("0x01")]),
+        (record("unregistered.bit").0, vec![]),
+        // Lookup of failed.bit failed: not in `resolved`.
+    ]);
+    assert_eq!(
+        reject_unresolved(unresolved, &resolved),
+        HashSet::from([record("spoofed.bit"), record("unregistered.bit")])
+    );
+}
@@ -0,0 +1,112 @@
+//! Background tasks spawned while serving a request, e.g. refetching outdated records.
+//!
+//! A long-running server just lets them run. A Lambda invocation is frozen as soon as
+//! its response is returned, so it may `wait_background_tasks` briefly before returning;
+//! unfinished tasks resume when the execution environment is reused, and are lost if it is not.
+//! Tasks spawned there must leave the database consistent wherever they are frozen.
+
+use crate::error::Error;
+use std::{
+    future::Future,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+use tokio::sync::Notify;
+use tracing::warn;
+
+/// How long to wait before refreshing an outdated record in a long-running server.
+const REFRESH_DELAY: Duration = Duration::from_secs(10);
+
+static SHORT_LIVED: AtomicBool = AtomicBool::new(false);
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// Notified when the last running background task finishes.
+    static ref ALL_DONE: Notify = Notify::new();
+}
+
+/// Mark this process as short-lived (i.e. a Lambda invocation), see `spawn_refresh`.
+pub fn set_short_lived(short_lived: bool) {
+    SHORT_LIVED.store(short_lived, Ordering::Relaxed);
+}
+
+/// If this process is frozen once its response is returned.
+pub fn is_short_lived() -> bool {
+    SHORT_LIVED.load(Ordering::Relaxed)
+}
+
+/// Refresh an outdated record in background: `delete` it after `REFRESH_DELAY`, then `refetch` it.
+/// A short-lived process may be frozen in between, leaving the record missing, so there it is
+/// only refetched right away, upserted over the outdated one. Connections gone upstream are
+/// kept until a long-running server refreshes it.
+pub fn spawn_refresh<D, R>(delete: D, refetch: R)
+where
+    D: Future<Output = Result<(), Error>> + Send + 'static,
+    R: Future<Output = Result<(), Error>> + Send + 'static,
+{
+    if is_short_lived() {
+        spawn_background(refetch);
+    } else {
+        spawn_background(async move {
+            tokio::time::sleep(REFRESH_DELAY).await;
+            delete.await?;
+            refetch.await
+        });
+    }
+}
+
+/// Spawn `task` in background. Errors are logged, since there is no one to return them to.
+pub fn spawn_background<F>(task: F)
+where
+    F: Future<Output = Result<(), Error>> + Send + 'static,
+{
+    RUNNING.fetch_add(1, Ordering::SeqCst);
+    let guard = RunningGuard;
+    tokio::spawn(async move {
+        let _guard = guard;
+        if let Err(err) = task.await {
+            warn!(err = err.to_string(), "Background task failed");
+        }
+    });
+}
+
+/// Counts a task as finished when dropped, even if the task panicked.
+struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        if RUNNING.fetch_sub(1, Ordering::SeqCst) == 1 {
+            ALL_DONE.notify_waiters();
+        }
+    }
+}
+
+/// Amount of background tasks still running.
+pub fn background_tasks() -> usize {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Wait until all background tasks (incl. the ones spawned by them) are done.
+/// Returns `false` if `timeout` is reached; unfinished tasks keep running.
+pub async fn wait_background_tasks(timeout: Duration) -> bool {
+    let wait_all = async {
+        loop {
+            // Register before checking, so a notification in between won't be missed.
+            let all_done = ALL_DONE.notified();
+            if background_tasks() == 0 {
+                break;
+            }
+            all_done.await;
+        }
+    };
+    match tokio::time::timeout(timeout, wait_all).await {
+        Ok(()) => true,
+        Err(_) => {
+            warn!(
+                remaining = background_tasks(),
+                "Timeout waiting for background tasks"
+            );
+            false
+        }
+    }
+}
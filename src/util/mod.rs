@@ -1,3 +1,4 @@
+pub mod background;
 #[cfg(test)]
 mod tests;
 
@@ -6,7 +7,11 @@ use std::{collections::HashSet, hash::Hash};
 use crate::error::Error;
 use chrono::{DateTime, NaiveDateTime};
 use http::Response;
-use hyper::{body::HttpBody as _, client::HttpConnector, Body, Client, Request};
+use hyper::{
+    body::HttpBody as _,
+    client::{connect::Connect, HttpConnector},
+    Body, Client, Request,
+};
 use hyper_tls::HttpsConnector;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -75,11 +80,14 @@ pub fn make_http_client() -> Client<HttpConnector> {
 }
 
 /// If timeout is None, default timeout is 5 seconds.
-pub async fn request_with_timeout(
-    client: &Client<HttpsConnector<HttpConnector>>,
+pub async fn request_with_timeout<C>(
+    client: &Client<C>,
     req: Request<Body>,
     timeout: Option<std::time::Duration>,
-) -> Result<Response<Body>, Error> {
+) -> Result<Response<Body>, Error>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
     match tokio::time::timeout(timeout.unwrap_or(DEFAULT_TIMEOUT), client.request(req)).await {
         Ok(resp) => match resp {
             Ok(resp) => Ok(resp),
@@ -1,4 +1,10 @@
 use super::*;
+use background::{set_short_lived, spawn_refresh, wait_background_tasks};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::time::Duration;
 
 #[test]
 fn test_timestamp_to_naive_success() {
@@ -8,3 +14,28 @@ fn test_timestamp_to_naive_success() {
         "2023-05-31 08:34:51".to_string()
     );
 }
+
+#[tokio::test]
+async fn test_refresh_survives_freeze() {
+    set_short_lived(true);
+    let record = Arc::new(Mutex::new(Some("outdated")));
+    let refetching = Arc::new(AtomicBool::new(false));
+
+    let deleted = record.clone();
+    let started = refetching.clone();
+    spawn_refresh(
+        async move {
+            *deleted.lock().unwrap() = None;
+            Ok(())
+        },
+        async move {
+            started.store(true, Ordering::SeqCst);
+            // Frozen partway through refetching, and never resumed.
+            std::future::pending::<()>().await;
+            Ok(())
+        },
+    );
+    assert!(!wait_background_tasks(Duration::from_millis(50)).await);
+    assert!(refetching.load(Ordering::SeqCst));
+    assert_eq!(*record.lock().unwrap(), Some("outdated"));
+}